@@ -1,4 +1,9 @@
-use blit::{geom::Size, Blit, BlitOptions, ToBlitBuffer};
+use blit::{
+    batch::BlitBatch,
+    geom::{Coordinate, Size},
+    rle::RleBlitBuffer,
+    Blit, BlitBuffer, BlitOptions, ToBlitBuffer,
+};
 use codspeed_criterion_compat::{
     black_box, criterion_group, criterion_main, BenchmarkId, Criterion,
 };
@@ -6,9 +11,8 @@ use codspeed_criterion_compat::{
 const SIZE: usize = 300;
 
 fn criterion_benchmark(c: &mut Criterion) {
-    let rgb = image::load_from_memory(include_bytes!("../examples/showcase/smiley_rgb.png"))
-        .unwrap()
-        .into_rgb8();
+    let rgb =
+        image::load_from_memory(include_bytes!("../examples/showcase/smiley_rgb.png")).unwrap();
 
     let blit = rgb.to_blit_buffer_with_mask_color(0xFF_00_FF);
     let size = blit.size();
@@ -64,6 +68,84 @@ fn criterion_benchmark(c: &mut Criterion) {
     }
     group.finish();
 
+    let mut group = c.benchmark_group("blit many positions");
+    {
+        let particle = BlitBuffer::from_buffer(&[0xFF_FF_FF_FF; 8 * 8], 8, 127);
+        let positions = (0..2000)
+            .map(|i| Coordinate::new((i * 7) % SIZE as i32 - 4, (i * 13) % SIZE as i32 - 4))
+            .collect::<Vec<_>>();
+
+        group.bench_function("loop", |b| {
+            let mut buffer: Vec<u32> = vec![0; SIZE * SIZE];
+
+            b.iter(|| {
+                for position in &positions {
+                    particle.blit(
+                        &mut buffer,
+                        black_box(Size::new(SIZE, SIZE)),
+                        black_box(&BlitOptions::new_position(position.x, position.y)),
+                    );
+                }
+            });
+        });
+
+        group.bench_function("positions", |b| {
+            let mut buffer: Vec<u32> = vec![0; SIZE * SIZE];
+
+            b.iter(|| {
+                particle.blit_positions(
+                    &mut buffer,
+                    black_box(Size::new(SIZE, SIZE)),
+                    black_box(&BlitOptions::new()),
+                    black_box(&positions),
+                );
+            });
+        });
+
+        group.bench_function("batch", |b| {
+            let mut buffer: Vec<u32> = vec![0; SIZE * SIZE];
+            let mut batch = BlitBatch::new();
+            for position in &positions {
+                batch.push(&particle, BlitOptions::new_position(position.x, position.y));
+            }
+
+            b.iter(|| {
+                black_box(&batch).execute(&mut buffer, black_box(Size::new(SIZE, SIZE)));
+            });
+        });
+    }
+    group.finish();
+
+    let mut group = c.benchmark_group("blit run-length encoded");
+    {
+        let rle = RleBlitBuffer::from(blit.clone());
+
+        group.bench_function("buffer", |b| {
+            let mut buffer: Vec<u32> = vec![0; SIZE * SIZE];
+
+            b.iter(|| {
+                blit.blit(
+                    &mut buffer,
+                    black_box(Size::new(SIZE, SIZE)),
+                    black_box(&BlitOptions::new_position(10, 10)),
+                )
+            });
+        });
+
+        group.bench_function("rle", |b| {
+            let mut buffer: Vec<u32> = vec![0; SIZE * SIZE];
+
+            b.iter(|| {
+                rle.blit(
+                    &mut buffer,
+                    black_box(Size::new(SIZE, SIZE)),
+                    black_box(&BlitOptions::new_position(10, 10)),
+                )
+            });
+        });
+    }
+    group.finish();
+
     c.bench_function("blit exact fit", |b| {
         let mut buffer: Vec<u32> = vec![0; size.pixels()];
 
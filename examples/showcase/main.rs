@@ -1,4 +1,7 @@
-use blit::{geom::Size, geom::SubRect, slice::Slice, Blit, BlitBuffer, BlitOptions, ToBlitBuffer};
+use blit::{
+    font::BitmapFont, geom::Size, geom::SubRect, slice::Slice, Anchor, Blit, BlitBuffer,
+    BlitOptions, ToBlitBuffer,
+};
 
 use num_traits::ToPrimitive;
 use pixel_game_lib::{
@@ -26,7 +29,7 @@ fn frame0(
     dst: &mut [u32],
     _buf: &BlitBuffer,
     _scalable_buf: &BlitBuffer,
-    font: &BlitBuffer,
+    font: &BitmapFont,
     _mouse: Vec2<i32>,
 ) {
     draw_text(dst, font, 0, "This is an interactive showcase of the\n'blit' crate, you can interact with the\nrendering by moving the cursor\n\nGo to the next showcase item by clicking\nthe left mouse button\n\nGo to the previous showcase item by\nclicking the right mouse button");
@@ -37,26 +40,28 @@ fn frame1(
     dst: &mut [u32],
     buf: &BlitBuffer,
     _scalable_buf: &BlitBuffer,
-    font: &BlitBuffer,
+    font: &BitmapFont,
     mouse: Vec2<i32>,
 ) {
-    let (center_x, center_y) = (DST_SIZE / 2 - buf.size() / 2).as_tuple();
-    let (mouse_x, mouse_y) =
-        (mouse - Vec2::new(buf.width() / 2, buf.height() / 2).as_()).into_tuple();
+    let (center_x, center_y) = (DST_SIZE / 2).as_tuple();
 
     buf.blit(
         dst,
         DST_SIZE,
-        &BlitOptions::new_position(center_x, center_y),
+        &BlitOptions::new_position(center_x, center_y).with_anchor(Anchor::Center),
+    );
+    buf.blit(
+        dst,
+        DST_SIZE,
+        &BlitOptions::new_position(mouse.x, mouse.y).with_anchor(Anchor::Center),
     );
-    buf.blit(dst, DST_SIZE, &BlitOptions::new_position(mouse_x, mouse_y));
 
     draw_text(dst, font, 0, "Blit the full sprite");
     draw_text(
         dst,
         font,
-        DST_SIZE.height - CHAR_SIZE.height,
-        "BlitOptions::new(mouse)",
+        DST_SIZE.height - CHAR_SIZE.height * 2,
+        "BlitOptions::new(mouse)\n\t.with_anchor(Anchor::Center)",
     );
 }
 
@@ -65,7 +70,7 @@ fn frame2(
     dst: &mut [u32],
     buf: &BlitBuffer,
     _scalable_buf: &BlitBuffer,
-    font: &BlitBuffer,
+    font: &BitmapFont,
     mouse: Vec2<i32>,
 ) {
     let (center_x, center_y) = (DST_SIZE / 2 - buf.size() / 2).as_tuple();
@@ -98,7 +103,7 @@ fn frame3(
     dst: &mut [u32],
     buf: &BlitBuffer,
     _scalable_buf: &BlitBuffer,
-    font: &BlitBuffer,
+    font: &BitmapFont,
     mouse: Vec2<i32>,
 ) {
     let (center_x, center_y) = (DST_SIZE / 2 - buf.size() / 2).as_tuple();
@@ -131,7 +136,7 @@ fn frame4(
     dst: &mut [u32],
     buf: &BlitBuffer,
     _scalable_buf: &BlitBuffer,
-    font: &BlitBuffer,
+    font: &BitmapFont,
     mouse: Vec2<i32>,
 ) {
     let src_size = buf.size() / 2;
@@ -166,7 +171,7 @@ fn frame5(
     dst: &mut [u32],
     buf: &BlitBuffer,
     _scalable_buf: &BlitBuffer,
-    font: &BlitBuffer,
+    font: &BitmapFont,
     mouse: Vec2<i32>,
 ) {
     let (offset_x, offset_y) = (40, 40);
@@ -199,7 +204,7 @@ fn frame6(
     dst: &mut [u32],
     buf: &BlitBuffer,
     _scalable_buf: &BlitBuffer,
-    font: &BlitBuffer,
+    font: &BitmapFont,
     mouse: Vec2<i32>,
 ) {
     let (offset_x, offset_y) = (40, 40);
@@ -234,7 +239,7 @@ fn frame7(
     dst: &mut [u32],
     _buf: &BlitBuffer,
     scalable_buf: &BlitBuffer,
-    font: &BlitBuffer,
+    font: &BitmapFont,
     mouse: Vec2<i32>,
 ) {
     let (offset_x, offset_y) = (60, 60);
@@ -269,7 +274,7 @@ fn frame8(
     dst: &mut [u32],
     buf: &BlitBuffer,
     _scalable_buf: &BlitBuffer,
-    font: &BlitBuffer,
+    font: &BitmapFont,
     mouse: Vec2<i32>,
 ) {
     let (offset_x, offset_y) = (60, 60);
@@ -301,7 +306,7 @@ fn frame9(
     dst: &mut [u32],
     buf: &BlitBuffer,
     _scalable_buf: &BlitBuffer,
-    font: &BlitBuffer,
+    font: &BitmapFont,
     mouse: Vec2<i32>,
 ) {
     let (center_x, center_y) = (DST_SIZE / 2 - buf.size() / 2).as_tuple();
@@ -332,40 +337,8 @@ fn frame9(
 }
 
 /// Draw an ASCII string.
-fn draw_text(dst: &mut [u32], font: &BlitBuffer, y: impl ToPrimitive, text: &str) {
-    // First character in the image
-    let char_start = '!';
-    let char_end = '~';
-
-    let mut x = 0;
-    let mut y = y.to_i32().unwrap_or_default();
-
-    // Draw each character from the string
-    text.chars().for_each(|ch| {
-        // Move the cursor
-        x += CHAR_SIZE.width as i32;
-
-        // Don't draw characters that are not in the picture
-        if ch < char_start || ch > char_end {
-            if ch == '\n' {
-                x = 0;
-                y += CHAR_SIZE.height as i32;
-            } else if ch == '\t' {
-                x += CHAR_SIZE.width as i32 * 3;
-            }
-            return;
-        }
-
-        // The sub rectangle offset of the character is based on the starting character and counted using the ASCII index
-        let char_offset = (ch as u8 - char_start as u8) as u32 * CHAR_SIZE.width;
-
-        // Draw the character
-        font.blit(
-            dst,
-            DST_SIZE,
-            &BlitOptions::new_position(x, y).with_sub_rect(SubRect::new(char_offset, 0, CHAR_SIZE)),
-        );
-    });
+fn draw_text(dst: &mut [u32], font: &BitmapFont, y: impl ToPrimitive, text: &str) {
+    font.draw(dst, DST_SIZE, (0, y.to_i32().unwrap_or_default()), text);
 }
 
 /// Load the images and draw the window.
@@ -373,19 +346,17 @@ fn main() {
     // Load an image with a mask color from disk
     let buf = image::load_from_memory(include_bytes!("./smiley_rgb.png"))
         .unwrap()
-        .into_rgb8()
         .to_blit_buffer_with_mask_color(MASK_COLOR);
 
     // Load the font image with mask color from disk
     let font = image::load_from_memory(include_bytes!("./ArtosSans.png"))
         .unwrap()
-        .into_rgb8()
         .to_blit_buffer_with_mask_color(MASK_COLOR);
+    let font = BitmapFont::new(font, CHAR_SIZE, '!');
 
     // Load a scalable image with a mask color from disk
     let scalable_buf = image::load_from_memory(include_bytes!("./9slice.png"))
         .unwrap()
-        .into_rgba8()
         .to_blit_buffer_with_alpha(127);
 
     // Setup a winit window
@@ -397,7 +368,7 @@ fn main() {
     };
 
     // All frame drawing functions, cycled by clicking
-    type Frames = Vec<fn(&mut [u32], &BlitBuffer, &BlitBuffer, &BlitBuffer, Vec2<i32>)>;
+    type Frames = Vec<fn(&mut [u32], &BlitBuffer, &BlitBuffer, &BitmapFont, Vec2<i32>)>;
     let frames: Frames = vec![
         frame0, frame1, frame2, frame3, frame4, frame5, frame6, frame7, frame8, frame9,
     ];
@@ -405,7 +376,7 @@ fn main() {
     // The game state container everything specified above
     struct State {
         buf: BlitBuffer,
-        font: BlitBuffer,
+        font: BitmapFont,
         scalable_buf: BlitBuffer,
         // Current mouse position
         mouse: Vec2<usize>,
@@ -0,0 +1,339 @@
+//! Named frames packed into a single buffer, loaded from the JSON exported by TexturePacker, Aseprite and similar tools.
+//!
+//! Both layouts of the format are supported, `frames` as an array of objects with a `filename` or as an object keyed by the names.
+//! Frames from the array layout keep the order they are defined in.
+//! Frames from the object layout are ordered by their names with numbers compared by value, so `walk 2` comes before `walk 10`.
+//!
+//! # Example
+//!
+//! ```rust
+//! use blit::{atlas::Atlas, Blit, BlitBuffer, geom::Size};
+//!
+//! let buffer = BlitBuffer::from_buffer(&[0xFF_00_00_01, 0xFF_00_00_02], 2, 127);
+//! let atlas = Atlas::from_json(
+//!     buffer,
+//!     r#"{ "frames": { "right": { "frame": { "x": 1, "y": 0, "w": 1, "h": 1 } } } }"#,
+//! )
+//! .unwrap();
+//!
+//! let mut canvas = [0; 3];
+//! let options = atlas.options("right").unwrap().offset_by((2, 0));
+//! atlas.buffer().blit(&mut canvas, Size::new(3, 1), &options);
+//! assert_eq!(canvas, [0, 0, 0xFF_00_00_02]);
+//! ```
+
+use std::{
+    cmp::Ordering,
+    collections::{BTreeMap, HashMap},
+};
+
+use serde::Deserialize;
+
+use crate::{
+    geom::{Coordinate, SubRect},
+    BlitBuffer, BlitOptions, Size,
+};
+
+/// Single named frame in an [`Atlas`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AtlasFrame {
+    /// Name of the frame, usually the file name of the original image.
+    pub name: String,
+    /// Rectangle of the frame in the buffer, the width and height are swapped when it's rotated.
+    pub rect: SubRect,
+    /// Whether the frame is stored rotated a quarter turn clockwise.
+    pub rotated: bool,
+    /// Position of the frame in the original image when transparent edges were trimmed.
+    pub offset: Coordinate,
+    /// Size of the original image before trimming.
+    pub source_size: Size,
+}
+
+impl AtlasFrame {
+    /// Options drawing the frame at the position where the original image would be drawn at `(0, 0)`.
+    ///
+    /// Rotated frames are rotated back and trimmed frames are offset to the position of the frame in the original image.
+    /// Move it with [`BlitOptions::offset_by`] to draw it somewhere else.
+    pub fn options(&self) -> BlitOptions {
+        let options = BlitOptions::new_position(self.offset.x, self.offset.y);
+        if self.rotated {
+            options.with_rotated_sub_rect(self.rect)
+        } else {
+            options.with_sub_rect(self.rect)
+        }
+    }
+}
+
+/// Buffer containing many frames that can be looked up by name.
+#[derive(Debug, Clone)]
+pub struct Atlas {
+    /// Pixels of all frames.
+    buffer: BlitBuffer,
+    /// Frames in the order of the array layout, or sorted by name for the object layout.
+    frames: Vec<AtlasFrame>,
+    /// Index of every frame by name.
+    names: HashMap<String, usize>,
+}
+
+impl Atlas {
+    /// Read the frames from the JSON describing the buffer.
+    ///
+    /// Fails when the JSON is invalid or doesn't contain a `frames` array or object.
+    pub fn from_json(buffer: BlitBuffer, json: &str) -> Result<Self, serde_json::Error> {
+        let frames = match serde_json::from_str::<Json>(json)?.frames {
+            JsonFrames::Array(frames) => frames
+                .into_iter()
+                .map(|frame| frame.data.into_frame(frame.filename))
+                .collect::<Vec<_>>(),
+            JsonFrames::Hash(frames) => {
+                let mut frames = frames
+                    .into_iter()
+                    .map(|(name, data)| data.into_frame(name))
+                    .collect::<Vec<_>>();
+                frames.sort_by(|a, b| natural_cmp(&a.name, &b.name));
+
+                frames
+            }
+        };
+
+        let names = frames
+            .iter()
+            .enumerate()
+            .map(|(index, frame)| (frame.name.clone(), index))
+            .collect();
+
+        Ok(Self {
+            buffer,
+            frames,
+            names,
+        })
+    }
+
+    /// Pixels of all frames.
+    pub fn buffer(&self) -> &BlitBuffer {
+        &self.buffer
+    }
+
+    /// All frames.
+    ///
+    /// With the array layout the frames are in the order they are defined, with the object layout they are sorted by name with numbers compared by value.
+    pub fn frames(&self) -> &[AtlasFrame] {
+        &self.frames
+    }
+
+    /// Frame with the name.
+    pub fn frame(&self, name: &str) -> Option<&AtlasFrame> {
+        self.names.get(name).map(|index| &self.frames[*index])
+    }
+
+    /// Options for drawing the frame with the name, see [`AtlasFrame::options`].
+    pub fn options(&self, name: &str) -> Option<BlitOptions> {
+        self.frame(name).map(AtlasFrame::options)
+    }
+}
+
+/// Root of the JSON, other fields such as `meta` are ignored.
+#[derive(Deserialize)]
+struct Json {
+    /// Frames in either layout.
+    frames: JsonFrames,
+}
+
+/// Both layouts of the frames.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum JsonFrames {
+    /// List of frames with their name as a field.
+    Array(Vec<JsonArrayFrame>),
+    /// Frames keyed by their name.
+    Hash(BTreeMap<String, JsonFrame>),
+}
+
+/// Frame in the array layout.
+#[derive(Deserialize)]
+struct JsonArrayFrame {
+    /// Name of the frame.
+    filename: String,
+    /// Other fields of the frame.
+    #[serde(flatten)]
+    data: JsonFrame,
+}
+
+/// Fields of a frame.
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct JsonFrame {
+    /// Rectangle in the buffer before rotating.
+    frame: JsonRect,
+    /// Whether the frame is rotated a quarter turn clockwise.
+    #[serde(default)]
+    rotated: bool,
+    /// Rectangle of the trimmed frame in the original image.
+    sprite_source_size: Option<JsonRect>,
+    /// Size of the original image.
+    source_size: Option<JsonSize>,
+}
+
+impl JsonFrame {
+    /// Convert to a named frame.
+    fn into_frame(self, name: String) -> AtlasFrame {
+        let JsonRect { x, y, w, h } = self.frame;
+        // The width and height describe the frame before it was rotated
+        let size = if self.rotated {
+            Size::new(h, w)
+        } else {
+            Size::new(w, h)
+        };
+
+        AtlasFrame {
+            name,
+            rect: SubRect::new(x, y, size),
+            rotated: self.rotated,
+            offset: self
+                .sprite_source_size
+                .map_or_else(Coordinate::default, |rect| Coordinate::new(rect.x, rect.y)),
+            source_size: self
+                .source_size
+                .map_or(Size::new(w, h), |size| Size::new(size.w, size.h)),
+        }
+    }
+}
+
+/// Rectangle in the JSON.
+#[derive(Deserialize)]
+struct JsonRect {
+    x: i32,
+    y: i32,
+    w: u32,
+    h: u32,
+}
+
+/// Size in the JSON.
+#[derive(Deserialize)]
+struct JsonSize {
+    w: u32,
+    h: u32,
+}
+
+/// Compare names with the digits in them compared by value.
+fn natural_cmp(a: &str, b: &str) -> Ordering {
+    let (mut a, mut b) = (a, b);
+    loop {
+        let (Some(a_char), Some(b_char)) = (a.chars().next(), b.chars().next()) else {
+            return a.len().cmp(&b.len());
+        };
+
+        if a_char.is_ascii_digit() && b_char.is_ascii_digit() {
+            // Compare the whole numbers, ignoring leading zeros
+            let a_end = a.find(|c: char| !c.is_ascii_digit()).unwrap_or(a.len());
+            let b_end = b.find(|c: char| !c.is_ascii_digit()).unwrap_or(b.len());
+            let (a_number, b_number) = (
+                a[..a_end].trim_start_matches('0'),
+                b[..b_end].trim_start_matches('0'),
+            );
+
+            let ordering = a_number
+                .len()
+                .cmp(&b_number.len())
+                .then_with(|| a_number.cmp(b_number));
+            if ordering != Ordering::Equal {
+                return ordering;
+            }
+
+            (a, b) = (&a[a_end..], &b[b_end..]);
+        } else {
+            if a_char != b_char {
+                return a_char.cmp(&b_char);
+            }
+
+            (a, b) = (&a[a_char.len_utf8()..], &b[b_char.len_utf8()..]);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Blit;
+
+    use super::*;
+
+    #[test]
+    fn rotated_and_trimmed() {
+        // A 2x3 sprite stored rotated clockwise
+        #[rustfmt::skip]
+        let buffer = BlitBuffer::from_buffer(&[
+            0xFF_00_00_05, 0xFF_00_00_03, 0xFF_00_00_01,
+            0xFF_00_00_06, 0xFF_00_00_04, 0xFF_00_00_02,
+        ], 3, 127);
+        let json = r#"{
+            "frames": [{
+                "filename": "sprite.png",
+                "frame": { "x": 0, "y": 0, "w": 2, "h": 3 },
+                "rotated": true,
+                "trimmed": true,
+                "spriteSourceSize": { "x": 1, "y": 0, "w": 2, "h": 3 },
+                "sourceSize": { "w": 3, "h": 3 }
+            }],
+            "meta": { "app": "https://www.codeandweb.com/texturepacker" }
+        }"#;
+        let atlas = Atlas::from_json(buffer, json).unwrap();
+
+        let frame = atlas.frame("sprite.png").unwrap();
+        assert_eq!(frame.rect, SubRect::new(0, 0, (3, 2)));
+        assert_eq!(frame.source_size, Size::new(3, 3));
+
+        let mut canvas = [0; 9];
+        atlas
+            .buffer()
+            .blit(&mut canvas, Size::new(3, 3), &frame.options());
+        assert_eq!(
+            canvas.map(|pixel| pixel & 0xFF),
+            [0, 1, 2, 0, 3, 4, 0, 5, 6]
+        );
+    }
+
+    #[test]
+    fn hash_order() {
+        let json = r#"{ "frames": {
+            "walk 10": { "frame": { "x": 0, "y": 0, "w": 1, "h": 1 } },
+            "walk 2": { "frame": { "x": 1, "y": 0, "w": 1, "h": 1 } },
+            "walk 02b": { "frame": { "x": 2, "y": 0, "w": 1, "h": 1 } },
+            "walk": { "frame": { "x": 3, "y": 0, "w": 1, "h": 1 } }
+        } }"#;
+        let atlas = Atlas::from_json(BlitBuffer::from_buffer(&[0; 4], 4, 127), json).unwrap();
+
+        assert_eq!(
+            atlas
+                .frames()
+                .iter()
+                .map(|frame| frame.name.as_str())
+                .collect::<Vec<_>>(),
+            ["walk", "walk 2", "walk 02b", "walk 10"]
+        );
+        assert_eq!(
+            atlas.options("walk 2").unwrap().sub_rect,
+            Some(SubRect::new(1, 0, (1, 1)))
+        );
+        assert!(atlas.options("run").is_none());
+
+        assert!(Atlas::from_json(BlitBuffer::from_buffer(&[0; 4], 4, 127), "{}").is_err());
+    }
+
+    #[test]
+    fn array_order() {
+        let json = r#"{ "frames": [
+            { "filename": "walk 10", "frame": { "x": 0, "y": 0, "w": 1, "h": 1 } },
+            { "filename": "walk 2", "frame": { "x": 1, "y": 0, "w": 1, "h": 1 } }
+        ] }"#;
+        let atlas = Atlas::from_json(BlitBuffer::from_buffer(&[0; 2], 2, 127), json).unwrap();
+
+        assert_eq!(
+            atlas
+                .frames()
+                .iter()
+                .map(|frame| frame.name.as_str())
+                .collect::<Vec<_>>(),
+            ["walk 10", "walk 2"]
+        );
+    }
+}
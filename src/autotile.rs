@@ -0,0 +1,207 @@
+//! Pick tiles for a grid of terrain based on which neighbors are the same terrain.
+//!
+//! Every cell gets a bitmask of its neighbors which is mapped to a tile index with a table, so the edges and corners of terrain resolve automatically.
+//! The tile indices can be drawn with the rectangles from [`BlitBuffer::split_grid`].
+//!
+//! # Example
+//!
+//! ```rust
+//! use blit::{autotile::{self, Autotiler, Neighbors}, geom::Size};
+//!
+//! // Tile 0 is an island, 1 the left end and 2 the right end of a platform
+//! let autotiler = Autotiler::new(Neighbors::Four)
+//!     .with_tile(0, 0)
+//!     .with_tile(autotile::EAST, 1)
+//!     .with_tile(autotile::WEST, 2);
+//!
+//! let terrain = [true, true, false, true];
+//! assert_eq!(
+//!     autotiler.resolve(&terrain, Size::new(4, 1)).unwrap(),
+//!     [Some(1), Some(2), None, Some(0)]
+//! );
+//! ```
+//!
+//! [`BlitBuffer::split_grid`]: crate::BlitBuffer::split_grid
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::{Error, Size};
+
+/// Bit set when the neighbor above is the same terrain.
+pub const NORTH: u8 = 1 << 0;
+/// Bit set when the neighbor at the top right is the same terrain.
+pub const NORTH_EAST: u8 = 1 << 1;
+/// Bit set when the neighbor at the right is the same terrain.
+pub const EAST: u8 = 1 << 2;
+/// Bit set when the neighbor at the bottom right is the same terrain.
+pub const SOUTH_EAST: u8 = 1 << 3;
+/// Bit set when the neighbor below is the same terrain.
+pub const SOUTH: u8 = 1 << 4;
+/// Bit set when the neighbor at the bottom left is the same terrain.
+pub const SOUTH_WEST: u8 = 1 << 5;
+/// Bit set when the neighbor at the left is the same terrain.
+pub const WEST: u8 = 1 << 6;
+/// Bit set when the neighbor at the top left is the same terrain.
+pub const NORTH_WEST: u8 = 1 << 7;
+
+/// Which neighbors are used for the bitmask.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum Neighbors {
+    /// Only the neighbors sharing an edge, resulting in 16 different masks.
+    #[default]
+    Four,
+    /// All neighbors, resulting in the 47 different masks of a blob tileset.
+    ///
+    /// A corner is only set when both edges next to it are set too, otherwise the corner tile can't be seen.
+    Eight,
+}
+
+/// Map of neighbor bitmasks to tile indices.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Autotiler {
+    /// Which neighbors are used for the bitmask.
+    neighbors: Neighbors,
+    /// Tile index for every possible bitmask.
+    table: Vec<Option<u32>>,
+}
+
+impl Autotiler {
+    /// Create an autotiler without any tiles.
+    pub fn new(neighbors: Neighbors) -> Self {
+        Self {
+            neighbors,
+            table: vec![None; 256],
+        }
+    }
+
+    /// Which neighbors are used for the bitmask.
+    pub fn neighbors(&self) -> Neighbors {
+        self.neighbors
+    }
+
+    /// Use the tile for cells with the bitmask.
+    #[must_use]
+    pub fn with_tile(mut self, mask: u8, tile: u32) -> Self {
+        self.set_tile(mask, tile);
+
+        self
+    }
+
+    /// Use the tile for cells with the bitmask.
+    pub fn set_tile(&mut self, mask: u8, tile: u32) {
+        self.table[mask as usize] = Some(tile);
+    }
+
+    /// Tile for the bitmask, `None` when it's not in the table.
+    pub fn tile(&self, mask: u8) -> Option<u32> {
+        self.table[mask as usize]
+    }
+
+    /// Bitmask of the neighbors of a cell that are also terrain.
+    ///
+    /// Neighbors outside of the grid are not terrain.
+    ///
+    /// # Panics
+    ///
+    /// When the amount of cells doesn't match the size.
+    pub fn mask(&self, terrain: &[bool], size: Size, x: u32, y: u32) -> u8 {
+        assert_eq!(terrain.len(), size.pixels());
+
+        let is_terrain = |offset_x: i32, offset_y: i32| {
+            let (x, y) = (x as i32 + offset_x, y as i32 + offset_y);
+
+            x >= 0
+                && y >= 0
+                && x < size.width as i32
+                && y < size.height as i32
+                && terrain[y as usize * size.width as usize + x as usize]
+        };
+
+        let edges = [(NORTH, 0, -1), (EAST, 1, 0), (SOUTH, 0, 1), (WEST, -1, 0)]
+            .into_iter()
+            .filter(|(_, offset_x, offset_y)| is_terrain(*offset_x, *offset_y))
+            .fold(0, |mask, (bit, _, _)| mask | bit);
+        if self.neighbors == Neighbors::Four {
+            return edges;
+        }
+
+        [
+            (NORTH_EAST, NORTH | EAST, 1, -1),
+            (SOUTH_EAST, SOUTH | EAST, 1, 1),
+            (SOUTH_WEST, SOUTH | WEST, -1, 1),
+            (NORTH_WEST, NORTH | WEST, -1, -1),
+        ]
+        .into_iter()
+        .filter(|(_, required, offset_x, offset_y)| {
+            edges & required == *required && is_terrain(*offset_x, *offset_y)
+        })
+        .fold(edges, |mask, (bit, _, _, _)| mask | bit)
+    }
+
+    /// Pick a tile for every cell of the grid, `None` for cells that aren't terrain or whose bitmask isn't in the table.
+    ///
+    /// Fails when the amount of cells doesn't match the size.
+    pub fn resolve(&self, terrain: &[bool], size: Size) -> Result<Vec<Option<u32>>, Error> {
+        if terrain.len() != size.pixels() {
+            return Err(Error::SizeMismatch {
+                size,
+                len: terrain.len(),
+            });
+        }
+
+        Ok((0..size.height)
+            .flat_map(|y| (0..size.width).map(move |x| (x, y)))
+            .zip(terrain)
+            .map(|((x, y), is_terrain)| {
+                is_terrain
+                    .then(|| self.tile(self.mask(terrain, size, x, y)))
+                    .flatten()
+            })
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn mask() {
+        #[rustfmt::skip]
+        let terrain = [
+            true, true, false,
+            true, true, true,
+            false, true, false,
+        ];
+        let size = Size::new(3, 3);
+
+        let four = Autotiler::new(Neighbors::Four);
+        assert_eq!(four.mask(&terrain, size, 1, 1), NORTH | EAST | SOUTH | WEST);
+        assert_eq!(four.mask(&terrain, size, 0, 0), EAST | SOUTH);
+
+        // Corners are only counted when both edges next to them are set
+        let eight = Autotiler::new(Neighbors::Eight);
+        assert_eq!(
+            eight.mask(&terrain, size, 1, 1),
+            NORTH | EAST | SOUTH | WEST | NORTH_WEST
+        );
+        assert_eq!(eight.mask(&terrain, size, 0, 0), EAST | SOUTH | SOUTH_EAST);
+        assert_eq!(eight.mask(&terrain, size, 2, 1), WEST);
+
+        let autotiler = Autotiler::new(Neighbors::Eight).with_tile(WEST, 5);
+        assert_eq!(
+            autotiler.resolve(&terrain, size).unwrap(),
+            [None, None, None, None, None, Some(5), None, None, None]
+        );
+        assert_eq!(
+            autotiler.resolve(&terrain, Size::new(2, 2)).unwrap_err(),
+            Error::SizeMismatch {
+                size: Size::new(2, 2),
+                len: 9
+            }
+        );
+    }
+}
@@ -0,0 +1,151 @@
+//! Endlessly scrolling backgrounds with parallax.
+//!
+//! # Example
+//!
+//! ```rust
+//! use blit::{background::ScrollingBackground, BlitBuffer, BlitOptions, geom::Size};
+//!
+//! let clouds = BlitBuffer::from_buffer(&[0xFF_00_00_01, 0xFF_00_00_02], 2, 127);
+//! let mut buffer = [0; 3];
+//!
+//! // Far away layers move slower than the camera
+//! let mut background = ScrollingBackground::new(clouds).with_parallax(0.5, 0.5);
+//! background.set_scroll(2.0, 0.0);
+//!
+//! background.blit(&mut buffer, Size::new(3, 1), &BlitOptions::new());
+//! assert_eq!(buffer.map(|pixel| pixel & 0xFF), [2, 1, 2]);
+//! ```
+
+use crate::{geom::Coordinate, Blit, BlitBuffer, BlitOptions, ScaleMode, Size};
+
+/// Buffer tiled over the destination and scrolled with a parallax factor, wrapping around so there are no seams.
+#[derive(Debug, Clone)]
+pub struct ScrollingBackground {
+    /// Pixels that are tiled.
+    buffer: BlitBuffer,
+    /// Scroll position before the parallax is applied, usually the camera position.
+    scroll: (f32, f32),
+    /// How fast the background moves relative to the scroll position.
+    parallax: (f32, f32),
+}
+
+impl ScrollingBackground {
+    /// Tile a buffer, moving it with the same speed as the scroll position.
+    pub fn new(buffer: BlitBuffer) -> Self {
+        Self {
+            buffer,
+            scroll: (0.0, 0.0),
+            parallax: (1.0, 1.0),
+        }
+    }
+
+    /// Set how fast the background moves relative to the scroll position for both axes.
+    ///
+    /// A factor of `1.0` moves with the scroll position, lower factors move slower which makes the background appear further away.
+    #[must_use]
+    pub fn with_parallax(mut self, horizontal: f32, vertical: f32) -> Self {
+        self.set_parallax(horizontal, vertical);
+
+        self
+    }
+
+    /// Set how fast the background moves relative to the scroll position for both axes.
+    pub fn set_parallax(&mut self, horizontal: f32, vertical: f32) {
+        self.parallax = (horizontal, vertical);
+    }
+
+    /// How fast the background moves relative to the scroll position for both axes.
+    pub fn parallax(&self) -> (f32, f32) {
+        self.parallax
+    }
+
+    /// Set the scroll position, usually the camera position.
+    pub fn set_scroll(&mut self, x: f32, y: f32) {
+        self.scroll = (x, y);
+    }
+
+    /// Move the scroll position.
+    pub fn scroll_by(&mut self, x: f32, y: f32) {
+        self.scroll = (self.scroll.0 + x, self.scroll.1 + y);
+    }
+
+    /// Scroll position before the parallax is applied.
+    pub fn scroll(&self) -> (f32, f32) {
+        self.scroll
+    }
+
+    /// Pixels that are tiled.
+    pub fn buffer(&self) -> &BlitBuffer {
+        &self.buffer
+    }
+
+    /// Offset in the buffer of the pixel drawn at the top left, see [`BlitOptions::uv_offset`].
+    pub fn uv_offset(&self) -> Coordinate {
+        Coordinate::new(
+            (self.scroll.0 * self.parallax.0).floor() as i32,
+            (self.scroll.1 * self.parallax.1).floor() as i32,
+        )
+    }
+
+    /// Draw the buffer tiled over the area of the options, which is the full destination when it's not set.
+    ///
+    /// The other options such as the blend mode and the mask are used as is, the scale mode is always [`ScaleMode::Tile`].
+    pub fn blit(&self, dst: &mut [u32], dst_size: Size, options: &BlitOptions) {
+        let mut options = options
+            .clone()
+            .with_scale(ScaleMode::Tile)
+            .with_uv_offset(self.uv_offset());
+        if options.area.is_none() {
+            options.area = Some(dst_size);
+        }
+
+        self.buffer.blit(dst, dst_size, &options);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn scroll() {
+        let source = (0..6).map(|i| 0xFF_00_00_00 | (i + 1)).collect::<Vec<_>>();
+        let buffer = BlitBuffer::from_buffer(&source, 3, 127);
+        let dst_size = Size::new(5, 4);
+
+        let mut background = ScrollingBackground::new(buffer.clone()).with_parallax(0.5, 2.0);
+        background.set_scroll(-3.0, 1.0);
+        background.scroll_by(-0.5, 0.25);
+        assert_eq!(background.uv_offset(), Coordinate::new(-2, 2));
+
+        let mut result = [0; 20];
+        background.blit(&mut result, dst_size, &BlitOptions::new());
+
+        let mut expected = [0; 20];
+        buffer.blit(
+            &mut expected,
+            dst_size,
+            &BlitOptions::new()
+                .with_area(dst_size)
+                .with_uv_offset((-2, 2)),
+        );
+        assert_eq!(result, expected);
+        assert!(result.iter().all(|pixel| *pixel != 0));
+    }
+
+    #[test]
+    fn scroll_screen_sized() {
+        // A background with the same size as the destination still wraps around
+        let buffer =
+            BlitBuffer::from_buffer(&[0xFF_00_00_01, 0xFF_00_00_02, 0xFF_00_00_03], 3, 127);
+        let mut background = ScrollingBackground::new(buffer);
+
+        for (scroll, expected) in [(0.0, [1, 2, 3]), (1.0, [2, 3, 1]), (2.0, [3, 1, 2])] {
+            background.set_scroll(scroll, 0.0);
+
+            let mut result = [0; 3];
+            background.blit(&mut result, Size::new(3, 1), &BlitOptions::new());
+            assert_eq!(result.map(|pixel| pixel & 0xFF), expected, "{scroll}");
+        }
+    }
+}
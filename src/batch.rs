@@ -0,0 +1,189 @@
+//! Record many blits and draw them in a single call.
+//!
+//! # Example
+//!
+//! ```rust
+//! use blit::{batch::BlitBatch, BlitBuffer, BlitOptions, geom::Size};
+//!
+//! let sprite = BlitBuffer::from_buffer(&[0xFF_FF_FF_FF; 4], 2, 127);
+//! let mut canvas = [0; 100];
+//!
+//! let mut batch = BlitBatch::new();
+//! for i in 0..5 {
+//!     batch.push(&sprite, BlitOptions::new_position(i * 2, i * 2));
+//! }
+//! batch.execute(&mut canvas, Size::new(10, 10));
+//! ```
+
+use crate::{geom::SubRect, Blit, BlitBuffer, BlitOptions, Size};
+
+/// Default amount of destination rows drawn at once.
+const DEFAULT_BAND_HEIGHT: u32 = 32;
+
+/// List of blits drawn together.
+///
+/// The destination is drawn in horizontal bands, every band draws all blits overlapping it before moving on to the next band.
+/// This keeps the destination pixels that are being drawn to in the cache, while the blits are still drawn in the order they are pushed so the last blit will be on top.
+#[derive(Debug, Clone)]
+pub struct BlitBatch<'a> {
+    /// Recorded blits in the order they are drawn.
+    commands: Vec<(&'a BlitBuffer, BlitOptions)>,
+    /// Amount of destination rows drawn at once.
+    band_height: u32,
+}
+
+impl<'a> BlitBatch<'a> {
+    /// Create an empty batch.
+    pub fn new() -> Self {
+        Self {
+            commands: Vec::new(),
+            band_height: DEFAULT_BAND_HEIGHT,
+        }
+    }
+
+    /// Set the amount of destination rows drawn at once.
+    ///
+    /// Smaller bands fit better in the cache but need more clipping, the height is clamped to the range `1..=i32::MAX`.
+    #[must_use]
+    pub fn with_band_height(mut self, band_height: u32) -> Self {
+        self.band_height = band_height.clamp(1, i32::MAX as u32);
+
+        self
+    }
+
+    /// Amount of destination rows drawn at once.
+    pub fn band_height(&self) -> u32 {
+        self.band_height
+    }
+
+    /// Record a blit, it will be drawn on top of all previously recorded blits.
+    pub fn push(&mut self, source: &'a BlitBuffer, options: BlitOptions) {
+        self.commands.push((source, options));
+    }
+
+    /// Amount of recorded blits.
+    pub fn len(&self) -> usize {
+        self.commands.len()
+    }
+
+    /// Whether no blits are recorded.
+    pub fn is_empty(&self) -> bool {
+        self.commands.is_empty()
+    }
+
+    /// Remove all recorded blits.
+    pub fn clear(&mut self) {
+        self.commands.clear();
+    }
+
+    /// Draw all recorded blits on the destination.
+    ///
+    /// The result is the same as calling [`Blit::blit`] for every recorded blit in order.
+    pub fn execute(&self, dst: &mut [u32], dst_size: Size) {
+        // Clip every blit once, the ones that are not drawn at all are skipped
+        let mut rects = self
+            .commands
+            .iter()
+            .enumerate()
+            .filter_map(|(index, (source, options))| {
+                source
+                    .effective_rect(dst_size, options)
+                    .map(|rect| (index, rect))
+            })
+            .collect::<Vec<_>>();
+
+        // Sort by the first row so every band only has to look at the blits that started before it
+        rects.sort_by_key(|(_, rect)| rect.y);
+
+        let mut next = 0;
+        let mut active: Vec<(usize, SubRect)> = Vec::new();
+        let band_height = self.band_height as i32;
+        for band_y in (0..dst_size.height as i32).step_by(band_height as usize) {
+            let band_bottom = band_y.saturating_add(band_height);
+
+            // Remove the blits that ended in a previous band
+            active.retain(|(_, rect)| rect.bottom() > band_y);
+
+            // Add the blits starting in this band
+            while next < rects.len() && rects[next].1.y < band_bottom {
+                active.push(rects[next]);
+                next += 1;
+            }
+            if active.is_empty() {
+                continue;
+            }
+
+            // Draw in the order the blits were pushed
+            active.sort_by_key(|(index, _)| *index);
+
+            for (index, rect) in active.iter() {
+                let (source, options) = &self.commands[*index];
+
+                // Only draw the part inside of the band
+                let top = rect.y.max(band_y);
+                let bottom = rect.bottom().min(band_bottom);
+                let mut band_options = options.clone();
+                band_options.mask = Some(SubRect::new(rect.x, top, (rect.width(), bottom - top)));
+
+                source.blit(dst, dst_size, &band_options);
+            }
+        }
+    }
+}
+
+impl Default for BlitBatch<'_> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn same_as_sequential() {
+        let source = (0..30)
+            .map(|i| if i % 4 == 0 { 0 } else { 0xFF_00_00_00 | i })
+            .collect::<Vec<_>>();
+        let first = BlitBuffer::from_buffer(&source, 5, 127);
+        let second = BlitBuffer::from_buffer(&source[..12], 3, 127);
+        let dst_size = Size::new(12, 11);
+
+        let commands = [
+            (&first, BlitOptions::new_position(1, 1)),
+            (&second, BlitOptions::new_position(2, 3).with_area((7, 5))),
+            (&first, BlitOptions::new_position(-2, 7)),
+            (
+                &second,
+                BlitOptions::new_position(0, 0).with_mask((1, 1, 3, 2)),
+            ),
+            (&first, BlitOptions::new_position(20, 0)),
+            (&first, BlitOptions::new_position(4, 2).with_opacity(0.5)),
+        ];
+
+        let mut expected = [0xFF_10_20_30; 12 * 11];
+        for (source, options) in commands.iter() {
+            source.blit(&mut expected, dst_size, options);
+        }
+
+        for band_height in [1, 3, 32, u32::MAX] {
+            let mut batch = BlitBatch::new().with_band_height(band_height);
+            for (source, options) in commands.iter() {
+                batch.push(source, options.clone());
+            }
+
+            let mut result = [0xFF_10_20_30; 12 * 11];
+            batch.execute(&mut result, dst_size);
+
+            assert_eq!(result, expected, "band height {band_height}");
+        }
+
+        // Heights that don't fit the destination coordinates are clamped
+        assert_eq!(BlitBatch::new().with_band_height(0).band_height(), 1);
+        assert_eq!(
+            BlitBatch::new().with_band_height(u32::MAX).band_height(),
+            i32::MAX as u32
+        );
+    }
+}
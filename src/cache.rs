@@ -0,0 +1,378 @@
+//! Cache buffers with effects applied so they don't need to be recalculated every frame.
+//!
+//! # Example
+//!
+//! ```rust
+//! use blit::{cache::{Effects, VariantCache}, BlitBuffer, BlitOptions, geom::Size};
+//!
+//! let buffer = BlitBuffer::from_buffer(&[0xFF_FF_FF_FF; 4], 2, 127);
+//! let mut canvas = [0; 4];
+//!
+//! // Allow 1KiB of cached variants, the buffers are identified by a name
+//! let mut cache = VariantCache::new(1024);
+//!
+//! // The first call creates the red variant, the second call reuses it
+//! let red = Effects::new().with_tint(0xFF_00_00);
+//! cache.blit("player", &buffer, red, &mut canvas, Size::new(2, 2), &BlitOptions::new());
+//! cache.blit("player", &buffer, red, &mut canvas, Size::new(2, 2), &BlitOptions::new());
+//! assert_eq!(canvas, [0xFF_FF_00_00; 4]);
+//! ```
+
+use std::{collections::HashMap, hash::Hash};
+
+use crate::{BlendMode, Blit, BlitBuffer, BlitOptions, Color, Size};
+
+/// Effects that can be applied to a buffer.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Effects {
+    /// Multiply all colors with this color, see [`BlitBuffer::tint`].
+    pub tint: Option<u32>,
+    /// Multiply the alpha of every pixel with this 8-bit opacity, the same as [`BlitOptions::opacity`] but baked into the pixels.
+    ///
+    /// The fade is only visible when the variant is drawn with [`BlendMode::Alpha`], masking draws every pixel that's not fully transparent as opaque.
+    pub opacity: Option<u8>,
+    /// Mirror the buffer around the vertical axis, see [`BlitBuffer::flip_horizontal`].
+    pub flip_horizontal: bool,
+    /// Mirror the buffer around the horizontal axis, see [`BlitBuffer::flip_vertical`].
+    pub flip_vertical: bool,
+}
+
+impl Effects {
+    /// No effects.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Multiply all colors with this color.
+    #[must_use]
+    pub fn with_tint(mut self, color: u32) -> Self {
+        self.tint = Some(color);
+
+        self
+    }
+
+    /// Fade the buffer by multiplying the alpha of every pixel, in the range `0.0..=1.0`.
+    ///
+    /// The opacity is clamped to the range `0.0..=1.0` and stored as an 8-bit alpha, so every opacity drawn the same is cached once.
+    #[must_use]
+    pub fn with_opacity(mut self, opacity: f32) -> Self {
+        self.opacity = Some((opacity.clamp(0.0, 1.0) * 255.0).round() as u8);
+
+        self
+    }
+
+    /// Mirror the buffer around the vertical axis.
+    #[must_use]
+    pub fn with_flip_horizontal(mut self) -> Self {
+        self.flip_horizontal = true;
+
+        self
+    }
+
+    /// Mirror the buffer around the horizontal axis.
+    #[must_use]
+    pub fn with_flip_vertical(mut self) -> Self {
+        self.flip_vertical = true;
+
+        self
+    }
+
+    /// Create a copy of the buffer with all effects applied.
+    pub fn apply(&self, buffer: &BlitBuffer) -> BlitBuffer {
+        let mut variant = buffer.clone();
+        if let Some(tint) = self.tint {
+            variant.tint(tint);
+        }
+        if let Some(opacity) = self.opacity {
+            variant.modify_pixels(|pixels| {
+                pixels
+                    .iter_mut()
+                    .for_each(|pixel| *pixel = BlendMode::fade(*pixel, opacity as Color));
+            });
+        }
+        if self.flip_horizontal {
+            variant.flip_horizontal();
+        }
+        if self.flip_vertical {
+            variant.flip_vertical();
+        }
+
+        variant
+    }
+}
+
+/// Least-recently-used cache of buffers with effects applied.
+///
+/// Buffers are identified by a key chosen by the caller, such as the name or the index of an asset.
+/// The same key must always be passed with the same pixels, when the pixels behind a key change the cached variants must be removed with [`VariantCache::remove`].
+#[derive(Debug)]
+pub struct VariantCache<K> {
+    /// Maximum amount of bytes of pixel data all variants together are allowed to use.
+    byte_budget: usize,
+    /// Amount of bytes of pixel data all variants currently use.
+    bytes: usize,
+    /// Counter incremented on every lookup to find the least recently used variant.
+    tick: u64,
+    /// Cached variants, keyed by the buffer key and the effects.
+    variants: HashMap<(K, Effects), Variant>,
+}
+
+impl<K> VariantCache<K>
+where
+    K: Hash + Eq + Clone,
+{
+    /// Create an empty cache that will never use more than the amount of bytes for all variants.
+    pub fn new(byte_budget: usize) -> Self {
+        Self {
+            byte_budget,
+            bytes: 0,
+            tick: 0,
+            variants: HashMap::new(),
+        }
+    }
+
+    /// Draw the buffer with the effects applied, creating the variant when it's not in the cache.
+    ///
+    /// When the variant is bigger than the budget it's drawn without being cached.
+    pub fn blit(
+        &mut self,
+        key: K,
+        buffer: &BlitBuffer,
+        effects: Effects,
+        dst: &mut [u32],
+        dst_size: Size,
+        options: &BlitOptions,
+    ) {
+        match self.variant(key, buffer, effects) {
+            Some(variant) => variant.blit(dst, dst_size, options),
+            None => effects.apply(buffer).blit(dst, dst_size, options),
+        }
+    }
+
+    /// Get the buffer with the effects applied, creating the variant when it's not in the cache.
+    ///
+    /// Returns `None` when the variant is bigger than the budget.
+    pub fn variant(
+        &mut self,
+        key: K,
+        buffer: &BlitBuffer,
+        effects: Effects,
+    ) -> Option<&BlitBuffer> {
+        self.tick += 1;
+        let key = (key, effects);
+
+        if !self.variants.contains_key(&key) {
+            let bytes = Variant::bytes(buffer);
+            if bytes > self.byte_budget {
+                return None;
+            }
+
+            // Make room for the new variant
+            while self.bytes + bytes > self.byte_budget {
+                self.evict();
+            }
+
+            self.bytes += bytes;
+            self.variants.insert(
+                key.clone(),
+                Variant {
+                    buffer: effects.apply(buffer),
+                    last_used: self.tick,
+                },
+            );
+        }
+
+        let variant = self.variants.get_mut(&key)?;
+        variant.last_used = self.tick;
+
+        Some(&variant.buffer)
+    }
+
+    /// Amount of bytes of pixel data used by all cached variants.
+    pub fn bytes(&self) -> usize {
+        self.bytes
+    }
+
+    /// Amount of cached variants.
+    pub fn len(&self) -> usize {
+        self.variants.len()
+    }
+
+    /// Whether no variants are cached.
+    pub fn is_empty(&self) -> bool {
+        self.variants.is_empty()
+    }
+
+    /// Remove all cached variants of the buffer with the key, for example after its pixels changed.
+    pub fn remove(&mut self, key: &K) {
+        let mut bytes = 0;
+        self.variants.retain(|(variant_key, _), variant| {
+            let keep = variant_key != key;
+            if !keep {
+                bytes += Variant::bytes(&variant.buffer);
+            }
+
+            keep
+        });
+        self.bytes -= bytes;
+    }
+
+    /// Remove all cached variants.
+    pub fn clear(&mut self) {
+        self.variants.clear();
+        self.bytes = 0;
+    }
+
+    /// Remove the least recently used variant.
+    fn evict(&mut self) {
+        let Some(key) = self
+            .variants
+            .iter()
+            .min_by_key(|(_, variant)| variant.last_used)
+            .map(|(key, _)| key.clone())
+        else {
+            return;
+        };
+
+        if let Some(variant) = self.variants.remove(&key) {
+            self.bytes -= Variant::bytes(&variant.buffer);
+        }
+    }
+}
+
+/// Buffer with effects applied.
+#[derive(Debug)]
+struct Variant {
+    /// The buffer with the effects applied.
+    buffer: BlitBuffer,
+    /// Tick of the last time this variant was requested.
+    last_used: u64,
+}
+
+impl Variant {
+    /// Amount of bytes the pixels of the buffer use.
+    fn bytes(buffer: &BlitBuffer) -> usize {
+        std::mem::size_of_val(buffer.pixels())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reuse_and_evict() {
+        // Every buffer uses 16 bytes
+        let first = BlitBuffer::from_buffer(&[0xFF_11_22_33, 0, 0, 0xFF_44_55_66], 2, 127);
+        let second = BlitBuffer::from_buffer(&[0xFF_FF_FF_FF; 4], 2, 127);
+        let effects = Effects::new().with_tint(0xFF_80_FF).with_flip_horizontal();
+
+        let mut cache = VariantCache::new(32);
+
+        // The same variant is returned the second time, pixel data is compared because the map can move the buffers
+        let variant = cache.variant(0, &first, effects).unwrap().pixels().as_ptr();
+        assert_eq!(
+            cache.variant(0, &first, effects).unwrap().pixels().as_ptr(),
+            variant
+        );
+        assert_eq!(
+            cache.variant(0, &first, effects).unwrap().pixels(),
+            [0, 0xFF_11_11_33, 0xFF_44_2A_66, 0]
+        );
+
+        // Fill the budget
+        cache.variant(1, &second, effects).unwrap();
+        assert_eq!(cache.bytes(), 32);
+
+        // Use the first one again so the second is the least recently used
+        cache.variant(0, &first, effects).unwrap();
+
+        // Adding another variant evicts the second variant
+        cache.variant(0, &first, Effects::new()).unwrap();
+        assert_eq!(cache.len(), 2);
+        assert_eq!(cache.bytes(), 32);
+        assert_eq!(
+            cache.variant(0, &first, effects).unwrap().pixels().as_ptr(),
+            variant
+        );
+
+        // Variants bigger than the budget are never cached
+        let big = BlitBuffer::from_buffer(&[0xFF_FF_FF_FF; 16], 4, 127);
+        assert!(cache.variant(2, &big, effects).is_none());
+        assert!(cache.bytes() <= 32);
+    }
+
+    #[test]
+    fn keys() {
+        let effects = Effects::new().with_flip_horizontal();
+        let mut cache = VariantCache::new(1024);
+
+        // A new buffer with a different key never gets the variant of a dropped buffer, even when the memory is reused
+        let first = BlitBuffer::from_buffer(&[0xFF_00_00_01, 0xFF_00_00_02], 2, 127);
+        cache.variant("first", &first, effects).unwrap();
+        drop(first);
+        let second = BlitBuffer::from_buffer(&[0xFF_00_00_03, 0xFF_00_00_04], 2, 127);
+        assert_eq!(
+            cache.variant("second", &second, effects).unwrap().pixels(),
+            [0xFF_00_00_04, 0xFF_00_00_03]
+        );
+
+        // Changed pixels are picked up after removing the variants of the key
+        let changed = BlitBuffer::from_buffer(&[0xFF_00_00_05, 0xFF_00_00_06], 2, 127);
+        cache.variant("second", &second, Effects::new()).unwrap();
+        assert_eq!(cache.len(), 3);
+        cache.remove(&"second");
+        assert_eq!((cache.len(), cache.bytes()), (1, 8));
+        assert_eq!(
+            cache.variant("second", &changed, effects).unwrap().pixels(),
+            [0xFF_00_00_06, 0xFF_00_00_05]
+        );
+    }
+
+    #[test]
+    fn opacity() {
+        let buffer = BlitBuffer::from_buffer_with_alpha(
+            &[
+                0xFF_FF_00_00,
+                0x80_00_FF_00,
+                0x01_00_00_FF,
+                0,
+                0xC0_12_34_56,
+                0xFF_FF_FF_FF,
+            ],
+            3,
+        );
+        let options = BlitOptions::new_position(1, 1).with_blend(BlendMode::Alpha);
+        let mut cache = VariantCache::new(1024);
+
+        for opacity in [0.0, 0.25, 0.5, 1.0] {
+            // Baking the opacity is the same as drawing with it
+            let mut cached = [0xFF_20_40_60; 16];
+            cache.blit(
+                "buffer",
+                &buffer,
+                Effects::new().with_opacity(opacity),
+                &mut cached,
+                Size::new(4, 4),
+                &options,
+            );
+            let mut expected = [0xFF_20_40_60; 16];
+            buffer.blit(
+                &mut expected,
+                Size::new(4, 4),
+                &options.clone().with_opacity(opacity),
+            );
+            assert_eq!(cached, expected, "{opacity}");
+        }
+
+        // Pixels faded to fully transparent are skipped when masking
+        let faded = cache
+            .variant("buffer", &buffer, Effects::new().with_opacity(0.5))
+            .unwrap()
+            .clone();
+        let mut masked = [0; 6];
+        faded.blit(&mut masked, Size::new(3, 2), &BlitOptions::new());
+        assert_eq!(masked[2], 0);
+    }
+}
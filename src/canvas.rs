@@ -0,0 +1,512 @@
+//! Destination buffer bundled with its size.
+//!
+//! # Example
+//!
+//! ```rust
+//! use blit::{canvas::Canvas, Blit, BlitBuffer, BlitOptions, geom::Size};
+//!
+//! let sprite = BlitBuffer::from_buffer(&[0xFF_FF_FF_FF; 4], 2, 127);
+//! let mut buffer = [0; 4 * 3];
+//!
+//! let mut canvas = Canvas::new(&mut buffer, Size::new(4, 3)).unwrap();
+//! canvas.clear(0xFF_00_00_00);
+//!
+//! // Everything drawn on the panel is relative to it and clipped by it
+//! let mut panel = canvas.sub((1, 1, 2, 2));
+//! panel.blit(&sprite, &BlitOptions::new_position(1, 0));
+//! panel.fill_rect((0, 1, 1, 1), 0xFF_FF_00_00);
+//!
+//! assert_eq!(
+//!     buffer,
+//!     [
+//!         0xFF_00_00_00, 0xFF_00_00_00, 0xFF_00_00_00, 0xFF_00_00_00,
+//!         0xFF_00_00_00, 0xFF_00_00_00, 0xFF_FF_FF_FF, 0xFF_00_00_00,
+//!         0xFF_00_00_00, 0xFF_FF_00_00, 0xFF_FF_FF_FF, 0xFF_00_00_00,
+//!     ]
+//! );
+//! ```
+
+use crate::{
+    geom::{Camera, SubRect},
+    view::ImageView,
+    BlendMode, Blit, BlitBuffer, BlitOptions, ChannelOrder, Error, ScaleMode, Size,
+};
+
+/// Mutable destination buffer with its size, optionally limited to a rectangle of it.
+///
+/// All positions are relative to the rectangle and everything drawn is clipped by it.
+#[derive(Debug)]
+pub struct Canvas<'a> {
+    /// Pixels of the full destination buffer.
+    pixels: &'a mut [u32],
+    /// Size of the full destination buffer.
+    buffer_size: Size,
+    /// Rectangle of the destination buffer that positions are relative to, inside of the buffer except for bands from [`Self::split_rows`].
+    view: SubRect,
+    /// Rectangle of the destination buffer that can be drawn on, always inside of the buffer and the view.
+    limit: SubRect,
+    /// Pushed clipping rectangles in the destination buffer, every rectangle is inside of the previous one and the view.
+    clips: Vec<SubRect>,
+}
+
+impl<'a> Canvas<'a> {
+    /// Wrap a destination buffer, fails when the amount of pixels doesn't match the size.
+    pub fn new(pixels: &'a mut [u32], size: Size) -> Result<Self, Error> {
+        if pixels.len() != size.pixels() {
+            return Err(Error::SizeMismatch {
+                size,
+                len: pixels.len(),
+            });
+        }
+
+        Ok(Self {
+            pixels,
+            buffer_size: size,
+            view: SubRect::from_size(size),
+            limit: SubRect::from_size(size),
+            clips: Vec::new(),
+        })
+    }
+
+    /// Wrap a destination buffer of which the amount of pixels is known to match the size.
+    #[cfg(feature = "softbuffer")]
+    pub(crate) fn from_parts(pixels: &'a mut [u32], size: Size) -> Self {
+        debug_assert_eq!(pixels.len(), size.pixels());
+
+        Self {
+            pixels,
+            buffer_size: size,
+            view: SubRect::from_size(size),
+            limit: SubRect::from_size(size),
+            clips: Vec::new(),
+        }
+    }
+
+    /// Size of the part that can be drawn on.
+    pub fn size(&self) -> Size {
+        self.view.size
+    }
+
+    /// Rectangle of the full destination buffer that positions are relative to.
+    ///
+    /// For bands from [`Self::split_rows`] the rectangle is relative to the first row of the band, and only the rows of the band can be drawn on.
+    pub fn view(&self) -> SubRect {
+        self.view
+    }
+
+    /// Get a canvas of a part of this canvas, the rectangle is relative to this canvas and clipped by it.
+    pub fn sub<R>(&mut self, rect: R) -> Canvas<'_>
+    where
+        R: Into<SubRect>,
+    {
+        let view = self.clip(rect.into());
+
+        Canvas {
+            pixels: self.pixels,
+            buffer_size: self.buffer_size,
+            view,
+            limit: view,
+            clips: Vec::new(),
+        }
+    }
+
+    /// Split the canvas into at most `bands` canvases of whole rows that don't overlap, so they can be drawn on from different threads.
+    ///
+    /// Every band keeps the positions of this canvas and clips everything drawn to its own rows and the currently pushed clipping rectangle.
+    /// Drawing the same things on every band gives the same result as drawing them once on this canvas.
+    ///
+    /// ```rust
+    /// # use blit::{canvas::Canvas, geom::Size};
+    /// let mut buffer = [0; 2 * 4];
+    /// let mut canvas = Canvas::new(&mut buffer, Size::new(2, 4)).unwrap();
+    ///
+    /// std::thread::scope(|scope| {
+    ///     for mut band in canvas.split_rows(2) {
+    ///         scope.spawn(move || band.fill_rect((1, 0, 1, 4), 1));
+    ///     }
+    /// });
+    ///
+    /// assert_eq!(buffer, [0, 1, 0, 1, 0, 1, 0, 1]);
+    /// ```
+    pub fn split_rows(&mut self, bands: usize) -> Vec<Canvas<'_>> {
+        let bounds = self.bounds();
+        if bands == 0 || bounds.width() == 0 || bounds.height() == 0 {
+            return Vec::new();
+        }
+
+        let width = self.buffer_size.width as usize;
+        let band_height = bounds.height().div_ceil(bands as u32);
+
+        // Skip the rows above the first band
+        let (_, mut rest) = self.pixels.split_at_mut(bounds.y as usize * width);
+        (bounds.y..bounds.y + bounds.height() as i32)
+            .step_by(band_height as usize)
+            .map(|y| {
+                let height = band_height.min((bounds.y + bounds.height() as i32 - y) as u32);
+                let (pixels, next) =
+                    std::mem::take(&mut rest).split_at_mut(height as usize * width);
+                rest = next;
+
+                // Move everything up so the band starts at the top of its pixels
+                Canvas {
+                    pixels,
+                    buffer_size: Size::new(self.buffer_size.width, height),
+                    view: SubRect::new(self.view.x, self.view.y - y, self.view.size),
+                    limit: SubRect::new(bounds.x, 0, (bounds.width(), height)),
+                    clips: Vec::new(),
+                }
+            })
+            .collect()
+    }
+
+    /// Only draw inside of the rectangle until [`Self::pop_clip`] is called.
+    ///
+    /// The rectangle is relative to this canvas and intersected with the previously pushed rectangles, positions are not changed.
+    ///
+    /// ```rust
+    /// # use blit::{canvas::Canvas, geom::Size};
+    /// let mut buffer = [0; 4];
+    /// let mut canvas = Canvas::new(&mut buffer, Size::new(4, 1)).unwrap();
+    ///
+    /// canvas.push_clip((1, 0, 3, 1));
+    /// canvas.push_clip((0, 0, 3, 1));
+    /// canvas.clear(1);
+    /// canvas.pop_clip();
+    /// canvas.pop_clip();
+    /// canvas.fill_rect((0, 0, 1, 1), 2);
+    ///
+    /// assert_eq!(buffer, [2, 1, 1, 0]);
+    /// ```
+    pub fn push_clip<R>(&mut self, rect: R)
+    where
+        R: Into<SubRect>,
+    {
+        let clip = self.clip(rect.into());
+
+        self.clips.push(clip);
+    }
+
+    /// Remove the last rectangle pushed with [`Self::push_clip`], returning it relative to this canvas.
+    pub fn pop_clip(&mut self) -> Option<SubRect> {
+        self.clips
+            .pop()
+            .map(|clip| SubRect::new(clip.x - self.view.x, clip.y - self.view.y, clip.size))
+    }
+
+    /// Set all pixels to a color.
+    pub fn clear(&mut self, color: u32) {
+        self.fill_rect(SubRect::from_size(self.view.size), color);
+    }
+
+    /// Set all pixels inside of the rectangle to a color, without blending.
+    pub fn fill_rect<R>(&mut self, rect: R, color: u32)
+    where
+        R: Into<SubRect>,
+    {
+        let rect = self.clip(rect.into());
+        if rect.width() == 0 || rect.height() == 0 {
+            return;
+        }
+
+        ImageView(rect)
+            .parent_ranges_iter(self.buffer_size)
+            .for_each(|range| self.pixels[range].fill(color));
+    }
+
+    /// Draw the source like [`Blit::blit`], the options are relative to this canvas and clipped by it.
+    pub fn blit<B>(&mut self, source: &B, options: &BlitOptions)
+    where
+        B: Blit + ?Sized,
+    {
+        let options = options
+            .clone()
+            .offset_by((self.view.x, self.view.y))
+            .clipped_by(self.bounds());
+
+        source.blit(self.pixels, self.buffer_size, &options);
+    }
+
+    /// Draw a buffer placed in the world as seen through the camera, the viewport of the camera is relative to this canvas.
+    ///
+    /// The position, area and mask of the options are in world coordinates.
+    /// Buffers that are completely outside of the viewport are skipped without drawing anything.
+    /// When zoomed every world pixel becomes a square of pixels, tiles and slices keep their size in the world.
+    ///
+    /// ```rust
+    /// # use blit::{canvas::Canvas, geom::{Camera, Size}, BlitBuffer, BlitOptions};
+    /// let sprite = BlitBuffer::from_buffer(&[0xFF_FF_FF_FF], 1, 127);
+    /// let mut buffer = [0; 4];
+    /// let mut canvas = Canvas::new(&mut buffer, Size::new(4, 1)).unwrap();
+    ///
+    /// let camera = Camera::new((0, 0, 4, 1)).with_offset((10, 0)).with_zoom(2);
+    /// canvas.blit_world(&camera, &sprite, &BlitOptions::new_position(11, 0));
+    ///
+    /// assert_eq!(buffer, [0, 0, 0xFF_FF_FF_FF, 0xFF_FF_FF_FF]);
+    /// ```
+    pub fn blit_world(&mut self, camera: &Camera, source: &BlitBuffer, options: &BlitOptions) {
+        let area = options.area(source.size());
+        let mut options = options.anchored(area).into_owned();
+        let world = SubRect::new(options.x, options.y, area);
+        if !camera.is_visible(world) {
+            return;
+        }
+
+        let position = camera.world_to_screen((options.x, options.y));
+        options.x = position.x;
+        options.y = position.y;
+        options.mask = options.mask.map(|mask| camera.world_to_screen_rect(mask));
+        if camera.zoom > 1 {
+            let is_single_copy = options.vertical_slice.is_none()
+                && options.horizontal_slice.is_none()
+                && (options.scale == ScaleMode::Stretch
+                    || options.fills_area_exactly(
+                        options.sub_rect(source.size()).size,
+                        options.rotated_size(area),
+                    ));
+            if !is_single_copy {
+                self.blit_world_zoomed(camera, source, &options, world);
+                return;
+            }
+
+            // A single copy of the source can be stretched directly
+            options.area = Some(area.saturating_mul((camera.zoom, camera.zoom)));
+            options.scale = ScaleMode::Stretch;
+        }
+
+        self.blit(source, &options.clipped_by(camera.viewport));
+    }
+
+    /// Draw tiles and slices at world scale first and stretch the result with the zoom, so the tiles keep their size in the world.
+    ///
+    /// Only the visible part of the area is drawn at world scale, the options are already converted to the screen except for the world rectangle.
+    fn blit_world_zoomed(
+        &mut self,
+        camera: &Camera,
+        source: &BlitBuffer,
+        options: &BlitOptions,
+        world: SubRect,
+    ) {
+        let Some(visible) = camera.visible_rect().intersection(world) else {
+            return;
+        };
+
+        // Draw the visible part as is, blending and color conversion happen when stretching
+        let mut world_options = options.clone();
+        world_options.x = world.x - visible.x;
+        world_options.y = world.y - visible.y;
+        world_options.mask = None;
+        world_options.blend = BlendMode::Mask;
+        world_options.opacity = None;
+        world_options.alpha_threshold = None;
+        world_options.channel_order = ChannelOrder::Argb;
+        let mut pixels = vec![0; visible.size.pixels()];
+        source.blit(&mut pixels, visible.size, &world_options);
+
+        let position = camera.world_to_screen((visible.x, visible.y));
+        let mut zoomed_options = BlitOptions::new_position(position.x, position.y)
+            .with_area(visible.size.saturating_mul((camera.zoom, camera.zoom)))
+            .with_scale(ScaleMode::Stretch)
+            .with_blend(options.blend)
+            .with_channel_order(options.channel_order);
+        zoomed_options.mask = options.mask;
+        zoomed_options.opacity = options.opacity;
+        zoomed_options.alpha_threshold = options.alpha_threshold;
+
+        self.blit(
+            &BlitBuffer::from_buffer_with_alpha(&pixels, visible.width()),
+            &zoomed_options.clipped_by(camera.viewport),
+        );
+    }
+
+    /// Convert a rectangle relative to this canvas to the destination buffer and clip it to the drawable part and the pushed clipping rectangle.
+    fn clip(&self, rect: SubRect) -> SubRect {
+        let rect = SubRect::new(rect.x + self.view.x, rect.y + self.view.y, rect.size);
+
+        ImageView(self.bounds()).clip(rect).as_sub_rect()
+    }
+
+    /// Rectangle of the destination buffer that can currently be drawn on.
+    fn bounds(&self) -> SubRect {
+        self.clips.last().copied().unwrap_or(self.limit)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{command::CommandBuffer, font::BitmapFont, Anchor};
+
+    use super::*;
+
+    #[test]
+    fn sub() {
+        let sprite = BlitBuffer::from_buffer(&[0xFF_00_00_01; 9], 3, 127);
+        let mut buffer = [0; 5 * 5];
+
+        let mut canvas = Canvas::new(&mut buffer, Size::new(5, 5)).unwrap();
+        assert_eq!(canvas.size(), Size::new(5, 5));
+
+        // Nested views are clipped by their parents
+        let mut inner = canvas.sub((-1, 1, 4, 4));
+        assert_eq!(inner.view(), SubRect::new(0, 1, (3, 4)));
+        let mut inner = inner.sub((2, 2, 5, 5));
+        assert_eq!(inner.view(), SubRect::new(2, 3, (1, 2)));
+
+        sprite.blit_canvas(&mut inner, &BlitOptions::new_position(-1, -1));
+        inner.fill_rect((0, 1, 9, 9), 0xFF_00_00_02);
+
+        let mut expected = [0; 5 * 5];
+        expected[3 * 5 + 2] = 0xFF_00_00_01;
+        expected[4 * 5 + 2] = 0xFF_00_00_02;
+        assert_eq!(buffer, expected);
+
+        assert_eq!(
+            Canvas::new(&mut [0; 3], Size::new(2, 2)).unwrap_err(),
+            Error::SizeMismatch {
+                size: Size::new(2, 2),
+                len: 3
+            }
+        );
+    }
+
+    #[test]
+    fn clip_stack() {
+        let sprite = BlitBuffer::from_buffer(&[0xFF_00_00_01; 16], 4, 127);
+        let mut buffer = [0; 5 * 5];
+
+        let mut canvas = Canvas::new(&mut buffer, Size::new(5, 5)).unwrap();
+        let mut inner = canvas.sub((1, 1, 4, 4));
+        inner.push_clip((-1, 1, 3, 9));
+        inner.push_clip((1, 0, 9, 2));
+        assert_eq!(inner.size(), Size::new(4, 4));
+
+        // Intersects with the mask of the options
+        inner.blit(&sprite, &BlitOptions::new().with_mask((0, 0, 9, 2)));
+        assert_eq!(inner.pop_clip(), Some(SubRect::new(1, 1, (1, 1))));
+        inner.fill_rect((0, 3, 9, 9), 0xFF_00_00_02);
+        assert_eq!(inner.pop_clip(), Some(SubRect::new(0, 1, (2, 3))));
+        assert_eq!(inner.pop_clip(), None);
+        inner.fill_rect((3, 3, 1, 1), 0xFF_00_00_03);
+
+        let mut expected = [0; 5 * 5];
+        expected[2 * 5 + 2] = 0xFF_00_00_01;
+        expected[4 * 5 + 1] = 0xFF_00_00_02;
+        expected[4 * 5 + 2] = 0xFF_00_00_02;
+        expected[4 * 5 + 4] = 0xFF_00_00_03;
+        assert_eq!(buffer, expected);
+    }
+
+    #[test]
+    fn split_rows() {
+        let sprite = BlitBuffer::from_buffer(
+            &(1..=12).map(|i| 0xFF_00_00_00 | i).collect::<Vec<_>>(),
+            3,
+            127,
+        );
+        let font = BitmapFont::new(
+            BlitBuffer::from_buffer(&[0xFF_00_00_0D, 0xFF_00_00_0E], 2, 127),
+            Size::new(1, 1),
+            'a',
+        );
+        let size = Size::new(6, 7);
+
+        let mut commands = CommandBuffer::new();
+        commands.fill_rect((-1, 0, 9, 9), 0xFF_00_00_0F);
+        commands.blit(&sprite, BlitOptions::new_position(1, -1));
+        commands.blit(
+            &sprite,
+            BlitOptions::new_position(-1, 2).with_flip_vertical(true),
+        );
+        commands.text(&font, (0, 4), "ab\nba");
+
+        let mut expected = [0; 6 * 7];
+        let mut canvas = Canvas::new(&mut expected, size).unwrap();
+        let mut inner = canvas.sub((1, 1, 5, 5));
+        inner.push_clip((0, 1, 4, 9));
+        commands.replay_canvas(&mut inner);
+
+        for bands in [1, 2, 3, 4, 9] {
+            let mut buffer = [0; 6 * 7];
+            let mut canvas = Canvas::new(&mut buffer, size).unwrap();
+            let mut inner = canvas.sub((1, 1, 5, 5));
+            inner.push_clip((0, 1, 4, 9));
+
+            let bands = inner.split_rows(bands);
+            assert!(bands.iter().all(|band| band.size() == Size::new(5, 5)));
+
+            std::thread::scope(|scope| {
+                for mut band in bands {
+                    let commands = &commands;
+                    scope.spawn(move || commands.replay_canvas(&mut band));
+                }
+            });
+            assert_eq!(buffer, expected);
+        }
+
+        let mut buffer = [0; 6 * 7];
+        let mut canvas = Canvas::new(&mut buffer, size).unwrap();
+        assert_eq!(canvas.split_rows(3).len(), 3);
+        assert_eq!(canvas.split_rows(6).len(), 4);
+        assert!(canvas.split_rows(0).is_empty());
+        assert!(canvas.sub((0, 9, 1, 1)).split_rows(2).is_empty());
+    }
+
+    #[test]
+    fn world() {
+        let sprite = BlitBuffer::from_buffer(&[0xFF_00_00_01, 0xFF_00_00_02], 2, 127);
+        let mut buffer = [0; 6 * 4];
+
+        let mut canvas = Canvas::new(&mut buffer, Size::new(6, 4)).unwrap();
+        let mut inner = canvas.sub((1, 0, 5, 4));
+
+        let camera = Camera::new((1, 1, 4, 2)).with_offset((-3, 5)).with_zoom(2);
+        assert_eq!(camera.visible_rect(), SubRect::new(-3, 5, (2, 1)));
+        assert!(camera.is_visible((-2, 4, 1, 2)));
+        assert!(!camera.is_visible((-1, 5, 1, 1)));
+
+        // Partially visible sprites are clipped by the viewport
+        inner.blit_world(&camera, &sprite, &BlitOptions::new_position(-3, 5));
+        inner.blit_world(
+            &camera,
+            &sprite,
+            &BlitOptions::new_position(-2, 6).with_anchor(Anchor::BottomLeft),
+        );
+        // Off-screen sprites are not drawn
+        inner.blit_world(&camera, &sprite, &BlitOptions::new_position(-5, 5));
+
+        let mut expected = [0; 6 * 4];
+        for y in 1..3 {
+            expected[y * 6 + 2..y * 6 + 6].fill(0xFF_00_00_01);
+        }
+        assert_eq!(buffer, expected);
+    }
+
+    #[test]
+    fn world_zoomed_tiles() {
+        let sprite = BlitBuffer::from_buffer(&[0xFF_00_00_01, 0xFF_00_00_02], 2, 127);
+        let tiled = BlitOptions::new().with_area((4, 1));
+        let draw = |camera: &Camera, options: &BlitOptions| {
+            let mut buffer = [0; 8];
+            let mut canvas = Canvas::new(&mut buffer, Size::new(8, 1)).unwrap();
+            canvas.blit_world(camera, &sprite, options);
+
+            buffer.map(|pixel| pixel & 0xFF)
+        };
+
+        // Every tile is zoomed instead of the whole area being stretched
+        let camera = Camera::new((0, 0, 8, 1)).with_zoom(2);
+        assert_eq!(draw(&camera, &tiled), [1, 1, 2, 2, 1, 1, 2, 2]);
+
+        // Only the visible part of the area is drawn
+        let camera = Camera::new((1, 0, 6, 1)).with_offset((1, 0)).with_zoom(2);
+        assert_eq!(draw(&camera, &tiled), [0, 2, 2, 1, 1, 2, 2, 0]);
+
+        // Scrolled tiles are masked in world coordinates
+        let options = tiled.clone().with_uv_offset((1, 0)).with_mask((0, 0, 3, 1));
+        let camera = Camera::new((0, 0, 8, 1)).with_zoom(2);
+        assert_eq!(draw(&camera, &options), [2, 2, 1, 1, 2, 2, 0, 0]);
+
+        // Stretching keeps working like before
+        let options = tiled.with_scale(ScaleMode::Stretch);
+        assert_eq!(draw(&camera, &options), [1, 1, 1, 1, 2, 2, 2, 2]);
+    }
+}
@@ -0,0 +1,206 @@
+//! Record drawing commands once and replay them on any destination.
+//!
+//! # Example
+//!
+//! ```rust
+//! use blit::{command::CommandBuffer, BlitBuffer, BlitOptions, geom::Size};
+//!
+//! let icon = BlitBuffer::from_buffer(&[0xFF_FF_FF_FF], 1, 127);
+//!
+//! // Record the user interface once
+//! let mut ui = CommandBuffer::new();
+//! ui.fill_rect((0, 0, 3, 1), 0xFF_00_00_00);
+//! ui.blit(&icon, BlitOptions::new_position(1, 0));
+//!
+//! // Replay it every frame
+//! let mut frame = [0; 3];
+//! ui.replay(&mut frame, Size::new(3, 1));
+//! assert_eq!(frame, [0xFF_00_00_00, 0xFF_FF_FF_FF, 0xFF_00_00_00]);
+//! ```
+
+use crate::{
+    canvas::Canvas,
+    draw,
+    font::BitmapFont,
+    geom::{Coordinate, SubRect},
+    Blit, BlitBuffer, BlitOptions, Size,
+};
+
+/// Single recorded drawing operation.
+#[derive(Debug, Clone)]
+pub enum Command<'a> {
+    /// Draw a buffer like [`Blit::blit`].
+    Blit {
+        /// Buffer that's drawn.
+        source: &'a BlitBuffer,
+        /// How the buffer is drawn.
+        options: BlitOptions,
+    },
+    /// Set all pixels inside of a rectangle like [`draw::fill_rect`].
+    FillRect {
+        /// Rectangle that's filled.
+        rect: SubRect,
+        /// Color of the pixels.
+        color: u32,
+    },
+    /// Draw text like [`BitmapFont::draw`].
+    Text {
+        /// Font the text is drawn with.
+        font: &'a BitmapFont,
+        /// Top left of the first glyph.
+        position: Coordinate,
+        /// Text that's drawn.
+        text: String,
+    },
+}
+
+impl Command<'_> {
+    /// Draw the command on the destination.
+    pub fn execute(&self, dst: &mut [u32], dst_size: Size) {
+        match self {
+            Command::Blit { source, options } => source.blit(dst, dst_size, options),
+            Command::FillRect { rect, color } => draw::fill_rect(dst, dst_size, *rect, *color),
+            Command::Text {
+                font,
+                position,
+                text,
+            } => font.draw(dst, dst_size, *position, text),
+        }
+    }
+
+    /// Draw the command on a canvas, the positions are relative to the canvas and everything is clipped by it.
+    pub fn execute_canvas(&self, canvas: &mut Canvas<'_>) {
+        match self {
+            Command::Blit { source, options } => canvas.blit(*source, options),
+            Command::FillRect { rect, color } => canvas.fill_rect(*rect, *color),
+            Command::Text {
+                font,
+                position,
+                text,
+            } => font.draw_canvas(canvas, *position, text),
+        }
+    }
+}
+
+/// List of drawing commands that can be replayed as often as needed.
+///
+/// The sources are borrowed, so recording is cheap and the commands always draw the current pixels of the sources.
+#[derive(Debug, Clone, Default)]
+pub struct CommandBuffer<'a> {
+    /// Recorded commands in the order they are drawn.
+    commands: Vec<Command<'a>>,
+}
+
+impl<'a> CommandBuffer<'a> {
+    /// Create an empty command buffer.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record a blit of a buffer.
+    pub fn blit(&mut self, source: &'a BlitBuffer, options: BlitOptions) {
+        self.push(Command::Blit { source, options });
+    }
+
+    /// Record filling a rectangle with a color, without blending.
+    pub fn fill_rect<R>(&mut self, rect: R, color: u32)
+    where
+        R: Into<SubRect>,
+    {
+        self.push(Command::FillRect {
+            rect: rect.into(),
+            color,
+        });
+    }
+
+    /// Record drawing text with the top left of the first glyph at the position.
+    pub fn text<P>(&mut self, font: &'a BitmapFont, position: P, text: &str)
+    where
+        P: Into<Coordinate>,
+    {
+        self.push(Command::Text {
+            font,
+            position: position.into(),
+            text: text.to_string(),
+        });
+    }
+
+    /// Record a command, it will be drawn on top of all previously recorded commands.
+    pub fn push(&mut self, command: Command<'a>) {
+        self.commands.push(command);
+    }
+
+    /// Recorded commands in the order they are drawn.
+    pub fn commands(&self) -> &[Command<'a>] {
+        &self.commands
+    }
+
+    /// Amount of recorded commands.
+    pub fn len(&self) -> usize {
+        self.commands.len()
+    }
+
+    /// Whether no commands are recorded.
+    pub fn is_empty(&self) -> bool {
+        self.commands.is_empty()
+    }
+
+    /// Remove all recorded commands.
+    pub fn clear(&mut self) {
+        self.commands.clear();
+    }
+
+    /// Draw all recorded commands on the destination in the order they were recorded.
+    pub fn replay(&self, dst: &mut [u32], dst_size: Size) {
+        self.commands
+            .iter()
+            .for_each(|command| command.execute(dst, dst_size));
+    }
+
+    /// Draw all recorded commands on a canvas in the order they were recorded.
+    ///
+    /// Together with [`Canvas::split_rows`] the same commands can be replayed on every band from a different thread.
+    pub fn replay_canvas(&self, canvas: &mut Canvas<'_>) {
+        self.commands
+            .iter()
+            .for_each(|command| command.execute_canvas(canvas));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn replay() {
+        let sprite =
+            BlitBuffer::from_buffer(&[0xFF_00_00_01, 0, 0xFF_00_00_02, 0xFF_00_00_03], 2, 127);
+        let font = BitmapFont::new(
+            BlitBuffer::from_buffer(&[0xFF_00_00_04, 0xFF_00_00_05], 2, 127),
+            Size::new(1, 1),
+            'a',
+        );
+        let dst_size = Size::new(4, 3);
+
+        let mut commands = CommandBuffer::new();
+        commands.fill_rect((1, 0, 3, 3), 0xFF_00_00_09);
+        commands.blit(&sprite, BlitOptions::new_position(2, 1));
+        commands.text(&font, (0, 2), "ba");
+        assert_eq!(commands.len(), 3);
+
+        let mut expected = [0; 12];
+        draw::fill_rect(&mut expected, dst_size, (1, 0, 3, 3), 0xFF_00_00_09);
+        sprite.blit(&mut expected, dst_size, &BlitOptions::new_position(2, 1));
+        font.draw(&mut expected, dst_size, (0, 2), "ba");
+
+        // Replaying multiple times on different destinations gives the same result
+        for _ in 0..2 {
+            let mut buffer = [0; 12];
+            commands.replay(&mut buffer, dst_size);
+            assert_eq!(buffer, expected);
+        }
+
+        commands.clear();
+        assert!(commands.is_empty());
+    }
+}
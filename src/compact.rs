@@ -0,0 +1,223 @@
+//! Compact serialization of a [`BlitBuffer`] with the transparent pixels run-length encoded.
+//!
+//! The default serialization of a [`BlitBuffer`] writes every pixel, this format only writes the pixels that are not fully transparent.
+//! It's opt-in by using this module with `#[serde(with = "blit::compact")]`:
+//!
+//! ```rust
+//! use blit::BlitBuffer;
+//! use serde::{Deserialize, Serialize};
+//!
+//! #[derive(Serialize, Deserialize)]
+//! struct Sprite {
+//!     #[serde(with = "blit::compact")]
+//!     buffer: BlitBuffer,
+//! }
+//!
+//! let sprite = Sprite {
+//!     buffer: BlitBuffer::from_buffer(&[0, 0, 0, 0xFF_FF_FF_FF], 2, 127),
+//! };
+//! let json = serde_json::to_string(&sprite).unwrap();
+//! assert_eq!(
+//!     json,
+//!     r#"{"buffer":{"version":1,"width":2,"height":2,"runs":[3,1],"pixels":[4294967295]}}"#
+//! );
+//!
+//! let deserialized: Sprite = serde_json::from_str(&json).unwrap();
+//! assert_eq!(deserialized.buffer.pixels(), sprite.buffer.pixels());
+//! ```
+
+use serde::{de::Error as _, Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::{BlitBuffer, Color, SerializedBlitBuffer, Size};
+
+/// Version of the format, increased when the format changes.
+const VERSION: u8 = 1;
+
+/// Maximum amount of pixels of a deserialized buffer, bigger buffers are rejected.
+///
+/// A single transparent run can describe any amount of pixels, so without a limit a tiny input could make the decoder allocate gigabytes.
+pub const MAX_PIXELS: u64 = 1 << 28;
+
+/// Serialized representation.
+#[derive(Serialize, Deserialize)]
+struct Compact {
+    /// Version of the format, see [`VERSION`].
+    version: u8,
+    /// Width of the buffer in pixels.
+    width: u32,
+    /// Height of the buffer in pixels.
+    height: u32,
+    /// Alternating amounts of transparent and non-transparent pixels, starting with transparent pixels.
+    runs: Vec<u32>,
+    /// All non-transparent pixels.
+    pixels: Vec<Color>,
+}
+
+/// Serialize the buffer in the compact format.
+pub fn serialize<S>(buffer: &BlitBuffer, serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    let mut runs = Vec::new();
+    let mut pixels = Vec::new();
+
+    // The colors of fully transparent pixels are cleared when deserializing, so they don't have to be written
+    let mut transparent = true;
+    let mut run = 0;
+    for pixel in buffer.pixels() {
+        if (*pixel >> 24 == 0) != transparent {
+            runs.push(run);
+            transparent = !transparent;
+            run = 0;
+        }
+        if !transparent {
+            pixels.push(*pixel);
+        }
+        run += 1;
+    }
+    runs.push(run);
+
+    Compact {
+        version: VERSION,
+        width: buffer.width(),
+        height: buffer.height(),
+        runs,
+        pixels,
+    }
+    .serialize(serializer)
+}
+
+/// Deserialize a buffer serialized in the compact format.
+pub fn deserialize<'de, D>(deserializer: D) -> Result<BlitBuffer, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let compact = Compact::deserialize(deserializer)?;
+    if compact.version != VERSION {
+        return Err(D::Error::custom(format!(
+            "unsupported compact buffer version {}",
+            compact.version
+        )));
+    }
+
+    // Neither the size nor the runs can be trusted, they must match and stay within the limit before the pixels are decoded
+    let size = Size::new(compact.width, compact.height);
+    if size.width as u64 * size.height as u64 > MAX_PIXELS {
+        return Err(D::Error::custom(format!(
+            "compact buffer of {}x{} pixels is bigger than the maximum of {MAX_PIXELS} pixels",
+            size.width, size.height
+        )));
+    }
+    let total = compact.runs.iter().map(|run| *run as u64).sum::<u64>();
+    if total != size.width as u64 * size.height as u64 {
+        return Err(D::Error::custom(
+            "compact buffer runs don't add up to the size",
+        ));
+    }
+    let opaque = compact
+        .runs
+        .iter()
+        .skip(1)
+        .step_by(2)
+        .map(|run| *run as u64)
+        .sum::<u64>();
+    if opaque != compact.pixels.len() as u64 {
+        return Err(D::Error::custom(
+            "compact buffer runs don't match the amount of pixels",
+        ));
+    }
+
+    let mut data = Vec::with_capacity(size.pixels());
+    let mut pixels = compact.pixels.into_iter();
+    for (index, run) in compact.runs.into_iter().enumerate() {
+        let run = run as usize;
+        if index % 2 == 0 {
+            data.resize(data.len() + run, 0);
+        } else {
+            data.extend(pixels.by_ref().take(run));
+        }
+    }
+
+    // Go through the same checks as the default format, so both produce identical buffers
+    BlitBuffer::try_from(SerializedBlitBuffer { size, data }).map_err(D::Error::custom)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Serialize, Deserialize)]
+    struct Sprite {
+        #[serde(with = "crate::compact")]
+        buffer: BlitBuffer,
+    }
+
+    #[test]
+    fn round_trip() {
+        let buffer = BlitBuffer::from_buffer_with_alpha(
+            &[0xFF_00_00_01, 0, 0, 0x00_00_00_02, 0x80_00_00_03, 0],
+            3,
+        );
+        let json = serde_json::to_string(&Sprite {
+            buffer: buffer.clone(),
+        })
+        .unwrap();
+
+        let sprite: Sprite = serde_json::from_str(&json).unwrap();
+        assert_eq!(sprite.buffer.size(), buffer.size());
+        assert_eq!(
+            sprite.buffer.pixels(),
+            [0xFF_00_00_01, 0, 0, 0, 0x80_00_00_03, 0]
+        );
+
+        // Invalid data is an error instead of a broken buffer
+        for invalid in [
+            r#"{"buffer":{"version":2,"width":1,"height":1,"runs":[1],"pixels":[]}}"#,
+            r#"{"buffer":{"version":1,"width":1,"height":1,"runs":[0,2],"pixels":[1,1]}}"#,
+            r#"{"buffer":{"version":1,"width":2,"height":1,"runs":[0,1],"pixels":[1]}}"#,
+            r#"{"buffer":{"version":1,"width":1,"height":1,"runs":[0,1],"pixels":[1,1]}}"#,
+            // A huge size in the header doesn't allocate anything
+            r#"{"buffer":{"version":1,"width":4294967295,"height":4294967295,"runs":[3,1],"pixels":[1]}}"#,
+            // Runs covering a size above the limit are rejected before they are decoded
+            r#"{"buffer":{"version":1,"width":65536,"height":65536,"runs":[4294967295,1],"pixels":[1]}}"#,
+            r#"{"buffer":{"version":1,"width":6,"height":1,"runs":[1,5],"pixels":[1]}}"#,
+        ] {
+            assert!(
+                serde_json::from_str::<Sprite>(invalid).is_err(),
+                "{invalid}"
+            );
+        }
+    }
+
+    #[test]
+    fn same_as_default() {
+        // Invisible pixels with a color are cleared by both formats
+        let buffer = BlitBuffer::from_buffer_with_alpha(
+            &[0x00_12_34_56, 0xFF_00_00_01, 0, 0x00_00_00_02],
+            2,
+        );
+        let compact: Sprite = serde_json::from_str(
+            &serde_json::to_string(&Sprite {
+                buffer: buffer.clone(),
+            })
+            .unwrap(),
+        )
+        .unwrap();
+        let default: BlitBuffer =
+            serde_json::from_str(&serde_json::to_string(&buffer).unwrap()).unwrap();
+        assert_eq!(compact.buffer.pixels(), [0, 0xFF_00_00_01, 0, 0]);
+        assert_eq!(compact.buffer.pixels(), default.pixels());
+        assert_eq!(compact.buffer.opaque_runs, default.opaque_runs);
+    }
+
+    #[test]
+    fn max_pixels() {
+        let json = |width: u64| {
+            format!(
+                r#"{{"buffer":{{"version":1,"width":{width},"height":1,"runs":[{width}],"pixels":[]}}}}"#
+            )
+        };
+        assert!(serde_json::from_str::<Sprite>(&json(MAX_PIXELS + 1)).is_err());
+        assert!(serde_json::from_str::<Sprite>(&json(4)).is_ok());
+    }
+}
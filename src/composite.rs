@@ -0,0 +1,280 @@
+//! Stack of buffers flattened into a single buffer.
+//!
+//! # Example
+//!
+//! ```rust
+//! use blit::{composite::Composite, Blit, BlitBuffer, BlitOptions, geom::Size};
+//!
+//! let body = BlitBuffer::from_buffer(&[0xFF_00_00_FF; 4], 2, 127);
+//! let hat = BlitBuffer::from_buffer(&[0xFF_FF_00_00; 2], 2, 127);
+//!
+//! // Layers are drawn in the order they are pushed
+//! let mut character = Composite::new(Size::new(2, 3));
+//! character.push(body, (0, 1));
+//! let hat = character.push(hat, (0, 0));
+//!
+//! // The layers are only flattened again after a layer is changed
+//! let mut canvas = [0; 2 * 3];
+//! character.flatten().blit(&mut canvas, Size::new(2, 3), &BlitOptions::new());
+//! assert_eq!(canvas[0], 0xFF_FF_00_00);
+//!
+//! character.set_visible(hat, false);
+//! assert_eq!(character.flatten().pixels()[0], 0);
+//! ```
+
+use crate::{geom::Coordinate, BlendMode, Blit, BlitBuffer, BlitOptions, Size};
+
+/// Single buffer in the stack.
+#[derive(Debug, Clone)]
+struct Layer {
+    /// Pixels of the layer.
+    buffer: BlitBuffer,
+    /// Position of the layer in the flattened buffer.
+    offset: Coordinate,
+    /// Whether the layer is drawn when flattening.
+    visible: bool,
+}
+
+/// List of buffers at an offset drawn on top of each other into a single cached buffer.
+///
+/// Useful for paper-doll characters or UI panels that are built from multiple parts but drawn as a whole.
+/// The layers are blended with [`BlendMode::Alpha`] in the order they are pushed, so translucent layers are kept translucent in the flattened buffer.
+#[derive(Debug, Clone)]
+pub struct Composite {
+    /// Size of the flattened buffer.
+    size: Size,
+    /// Layers in the order they are drawn.
+    layers: Vec<Layer>,
+    /// Result of the last flattening.
+    flattened: BlitBuffer,
+    /// Whether a layer changed after the last flattening.
+    changed: bool,
+}
+
+impl Composite {
+    /// Create an empty stack which flattens into a buffer of the size.
+    pub fn new<S>(size: S) -> Self
+    where
+        S: Into<Size>,
+    {
+        let size = size.into();
+
+        Self {
+            size,
+            layers: Vec::new(),
+            flattened: Self::empty(size),
+            changed: false,
+        }
+    }
+
+    /// Size of the flattened buffer.
+    pub fn size(&self) -> Size {
+        self.size
+    }
+
+    /// Add a layer on top of all other layers, returns the index of the layer.
+    pub fn push<C>(&mut self, buffer: BlitBuffer, offset: C) -> usize
+    where
+        C: Into<Coordinate>,
+    {
+        self.layers.push(Layer {
+            buffer,
+            offset: offset.into(),
+            visible: true,
+        });
+        self.changed = true;
+
+        self.layers.len() - 1
+    }
+
+    /// Remove a layer, the layers above it move down an index.
+    ///
+    /// # Panics
+    ///
+    /// When the index is out of bounds.
+    pub fn remove(&mut self, index: usize) -> BlitBuffer {
+        self.changed = true;
+
+        self.layers.remove(index).buffer
+    }
+
+    /// Amount of layers.
+    pub fn len(&self) -> usize {
+        self.layers.len()
+    }
+
+    /// Whether there are no layers.
+    pub fn is_empty(&self) -> bool {
+        self.layers.is_empty()
+    }
+
+    /// Buffer of a layer.
+    pub fn layer(&self, index: usize) -> Option<&BlitBuffer> {
+        self.layers.get(index).map(|layer| &layer.buffer)
+    }
+
+    /// Mutable buffer of a layer, the layers will be flattened again.
+    pub fn layer_mut(&mut self, index: usize) -> Option<&mut BlitBuffer> {
+        let layer = self.layers.get_mut(index)?;
+        self.changed = true;
+
+        Some(&mut layer.buffer)
+    }
+
+    /// Replace the buffer of a layer, returning the previous buffer.
+    ///
+    /// # Panics
+    ///
+    /// When the index is out of bounds.
+    pub fn set_layer(&mut self, index: usize, buffer: BlitBuffer) -> BlitBuffer {
+        self.changed = true;
+
+        std::mem::replace(&mut self.layers[index].buffer, buffer)
+    }
+
+    /// Move a layer.
+    ///
+    /// # Panics
+    ///
+    /// When the index is out of bounds.
+    pub fn set_offset<C>(&mut self, index: usize, offset: C)
+    where
+        C: Into<Coordinate>,
+    {
+        let offset = offset.into();
+        let layer = &mut self.layers[index];
+        if layer.offset != offset {
+            layer.offset = offset;
+            self.changed = true;
+        }
+    }
+
+    /// Show or hide a layer.
+    ///
+    /// # Panics
+    ///
+    /// When the index is out of bounds.
+    pub fn set_visible(&mut self, index: usize, visible: bool) {
+        let layer = &mut self.layers[index];
+        if layer.visible != visible {
+            layer.visible = visible;
+            self.changed = true;
+        }
+    }
+
+    /// Whether a layer is shown, `None` when the index is out of bounds.
+    pub fn is_visible(&self, index: usize) -> Option<bool> {
+        self.layers.get(index).map(|layer| layer.visible)
+    }
+
+    /// Get the flattened buffer, the visible layers are only drawn again when something changed.
+    pub fn flatten(&mut self) -> &BlitBuffer {
+        if self.changed {
+            // Reuse the pixels of the previous flattening
+            let pixels = &mut self.flattened.data;
+            pixels.fill(0);
+            self.layers
+                .iter()
+                .filter(|layer| layer.visible)
+                .for_each(|layer| {
+                    layer.buffer.blit(
+                        pixels,
+                        self.size,
+                        &BlitOptions::new_position(layer.offset.x, layer.offset.y)
+                            .with_blend(BlendMode::Alpha),
+                    )
+                });
+
+            self.flattened.update_opaque_runs();
+            self.changed = false;
+        }
+
+        &self.flattened
+    }
+
+    /// Fully transparent buffer.
+    fn empty(size: Size) -> BlitBuffer {
+        BlitBuffer {
+            size,
+            data: vec![0; size.pixels()],
+            opaque_runs: Vec::new(),
+            row_runs: Vec::new(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn flatten() {
+        let first = BlitBuffer::from_buffer_with_alpha(&[0xFF_00_00_01, 0, 0xFF_00_00_01, 0], 2);
+        let second = BlitBuffer::from_buffer_with_alpha(&[0xFF_00_00_02; 2], 1);
+        let size = Size::new(3, 3);
+
+        let mut composite = Composite::new(size);
+        assert!(composite.flatten().pixels().iter().all(|pixel| *pixel == 0));
+
+        composite.push(first.clone(), (0, 0));
+        let top = composite.push(second.clone(), (1, 1));
+        assert_eq!(composite.len(), 2);
+
+        // Same as drawing every layer in order
+        let expected = |layers: &[(&BlitBuffer, (i32, i32))]| {
+            let mut pixels = [0; 9];
+            for (buffer, (x, y)) in layers {
+                buffer.blit(
+                    &mut pixels,
+                    size,
+                    &BlitOptions::new_position(*x, *y).with_blend(BlendMode::Alpha),
+                );
+            }
+
+            pixels
+        };
+        assert_eq!(
+            composite.flatten().pixels(),
+            expected(&[(&first, (0, 0)), (&second, (1, 1))])
+        );
+
+        composite.set_offset(top, (2, -1));
+        assert_eq!(
+            composite.flatten().pixels(),
+            expected(&[(&first, (0, 0)), (&second, (2, -1))])
+        );
+
+        composite.set_visible(0, false);
+        assert_eq!(composite.is_visible(0), Some(false));
+        assert_eq!(
+            composite.flatten().pixels(),
+            expected(&[(&second, (2, -1))])
+        );
+
+        assert_eq!(composite.remove(top).pixels(), second.pixels());
+        assert!(composite.flatten().pixels().iter().all(|pixel| *pixel == 0));
+    }
+
+    #[test]
+    fn flatten_runs() {
+        let size = Size::new(3, 2);
+        let mut composite = Composite::new(size);
+        let layer = composite.push(BlitBuffer::from_buffer(&[0xFF_00_00_01; 2], 2, 127), (0, 0));
+        composite.push(BlitBuffer::from_buffer(&[0xFF_00_00_02], 1, 127), (0, 0));
+        composite.flatten();
+        composite.flattened.set_cached_rows(true);
+
+        // Both the runs and the cached rows follow the flattened pixels
+        composite.set_offset(layer, (1, 1));
+        let flattened = composite.flatten();
+        assert_eq!(flattened.opaque_runs, [0..1, 4..6]);
+        assert_eq!(flattened.row_runs, [0, 1, 2]);
+
+        let mut result = [0; 6];
+        flattened.blit(&mut result, size, &BlitOptions::new());
+        assert_eq!(
+            result,
+            [0xFF_00_00_02, 0, 0, 0, 0xFF_00_00_01, 0xFF_00_00_01]
+        );
+    }
+}
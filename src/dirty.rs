@@ -0,0 +1,160 @@
+//! Track which parts of the destination changed so only those need to be presented.
+//!
+//! # Example
+//!
+//! ```rust
+//! use blit::{dirty::DirtyTracker, BlitBuffer, BlitOptions, geom::{Size, SubRect}};
+//!
+//! let sprite = BlitBuffer::from_buffer(&[0xFF_FF_FF_FF; 4], 2, 127);
+//! let dst_size = Size::new(10, 10);
+//! let mut canvas = [0; 100];
+//!
+//! let mut tracker = DirtyTracker::new();
+//! tracker.blit(&sprite, &mut canvas, dst_size, &BlitOptions::new_position(1, 1));
+//! tracker.blit(&sprite, &mut canvas, dst_size, &BlitOptions::new_position(2, 2));
+//! tracker.blit(&sprite, &mut canvas, dst_size, &BlitOptions::new_position(7, 7));
+//!
+//! // The overlapping rectangles are merged
+//! assert_eq!(tracker.rects(), [SubRect::new(1, 1, (3, 3)), SubRect::new(7, 7, (2, 2))]);
+//!
+//! for _rect in tracker.take() {
+//!     // Push only this part of the canvas to the screen
+//! }
+//! assert!(tracker.is_empty());
+//! ```
+
+use crate::{geom::SubRect, Blit, BlitBuffer, BlitOptions, Size};
+
+/// Accumulates the rectangles of the destination touched by multiple blits.
+///
+/// Overlapping rectangles are merged into their bounding rectangle, so the same pixels are never reported twice.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct DirtyTracker {
+    /// Damaged regions, none of them overlap.
+    rects: Vec<SubRect>,
+}
+
+impl DirtyTracker {
+    /// Create a tracker without any damaged regions.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Draw the buffer like [`Blit::blit`] and mark the part of the destination that got drawn as damaged.
+    ///
+    /// Returns the rectangle that got drawn, see [`BlitBuffer::effective_rect`].
+    pub fn blit(
+        &mut self,
+        source: &BlitBuffer,
+        dst: &mut [u32],
+        dst_size: Size,
+        options: &BlitOptions,
+    ) -> Option<SubRect> {
+        let rect = source.effective_rect(dst_size, options)?;
+        source.blit(dst, dst_size, options);
+        self.add(rect);
+
+        Some(rect)
+    }
+
+    /// Mark a rectangle of the destination as damaged.
+    ///
+    /// Empty rectangles are ignored.
+    pub fn add<R>(&mut self, rect: R)
+    where
+        R: Into<SubRect>,
+    {
+        let mut rect = rect.into();
+        if rect.width() == 0 || rect.height() == 0 {
+            return;
+        }
+
+        // Keep merging until the rectangle doesn't overlap with any other, a merged rectangle can overlap new ones
+        while let Some(index) = self.rects.iter().position(|other| rect.overlaps(*other)) {
+            rect = rect.union(self.rects.swap_remove(index));
+        }
+
+        self.rects.push(rect);
+    }
+
+    /// All damaged regions, none of them overlap.
+    pub fn rects(&self) -> &[SubRect] {
+        &self.rects
+    }
+
+    /// Smallest rectangle containing all damaged regions.
+    ///
+    /// Returns `None` when nothing is damaged.
+    pub fn bounding_rect(&self) -> Option<SubRect> {
+        self.rects.iter().copied().reduce(|a, b| a.union(b))
+    }
+
+    /// Whether nothing is damaged.
+    pub fn is_empty(&self) -> bool {
+        self.rects.is_empty()
+    }
+
+    /// Remove all damaged regions.
+    pub fn clear(&mut self) {
+        self.rects.clear();
+    }
+
+    /// Remove and return all damaged regions, meant to be called once every frame after presenting.
+    pub fn take(&mut self) -> Vec<SubRect> {
+        std::mem::take(&mut self.rects)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn merge() {
+        let mut tracker = DirtyTracker::new();
+
+        tracker.add((0, 0, 2, 2));
+        tracker.add((5, 5, 2, 2));
+        // Touching but not overlapping
+        tracker.add((2, 0, 1, 1));
+        tracker.add((0, 0, 0, 5));
+        assert_eq!(tracker.rects().len(), 3);
+
+        // Overlaps with all rectangles, merged into a single one
+        tracker.add((1, 0, 5, 6));
+        assert_eq!(tracker.rects(), [SubRect::new(0, 0, (7, 7))]);
+        assert_eq!(tracker.bounding_rect(), Some(SubRect::new(0, 0, (7, 7))));
+
+        assert_eq!(tracker.take().len(), 1);
+        assert!(tracker.is_empty());
+        assert_eq!(tracker.bounding_rect(), None);
+    }
+
+    #[test]
+    fn blit() {
+        let sprite = BlitBuffer::from_buffer(&[0xFF_FF_FF_FF; 4], 2, 127);
+        let dst_size = Size::new(4, 4);
+        let mut canvas = [0; 16];
+
+        let mut tracker = DirtyTracker::new();
+        assert_eq!(
+            tracker.blit(
+                &sprite,
+                &mut canvas,
+                dst_size,
+                &BlitOptions::new_position(3, -1)
+            ),
+            Some(SubRect::new(3, 0, (1, 1)))
+        );
+        assert_eq!(
+            tracker.blit(
+                &sprite,
+                &mut canvas,
+                dst_size,
+                &BlitOptions::new_position(5, 0)
+            ),
+            None
+        );
+        assert_eq!(tracker.rects(), [SubRect::new(3, 0, (1, 1))]);
+    }
+}
@@ -0,0 +1,363 @@
+//! Simple shapes drawn directly on a destination buffer.
+//!
+//! Pixels are set to the color without blending, everything outside of the destination is clipped like with blitting.
+//!
+//! # Example
+//!
+//! ```rust
+//! use blit::{draw, geom::Size};
+//!
+//! let size = Size::new(4, 4);
+//! let mut buffer = [0; 16];
+//!
+//! draw::draw_rect(&mut buffer, size, (0, 0, 4, 4), 0xFF_FF_FF_FF);
+//! draw::draw_line(&mut buffer, size, (1, 1), (2, 2), 0xFF_FF_00_00);
+//!
+//! let white = 0xFF_FF_FF_FF;
+//! let red = 0xFF_FF_00_00;
+//! assert_eq!(
+//!     buffer,
+//!     [
+//!         white, white, white, white,
+//!         white, red, 0, white,
+//!         white, 0, red, white,
+//!         white, white, white, white,
+//!     ]
+//! );
+//! ```
+
+use num_traits::ToPrimitive;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    geom::{Coordinate, SubRect},
+    view::ImageView,
+    Size,
+};
+
+/// Colors interpolated over a rectangle, all channels including alpha are interpolated.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum Gradient {
+    /// From the color at the left edge to the color at the right edge.
+    Horizontal {
+        /// Color of the left edge.
+        from: u32,
+        /// Color of the right edge.
+        to: u32,
+    },
+    /// From the color at the top edge to the color at the bottom edge.
+    Vertical {
+        /// Color of the top edge.
+        from: u32,
+        /// Color of the bottom edge.
+        to: u32,
+    },
+    /// From the color at the center to the color at the ellipse touching the edges, the corners outside of the ellipse get the outer color.
+    Radial {
+        /// Color of the center.
+        inner: u32,
+        /// Color of the ellipse touching the edges and everything outside of it.
+        outer: u32,
+    },
+}
+
+impl Gradient {
+    /// Color at a position relative to the rectangle.
+    fn color(&self, x: u32, y: u32, size: Size) -> u32 {
+        // Position along the length, where the last pixel is the full weight
+        let weight = |position: u32, length: u32| {
+            if length <= 1 {
+                0
+            } else {
+                position * 0xFF / (length - 1)
+            }
+        };
+
+        match *self {
+            Gradient::Horizontal { from, to } => lerp(from, to, weight(x, size.width)),
+            Gradient::Vertical { from, to } => lerp(from, to, weight(y, size.height)),
+            Gradient::Radial { inner, outer } => {
+                // Distance of the center of the pixel to the center of the rectangle relative to the ellipse
+                let relative = |position: u32, length: u32| {
+                    (position as f32 + 0.5 - length as f32 / 2.0) / (length as f32 / 2.0)
+                };
+                let (relative_x, relative_y) = (relative(x, size.width), relative(y, size.height));
+                let distance = (relative_x * relative_x + relative_y * relative_y)
+                    .sqrt()
+                    .min(1.0);
+
+                lerp(inner, outer, (distance * 255.0).round() as u32)
+            }
+        }
+    }
+}
+
+/// Set all pixels inside of the rectangle.
+pub fn fill_rect<R>(dst: &mut [u32], dst_size: Size, rect: R, color: u32)
+where
+    R: Into<SubRect>,
+{
+    let Some(view) = ImageView::full(dst_size).sub(rect) else {
+        return;
+    };
+
+    view.parent_ranges_iter(dst_size)
+        .for_each(|range| dst[range].fill(color));
+}
+
+/// Set the pixels on the edges of the rectangle, the outline is a single pixel wide and inside of the rectangle.
+pub fn draw_rect<R>(dst: &mut [u32], dst_size: Size, rect: R, color: u32)
+where
+    R: Into<SubRect>,
+{
+    let rect = rect.into();
+    let (width, height) = (rect.width(), rect.height());
+    if width == 0 || height == 0 {
+        return;
+    }
+
+    // Top and bottom edges
+    fill_rect(dst, dst_size, (rect.x, rect.y, width, 1), color);
+    fill_rect(dst, dst_size, (rect.x, rect.bottom() - 1, width, 1), color);
+
+    // Left and right edges without the corners
+    let side_height = height.saturating_sub(2);
+    fill_rect(dst, dst_size, (rect.x, rect.y + 1, 1, side_height), color);
+    fill_rect(
+        dst,
+        dst_size,
+        (rect.right() - 1, rect.y + 1, 1, side_height),
+        color,
+    );
+}
+
+/// Set the pixels of a line between both points, including the points themselves.
+pub fn draw_line<S, E>(dst: &mut [u32], dst_size: Size, start: S, end: E, color: u32)
+where
+    S: Into<Coordinate>,
+    E: Into<Coordinate>,
+{
+    let (start, end) = (start.into(), end.into());
+
+    // Bresenham's line algorithm, handling all octants
+    let (delta_x, delta_y) = ((end.x - start.x).abs(), -(end.y - start.y).abs());
+    let (step_x, step_y) = ((end.x - start.x).signum(), (end.y - start.y).signum());
+    let (mut x, mut y) = (start.x, start.y);
+    let mut error = delta_x + delta_y;
+    loop {
+        set_pixel(dst, dst_size, x, y, color);
+
+        if x == end.x && y == end.y {
+            break;
+        }
+
+        let double_error = error * 2;
+        if double_error >= delta_y {
+            error += delta_y;
+            x += step_x;
+        }
+        if double_error <= delta_x {
+            error += delta_x;
+            y += step_y;
+        }
+    }
+}
+
+/// Set the pixels of the outline of a circle.
+pub fn draw_circle<C, R>(dst: &mut [u32], dst_size: Size, center: C, radius: R, color: u32)
+where
+    C: Into<Coordinate>,
+    R: ToPrimitive,
+{
+    let center = center.into();
+    let radius = radius.to_i32().unwrap_or_default();
+
+    // Midpoint circle algorithm, every point is mirrored to all eight octants
+    let (mut x, mut y) = (radius, 0);
+    let mut error = 1 - radius;
+    while x >= y {
+        for (offset_x, offset_y) in [
+            (x, y),
+            (y, x),
+            (-y, x),
+            (-x, y),
+            (-x, -y),
+            (-y, -x),
+            (y, -x),
+            (x, -y),
+        ] {
+            set_pixel(
+                dst,
+                dst_size,
+                center.x + offset_x,
+                center.y + offset_y,
+                color,
+            );
+        }
+
+        y += 1;
+        if error < 0 {
+            error += 2 * y + 1;
+        } else {
+            x -= 1;
+            error += 2 * (y - x) + 1;
+        }
+    }
+}
+
+/// Set all pixels inside of the rectangle to the gradient, only drawing the pixels inside of the mask when it's set.
+///
+/// The gradient always spans the full rectangle, clipping doesn't change the colors of the pixels drawn.
+///
+/// ```rust
+/// # use blit::{draw::{self, Gradient}, geom::Size};
+/// let mut buffer = [0; 3];
+/// let gradient = Gradient::Horizontal { from: 0xFF_00_00_00, to: 0xFF_FF_FF_FF };
+///
+/// draw::fill_gradient(&mut buffer, Size::new(3, 1), (0, 0, 3, 1), gradient, None);
+/// assert_eq!(buffer, [0xFF_00_00_00, 0xFF_7F_7F_7F, 0xFF_FF_FF_FF]);
+/// ```
+pub fn fill_gradient<R>(
+    dst: &mut [u32],
+    dst_size: Size,
+    rect: R,
+    gradient: Gradient,
+    mask: Option<SubRect>,
+) where
+    R: Into<SubRect>,
+{
+    let rect = rect.into();
+    let Some(mut view) = ImageView::full(dst_size).sub(rect) else {
+        return;
+    };
+    if let Some(mask) = mask {
+        view = view.clip(mask);
+        if view.width() == 0 || view.height() == 0 {
+            return;
+        }
+    }
+
+    let (view_x, view_y) = view.coord();
+    view.parent_ranges_iter(dst_size)
+        .zip(view_y..)
+        .for_each(|(range, y)| {
+            dst[range].iter_mut().zip(view_x..).for_each(|(pixel, x)| {
+                *pixel = gradient.color((x - rect.x) as u32, (y - rect.y) as u32, rect.size)
+            });
+        });
+}
+
+/// Interpolate every channel of both colors, a weight of `0xFF` is the second color.
+fn lerp(from: u32, to: u32, weight: u32) -> u32 {
+    let channel = |shift: u32| {
+        let (from, to) = ((from >> shift) & 0xFF, (to >> shift) & 0xFF);
+
+        ((from * (0xFF - weight) + to * weight) / 0xFF) << shift
+    };
+
+    channel(24) | channel(16) | channel(8) | channel(0)
+}
+
+/// Set a single pixel when it's inside of the destination.
+fn set_pixel(dst: &mut [u32], dst_size: Size, x: i32, y: i32, color: u32) {
+    if x >= 0 && y >= 0 && x < dst_size.width as i32 && y < dst_size.height as i32 {
+        dst[y as usize * dst_size.width as usize + x as usize] = color;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Draw with the function and return the pixels as characters for easy comparison.
+    fn render(draw: impl FnOnce(&mut [u32], Size)) -> Vec<String> {
+        let size = Size::new(7, 7);
+        let mut buffer = [0; 7 * 7];
+        draw(&mut buffer, size);
+
+        buffer
+            .chunks_exact(7)
+            .map(|row| {
+                row.iter()
+                    .map(|pixel| if *pixel == 0 { '.' } else { '#' })
+                    .collect()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn clipped() {
+        assert_eq!(
+            render(|dst, size| fill_rect(dst, size, (-2, 5, 4, 9), 1)),
+            [".......", ".......", ".......", ".......", ".......", "##.....", "##....."]
+        );
+        assert_eq!(
+            render(|dst, size| draw_rect(dst, size, (4, -1, 5, 4), 1)),
+            ["....#..", "....#..", "....###", ".......", ".......", ".......", "......."]
+        );
+        assert_eq!(
+            render(|dst, size| draw_line(dst, size, (-2, 6), (8, 1), 1)),
+            [".......", ".......", ".....##", "...##..", ".##....", "#......", "......."]
+        );
+    }
+
+    #[test]
+    fn gradient() {
+        let (black, white) = (0xFF_00_00_00, 0xFF_FF_FF_FF);
+
+        // The colors don't change by clipping
+        let mut buffer = [0; 4 * 2];
+        fill_gradient(
+            &mut buffer,
+            Size::new(4, 2),
+            (-1, 0, 5, 3),
+            Gradient::Vertical {
+                from: black,
+                to: white,
+            },
+            Some(SubRect::new(1, 0, (9, 9))),
+        );
+        assert_eq!(
+            buffer,
+            [
+                0,
+                black,
+                black,
+                black,
+                0,
+                0xFF_7F_7F_7F,
+                0xFF_7F_7F_7F,
+                0xFF_7F_7F_7F
+            ]
+        );
+
+        let mut buffer = [0; 3 * 3];
+        fill_gradient(
+            &mut buffer,
+            Size::new(3, 3),
+            (0, 0, 3, 3),
+            Gradient::Radial {
+                inner: white,
+                outer: black,
+            },
+            None,
+        );
+        assert_eq!(buffer[4], white);
+        assert_eq!(buffer[1], 0xFF_55_55_55);
+        assert_eq!(buffer[0], 0xFF_0F_0F_0F);
+    }
+
+    #[test]
+    fn circle() {
+        assert_eq!(
+            render(|dst, size| draw_circle(dst, size, (3, 3), 3, 1)),
+            ["..###..", ".#...#.", "#.....#", "#.....#", "#.....#", ".#...#.", "..###.."]
+        );
+        assert_eq!(
+            render(|dst, size| draw_circle(dst, size, (0, 0), 0, 1)),
+            ["#......", ".......", ".......", ".......", ".......", ".......", "......."]
+        );
+    }
+}
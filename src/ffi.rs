@@ -0,0 +1,378 @@
+//! C API for drawing buffers from other languages.
+//!
+//! Buffers are passed as opaque pointers created with [`blit_buffer_new`] and freed with [`blit_buffer_free`].
+//! The options are a plain struct, [`FfiBlitOptions`], which can be created with [`blit_options_default`] and changed directly or with the `blit_options_*` functions.
+//!
+//! Build the crate as a static or dynamic library to link it, for example with `cargo rustc --release --features ffi --crate-type staticlib`.
+
+use crate::{
+    geom::{Size, SubRect},
+    BlendMode, Blit, BlitBuffer, BlitOptions, Rotation, ScaleMode,
+};
+
+/// Overwrite the destination with every pixel that's not fully transparent, see [`BlendMode::Mask`].
+pub const BLIT_BLEND_MASK: u32 = 0;
+/// Blend using the alpha channel, see [`BlendMode::Alpha`].
+pub const BLIT_BLEND_ALPHA: u32 = 1;
+/// Add the colors, see [`BlendMode::Add`].
+pub const BLIT_BLEND_ADD: u32 = 2;
+/// Multiply the colors, see [`BlendMode::Multiply`].
+pub const BLIT_BLEND_MULTIPLY: u32 = 3;
+/// Inverse multiply the colors, see [`BlendMode::Screen`].
+pub const BLIT_BLEND_SCREEN: u32 = 4;
+/// Subtract the colors, see [`BlendMode::Subtract`].
+pub const BLIT_BLEND_SUBTRACT: u32 = 5;
+
+/// Rectangle with a position and a size in pixels, see [`SubRect`].
+#[repr(C)]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct FfiRect {
+    /// X position in pixels.
+    pub x: i32,
+    /// Y position in pixels.
+    pub y: i32,
+    /// Width in pixels.
+    pub width: u32,
+    /// Height in pixels.
+    pub height: u32,
+}
+
+impl From<FfiRect> for SubRect {
+    fn from(rect: FfiRect) -> Self {
+        SubRect::new(rect.x, rect.y, (rect.width, rect.height))
+    }
+}
+
+/// Options for drawing a buffer, see [`BlitOptions`] for what every option does.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FfiBlitOptions {
+    /// X position on the destination.
+    pub x: i32,
+    /// Y position on the destination.
+    pub y: i32,
+    /// Width of the area on the destination, when the width or the height is zero the size of the source is used.
+    pub area_width: u32,
+    /// Height of the area on the destination, when the width or the height is zero the size of the source is used.
+    pub area_height: u32,
+    /// Whether only the sub rectangle of the source is drawn.
+    pub has_sub_rect: bool,
+    /// Rectangle of the source to draw, only used when `has_sub_rect` is set.
+    pub sub_rect: FfiRect,
+    /// Whether only pixels inside of the mask on the destination are drawn.
+    pub has_mask: bool,
+    /// Rectangle on the destination, only used when `has_mask` is set.
+    pub mask: FfiRect,
+    /// One of the `BLIT_BLEND_*` constants, unknown values use [`BLIT_BLEND_MASK`].
+    pub blend: u32,
+    /// Opacity from `0.0` to `1.0`.
+    pub opacity: f32,
+    /// Mirror the source horizontally.
+    pub flip_horizontal: bool,
+    /// Mirror the source vertically.
+    pub flip_vertical: bool,
+    /// Amount of quarter turns to rotate clockwise.
+    pub quarter_turns: u32,
+    /// Stretch the source to fill the area instead of tiling it.
+    pub stretch: bool,
+}
+
+impl From<&FfiBlitOptions> for BlitOptions {
+    fn from(options: &FfiBlitOptions) -> Self {
+        let area = Size::new(options.area_width, options.area_height);
+
+        BlitOptions {
+            x: options.x,
+            y: options.y,
+            area: (area.width > 0 && area.height > 0).then_some(area),
+            sub_rect: options.has_sub_rect.then(|| options.sub_rect.into()),
+            mask: options.has_mask.then(|| options.mask.into()),
+            blend: match options.blend {
+                BLIT_BLEND_ALPHA => BlendMode::Alpha,
+                BLIT_BLEND_ADD => BlendMode::Add,
+                BLIT_BLEND_MULTIPLY => BlendMode::Multiply,
+                BLIT_BLEND_SCREEN => BlendMode::Screen,
+                BLIT_BLEND_SUBTRACT => BlendMode::Subtract,
+                _ => BlendMode::Mask,
+            },
+            opacity: Some(options.opacity),
+            flip_horizontal: options.flip_horizontal,
+            flip_vertical: options.flip_vertical,
+            rotation: match options.quarter_turns % 4 {
+                1 => Rotation::Clockwise90,
+                2 => Rotation::Clockwise180,
+                3 => Rotation::Clockwise270,
+                _ => Rotation::None,
+            },
+            scale: if options.stretch {
+                ScaleMode::Stretch
+            } else {
+                ScaleMode::Tile
+            },
+            ..Default::default()
+        }
+    }
+}
+
+/// Options drawing the full source at the top left of the destination.
+#[no_mangle]
+pub extern "C" fn blit_options_default() -> FfiBlitOptions {
+    FfiBlitOptions {
+        x: 0,
+        y: 0,
+        area_width: 0,
+        area_height: 0,
+        has_sub_rect: false,
+        sub_rect: FfiRect::default(),
+        has_mask: false,
+        mask: FfiRect::default(),
+        blend: BLIT_BLEND_MASK,
+        opacity: 1.0,
+        flip_horizontal: false,
+        flip_vertical: false,
+        quarter_turns: 0,
+        stretch: false,
+    }
+}
+
+/// Set the position on the destination.
+///
+/// # Safety
+///
+/// The options must be null or point to valid options.
+#[no_mangle]
+pub unsafe extern "C" fn blit_options_set_position(options: *mut FfiBlitOptions, x: i32, y: i32) {
+    if let Some(options) = options.as_mut() {
+        options.x = x;
+        options.y = y;
+    }
+}
+
+/// Set the size of the area on the destination.
+///
+/// # Safety
+///
+/// The options must be null or point to valid options.
+#[no_mangle]
+pub unsafe extern "C" fn blit_options_set_area(
+    options: *mut FfiBlitOptions,
+    width: u32,
+    height: u32,
+) {
+    if let Some(options) = options.as_mut() {
+        options.area_width = width;
+        options.area_height = height;
+    }
+}
+
+/// Only draw a rectangle of the source.
+///
+/// # Safety
+///
+/// The options must be null or point to valid options.
+#[no_mangle]
+pub unsafe extern "C" fn blit_options_set_sub_rect(options: *mut FfiBlitOptions, rect: FfiRect) {
+    if let Some(options) = options.as_mut() {
+        options.has_sub_rect = true;
+        options.sub_rect = rect;
+    }
+}
+
+/// Only draw inside of a rectangle on the destination.
+///
+/// # Safety
+///
+/// The options must be null or point to valid options.
+#[no_mangle]
+pub unsafe extern "C" fn blit_options_set_mask(options: *mut FfiBlitOptions, rect: FfiRect) {
+    if let Some(options) = options.as_mut() {
+        options.has_mask = true;
+        options.mask = rect;
+    }
+}
+
+/// Create a buffer from `0xAARRGGBB` pixels, pixels with an alpha below the threshold become transparent.
+///
+/// Returns null when the pointer is null or when the amount of pixels is not a multiple of the width.
+///
+/// # Safety
+///
+/// The pixels must point to `len` readable pixels.
+#[no_mangle]
+pub unsafe extern "C" fn blit_buffer_new(
+    pixels: *const u32,
+    len: usize,
+    width: u32,
+    alpha_threshold: u8,
+) -> *mut BlitBuffer {
+    match pixels_slice(pixels, len, width) {
+        Some(pixels) => Box::into_raw(Box::new(BlitBuffer::from_buffer(
+            pixels,
+            width,
+            alpha_threshold,
+        ))),
+        None => std::ptr::null_mut(),
+    }
+}
+
+/// Create a buffer from `0xAARRGGBB` pixels keeping the full alpha channel.
+///
+/// Returns null when the pointer is null or when the amount of pixels is not a multiple of the width.
+///
+/// # Safety
+///
+/// The pixels must point to `len` readable pixels.
+#[no_mangle]
+pub unsafe extern "C" fn blit_buffer_new_with_alpha(
+    pixels: *const u32,
+    len: usize,
+    width: u32,
+) -> *mut BlitBuffer {
+    match pixels_slice(pixels, len, width) {
+        Some(pixels) => Box::into_raw(Box::new(BlitBuffer::from_buffer_with_alpha(pixels, width))),
+        None => std::ptr::null_mut(),
+    }
+}
+
+/// Free a buffer, null is ignored.
+///
+/// # Safety
+///
+/// The buffer must be null or created by one of the `blit_buffer_new` functions and not freed before.
+#[no_mangle]
+pub unsafe extern "C" fn blit_buffer_free(buffer: *mut BlitBuffer) {
+    if !buffer.is_null() {
+        drop(Box::from_raw(buffer));
+    }
+}
+
+/// Width of the buffer in pixels, zero for null.
+///
+/// # Safety
+///
+/// The buffer must be null or a valid buffer.
+#[no_mangle]
+pub unsafe extern "C" fn blit_buffer_width(buffer: *const BlitBuffer) -> u32 {
+    buffer.as_ref().map_or(0, BlitBuffer::width)
+}
+
+/// Height of the buffer in pixels, zero for null.
+///
+/// # Safety
+///
+/// The buffer must be null or a valid buffer.
+#[no_mangle]
+pub unsafe extern "C" fn blit_buffer_height(buffer: *const BlitBuffer) -> u32 {
+    buffer.as_ref().map_or(0, BlitBuffer::height)
+}
+
+/// Draw the buffer on a destination of `dst_width * dst_height` pixels, null options use [`blit_options_default`].
+///
+/// Returns false without drawing when the buffer or the destination is null.
+///
+/// # Safety
+///
+/// The buffer must be valid, the destination must point to `dst_width * dst_height` writable pixels and the options must be null or valid.
+#[no_mangle]
+pub unsafe extern "C" fn blit_buffer_blit(
+    buffer: *const BlitBuffer,
+    dst: *mut u32,
+    dst_width: u32,
+    dst_height: u32,
+    options: *const FfiBlitOptions,
+) -> bool {
+    let Some(buffer) = buffer.as_ref() else {
+        return false;
+    };
+    if dst.is_null() {
+        return false;
+    }
+
+    let dst_size = Size::new(dst_width, dst_height);
+    let dst = std::slice::from_raw_parts_mut(dst, dst_size.pixels());
+    let options = match options.as_ref() {
+        Some(options) => BlitOptions::from(options),
+        None => BlitOptions::from(&blit_options_default()),
+    };
+    buffer.blit(dst, dst_size, &options);
+
+    true
+}
+
+/// Borrow the pixels when they form whole rows.
+///
+/// # Safety
+///
+/// The pixels must be null or point to `len` readable pixels.
+unsafe fn pixels_slice<'a>(pixels: *const u32, len: usize, width: u32) -> Option<&'a [u32]> {
+    if pixels.is_null() || width == 0 || !len.is_multiple_of(width as usize) {
+        return None;
+    }
+
+    Some(std::slice::from_raw_parts(pixels, len))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn blit() {
+        let pixels = [0xFF_00_00_01, 0, 0xFF_00_00_02, 0xFF_00_00_03];
+        let mut options = blit_options_default();
+        assert_eq!(
+            BlitOptions::from(&options),
+            BlitOptions::new().with_opacity(1.0)
+        );
+
+        unsafe {
+            assert!(blit_buffer_new(pixels.as_ptr(), 3, 2, 127).is_null());
+            assert!(blit_buffer_new(std::ptr::null(), 0, 2, 127).is_null());
+
+            let buffer = blit_buffer_new(pixels.as_ptr(), pixels.len(), 2, 127);
+            assert_eq!(
+                (blit_buffer_width(buffer), blit_buffer_height(buffer)),
+                (2, 2)
+            );
+
+            blit_options_set_position(&mut options, 1, 0);
+            blit_options_set_area(&mut options, 4, 2);
+            blit_options_set_mask(
+                &mut options,
+                FfiRect {
+                    x: 0,
+                    y: 0,
+                    width: 3,
+                    height: 2,
+                },
+            );
+            options.quarter_turns = 5;
+            options.stretch = true;
+
+            let mut dst = [0; 8];
+            assert!(blit_buffer_blit(buffer, dst.as_mut_ptr(), 4, 2, &options));
+
+            let mut expected = [0; 8];
+            BlitBuffer::from_buffer(&pixels, 2, 127).blit(
+                &mut expected,
+                Size::new(4, 2),
+                &BlitOptions::new_position(1, 0)
+                    .with_area((4, 2))
+                    .with_mask((0, 0, 3, 2))
+                    .with_rotation(Rotation::Clockwise90)
+                    .with_scale(ScaleMode::Stretch),
+            );
+            assert_eq!(dst, expected);
+            assert!(dst.iter().any(|pixel| *pixel != 0));
+
+            assert!(!blit_buffer_blit(
+                std::ptr::null(),
+                dst.as_mut_ptr(),
+                4,
+                2,
+                &options
+            ));
+            blit_buffer_free(buffer);
+        }
+    }
+}
@@ -0,0 +1,105 @@
+//! Filters for sampling sources that are scaled.
+
+use crate::Color;
+
+/// Two neighboring pixels along a single axis and how much the second one contributes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct Sample {
+    /// Offset of the first pixel.
+    pub first: usize,
+    /// Offset of the second pixel, the same as the first at the edges.
+    pub second: usize,
+    /// Weight of the second pixel in the range `0..256`.
+    pub weight: u32,
+}
+
+impl Sample {
+    /// Find the pixels to interpolate between for a position on the area when stretching the source length over the area length.
+    pub fn new(position: u32, area_length: u32, src_length: u32) -> Self {
+        // Center of the destination pixel mapped to the source in 1/256th of a pixel, shifted so pixel centers have no weight
+        let center = ((2 * position as u64 + 1) * src_length as u64 * 256
+            / (2 * area_length as u64))
+            .saturating_sub(128);
+
+        let last = src_length as usize - 1;
+        let first = ((center >> 8) as usize).min(last);
+        let second = (first + 1).min(last);
+        let weight = (center & 0xFF) as u32;
+
+        Self {
+            first,
+            second,
+            weight,
+        }
+    }
+}
+
+/// Interpolate between the top left, top right, bottom left and bottom right pixels.
+///
+/// The colors are weighted by their alpha so transparent pixels don't darken the edges.
+pub(crate) fn bilinear(pixels: [Color; 4], weight_x: u32, weight_y: u32) -> Color {
+    // The weights together are always 256 * 256
+    let weights = [
+        (256 - weight_x) * (256 - weight_y),
+        weight_x * (256 - weight_y),
+        (256 - weight_x) * weight_y,
+        weight_x * weight_y,
+    ];
+    let alpha_weights =
+        [0, 1, 2, 3].map(|index| weights[index] as u64 * (pixels[index] >> 24) as u64);
+
+    let alpha_sum = alpha_weights.iter().sum::<u64>();
+    if alpha_sum == 0 {
+        return 0;
+    }
+
+    let channel = |shift: u32| -> Color {
+        let sum = alpha_weights
+            .iter()
+            .zip(pixels)
+            .map(|(weight, pixel)| weight * ((pixel >> shift) & 0xFF) as u64)
+            .sum::<u64>();
+
+        ((sum / alpha_sum) as Color) << shift
+    };
+
+    (((alpha_sum >> 16) as Color) << 24) | channel(16) | channel(8) | channel(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sample() {
+        // Upscaling interpolates between the pixels, clamped at the edges
+        assert_eq!(
+            (0..4).map(|x| Sample::new(x, 4, 2)).collect::<Vec<_>>(),
+            [(0, 1, 0), (0, 1, 64), (0, 1, 192), (1, 1, 64)].map(|(first, second, weight)| {
+                Sample {
+                    first,
+                    second,
+                    weight,
+                }
+            })
+        );
+
+        // Same size maps to the pixels exactly
+        assert!((0..8).all(|x| Sample::new(x, 8, 8).weight == 0));
+    }
+
+    #[test]
+    fn bilinear_alpha() {
+        // Transparent pixels don't contribute their color
+        assert_eq!(
+            bilinear([0xFF_FF_00_00, 0, 0xFF_FF_00_00, 0], 128, 128),
+            0x7F_FF_00_00
+        );
+
+        assert_eq!(bilinear([0; 4], 128, 128), 0);
+        assert_eq!(
+            bilinear([0xFF_00_00_00, 0xFF_00_00_FF, 0, 0], 64, 0),
+            0xFF_00_00_3F
+        );
+    }
+}
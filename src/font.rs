@@ -0,0 +1,499 @@
+//! Text drawn with glyphs from an image.
+//!
+//! # Example
+//!
+//! ```rust
+//! use blit::{font::BitmapFont, BlitBuffer, geom::Size};
+//!
+//! // Two glyphs of 1x2 pixels, 'A' is the full glyph and 'B' only has the bottom pixel
+//! let image = BlitBuffer::from_buffer(&[0xFF_FF_FF_FF, 0, 0xFF_FF_FF_FF, 0xFF_FF_FF_FF], 2, 127);
+//! let font = BitmapFont::new(image, Size::new(1, 2), 'A');
+//!
+//! let mut buffer = [0; 3 * 4];
+//! font.draw(&mut buffer, Size::new(3, 4), (0, 0), "AB\n B");
+//!
+//! let white = 0xFF_FF_FF_FF;
+//! assert_eq!(
+//!     buffer,
+//!     [
+//!         white, 0, 0,
+//!         white, white, 0,
+//!         0, 0, 0,
+//!         0, white, 0,
+//!     ]
+//! );
+//! ```
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    canvas::Canvas,
+    geom::{Coordinate, SubRect},
+    Blit, BlitBuffer, BlitOptions, Size,
+};
+
+/// Amount of glyphs between tab stops.
+const TAB_GLYPHS: i32 = 4;
+
+/// Horizontal spacing of a single glyph, for fonts where not every glyph is as wide as the glyph size.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct GlyphMetrics {
+    /// Horizontal offset in pixels from the cursor to the left of the glyph.
+    pub offset: i32,
+    /// Amount of pixels the cursor moves to the right after drawing the glyph.
+    pub advance: u32,
+}
+
+impl GlyphMetrics {
+    /// Construct new metrics.
+    pub fn new(offset: i32, advance: u32) -> Self {
+        Self { offset, advance }
+    }
+}
+
+/// Horizontal alignment of wrapped lines.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum Align {
+    /// Lines start at the left of the width.
+    #[default]
+    Left,
+    /// Lines are centered in the width.
+    Center,
+    /// Lines end at the right of the width.
+    Right,
+}
+
+/// Font with all glyphs the same size in a grid on an image.
+///
+/// The glyphs are ordered left to right and top to bottom, starting with the first character, every following glyph is the next character.
+/// By default every glyph is as wide as the glyph size, proportional fonts can set the width of every glyph with [`BitmapFont::with_metrics`] or [`BitmapFont::with_detected_metrics`].
+#[derive(Debug, Clone)]
+pub struct BitmapFont {
+    /// Image containing all glyphs.
+    buffer: BlitBuffer,
+    /// Size of a single glyph in pixels.
+    glyph_size: Size,
+    /// Character of the first glyph in the image.
+    first_char: char,
+    /// Amount of glyphs on a single row of the image.
+    columns: u32,
+    /// Amount of glyphs in the image.
+    glyphs: u32,
+    /// Spacing of every glyph in the image.
+    metrics: Vec<GlyphMetrics>,
+}
+
+impl BitmapFont {
+    /// Create a font from an image with the glyphs in a grid.
+    ///
+    /// Glyphs that don't fully fit in the image are not drawn.
+    pub fn new(buffer: BlitBuffer, glyph_size: Size, first_char: char) -> Self {
+        let (columns, rows) = if glyph_size.width == 0 || glyph_size.height == 0 {
+            (0, 0)
+        } else {
+            (
+                buffer.width() / glyph_size.width,
+                buffer.height() / glyph_size.height,
+            )
+        };
+
+        let glyphs = columns * rows;
+        let metrics = vec![GlyphMetrics::new(0, glyph_size.width); glyphs as usize];
+
+        Self {
+            buffer,
+            glyph_size,
+            first_char,
+            columns,
+            glyphs,
+            metrics,
+        }
+    }
+
+    /// Set the spacing of the glyphs, the first metrics are for the first character.
+    ///
+    /// Glyphs without metrics in the list keep their previous spacing.
+    #[must_use]
+    pub fn with_metrics(mut self, metrics: &[GlyphMetrics]) -> Self {
+        self.metrics
+            .iter_mut()
+            .zip(metrics)
+            .for_each(|(glyph_metrics, metrics)| *glyph_metrics = *metrics);
+
+        self
+    }
+
+    /// Set the spacing of the glyphs by scanning the image for the transparent columns on both sides of every glyph.
+    ///
+    /// Every glyph is drawn from its first non-transparent column and advances to its last non-transparent column with the extra spacing.
+    /// Fully transparent glyphs, like a space, keep their previous spacing.
+    #[must_use]
+    pub fn with_detected_metrics(mut self, spacing: u32) -> Self {
+        for index in 0..self.glyphs {
+            let rect = self.glyph_rect_by_index(index);
+
+            // Find the first and last column with any non-transparent pixel
+            let is_visible = |column: i32| {
+                (rect.y..rect.bottom()).any(|y| {
+                    let index = y as usize * self.buffer.width() as usize + column as usize;
+
+                    self.buffer.pixels()[index] >> 24 > 0
+                })
+            };
+            let Some(left) = (rect.x..rect.right()).find(|column| is_visible(*column)) else {
+                continue;
+            };
+            let right = (rect.x..rect.right())
+                .rev()
+                .find(|column| is_visible(*column))
+                .unwrap_or(left);
+
+            self.metrics[index as usize] =
+                GlyphMetrics::new(rect.x - left, (right - left + 1) as u32 + spacing);
+        }
+
+        self
+    }
+
+    /// Image containing all glyphs.
+    pub fn buffer(&self) -> &BlitBuffer {
+        &self.buffer
+    }
+
+    /// Size of a single glyph in pixels.
+    pub fn glyph_size(&self) -> Size {
+        self.glyph_size
+    }
+
+    /// Character of the first glyph in the image.
+    pub fn first_char(&self) -> char {
+        self.first_char
+    }
+
+    /// Spacing of the glyph of the character, `None` when the character has no glyph.
+    pub fn glyph_metrics(&self, ch: char) -> Option<GlyphMetrics> {
+        self.glyph_index(ch)
+            .map(|index| self.metrics[index as usize])
+    }
+
+    /// Draw the text with the top left of the first glyph at the position.
+    ///
+    /// A newline moves to the start of the next line, a tab moves to the next tab stop of four times the glyph width and characters without a glyph are drawn as empty space of the glyph width.
+    pub fn draw<P>(&self, dst: &mut [u32], dst_size: Size, position: P, text: &str)
+    where
+        P: Into<Coordinate>,
+    {
+        self.glyphs(position.into(), text)
+            .for_each(|options| self.buffer.blit(dst, dst_size, &options));
+    }
+
+    /// Draw the text on a canvas like [`Self::draw`], the position is relative to the canvas and the glyphs are clipped by it.
+    pub fn draw_canvas<P>(&self, canvas: &mut Canvas<'_>, position: P, text: &str)
+    where
+        P: Into<Coordinate>,
+    {
+        self.glyphs(position.into(), text)
+            .for_each(|options| canvas.blit(&self.buffer, &options));
+    }
+
+    /// Draw the text wrapped to a maximum width in pixels, with every line aligned inside of the width.
+    ///
+    /// Lines are wrapped at spaces, words that are wider than the maximum width are wrapped at any character.
+    /// Returns the amount of lines drawn, including empty lines.
+    ///
+    /// ```rust
+    /// # use blit::{font::{Align, BitmapFont}, BlitBuffer, geom::Size};
+    /// # let font = BitmapFont::new(BlitBuffer::from_buffer(&[0xFF_FF_FF_FF; 26], 26, 127), Size::new(1, 1), 'a');
+    /// let mut buffer = [0; 5 * 3];
+    /// let lines = font.draw_wrapped(&mut buffer, Size::new(5, 3), (0, 0), "hello world", 5, Align::Right);
+    /// assert_eq!(lines, 2);
+    /// ```
+    pub fn draw_wrapped<P>(
+        &self,
+        dst: &mut [u32],
+        dst_size: Size,
+        position: P,
+        text: &str,
+        max_width: u32,
+        align: Align,
+    ) -> usize
+    where
+        P: Into<Coordinate>,
+    {
+        let start = position.into();
+
+        let lines = self.wrap(text, max_width);
+        for (index, line) in lines.iter().enumerate() {
+            let y = start.y + index as i32 * self.glyph_size.height as i32;
+            let free_width = max_width.saturating_sub(self.text_width(line)) as i32;
+            let x = match align {
+                Align::Left => start.x,
+                Align::Center => start.x + free_width / 2,
+                Align::Right => start.x + free_width,
+            };
+
+            self.draw(dst, dst_size, (x, y), line);
+        }
+
+        lines.len()
+    }
+
+    /// Width in pixels of the widest line of the text.
+    pub fn text_width(&self, text: &str) -> u32 {
+        text.split('\n')
+            .map(|line| line.chars().fold(0, |x, ch| self.advance(x, ch)))
+            .max()
+            .unwrap_or_default()
+            .max(0) as u32
+    }
+
+    /// Split the text into lines that fit in the width.
+    fn wrap<'t>(&self, text: &'t str, max_width: u32) -> Vec<&'t str> {
+        let mut lines = Vec::new();
+        for paragraph in text.split('\n') {
+            // Byte range of the words that fit on the current line
+            let (mut start, mut end) = (0, 0);
+
+            let mut word_start = 0;
+            for word in paragraph.split(' ') {
+                let word_end = word_start + word.len();
+                if !word.is_empty() {
+                    if self.text_width(&paragraph[start..word_end]) > max_width {
+                        if end > start {
+                            lines.push(&paragraph[start..end]);
+                        }
+                        start = word_start;
+
+                        // Break the word itself when it's too wide, every line gets at least a single character
+                        while self.text_width(&paragraph[start..word_end]) > max_width {
+                            let mut splits = paragraph[start..word_end]
+                                .char_indices()
+                                .skip(1)
+                                .map(|(index, _)| start + index);
+                            let Some(first_split) = splits.next() else {
+                                break;
+                            };
+
+                            let split = std::iter::once(first_split)
+                                .chain(splits)
+                                .take_while(|split| {
+                                    self.text_width(&paragraph[start..*split]) <= max_width
+                                })
+                                .last()
+                                .unwrap_or(first_split);
+
+                            lines.push(&paragraph[start..split]);
+                            start = split;
+                        }
+                    }
+
+                    end = word_end;
+                }
+
+                // Skip the space
+                word_start = word_end + 1;
+            }
+
+            lines.push(&paragraph[start..end.max(start)]);
+        }
+
+        lines
+    }
+
+    /// Options to draw every visible glyph of the text with the top left of the first glyph at the position.
+    fn glyphs<'t>(
+        &'t self,
+        start: Coordinate,
+        text: &'t str,
+    ) -> impl Iterator<Item = BlitOptions> + 't {
+        let (mut x, mut y) = (0, start.y);
+        text.chars().filter_map(move |ch| {
+            if ch == '\n' {
+                x = 0;
+                y += self.glyph_size.height as i32;
+
+                return None;
+            }
+
+            let options = self.glyph_index(ch).map(|index| {
+                BlitOptions::new_position(start.x + x + self.metrics[index as usize].offset, y)
+                    .with_sub_rect(self.glyph_rect_by_index(index))
+            });
+            x = self.advance(x, ch);
+
+            options
+        })
+    }
+
+    /// Position of the cursor relative to the start of the line after the character.
+    fn advance(&self, x: i32, ch: char) -> i32 {
+        let glyph_width = self.glyph_size.width as i32;
+
+        match ch {
+            '\t' => {
+                let tab_width = glyph_width * TAB_GLYPHS;
+                if tab_width > 0 {
+                    (x / tab_width + 1) * tab_width
+                } else {
+                    x
+                }
+            }
+            _ => {
+                x + self.glyph_index(ch).map_or(glyph_width, |index| {
+                    self.metrics[index as usize].advance as i32
+                })
+            }
+        }
+    }
+
+    /// Index of the glyph in the image, `None` when the character has no glyph.
+    fn glyph_index(&self, ch: char) -> Option<u32> {
+        (ch as u32)
+            .checked_sub(self.first_char as u32)
+            .filter(|index| *index < self.glyphs)
+    }
+
+    /// Rectangle of the glyph in the image.
+    fn glyph_rect_by_index(&self, index: u32) -> SubRect {
+        SubRect::new(
+            (index % self.columns * self.glyph_size.width) as i32,
+            (index / self.columns * self.glyph_size.height) as i32,
+            self.glyph_size,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn draw() {
+        // Grid of 2x2 glyphs with a single pixel each, with the value of the character as a hexadecimal digit
+        let image = BlitBuffer::from_buffer(
+            &[0xFF_00_00_0A, 0xFF_00_00_0B, 0xFF_00_00_0C, 0xFF_00_00_0D],
+            2,
+            127,
+        );
+        let font = BitmapFont::new(image, Size::new(1, 1), 'a');
+        assert_eq!(
+            font.glyph_index('d')
+                .map(|index| font.glyph_rect_by_index(index)),
+            Some(SubRect::new(1, 1, (1, 1)))
+        );
+        assert_eq!(font.glyph_index('e'), None);
+        assert_eq!(font.glyph_index('A'), None);
+
+        let mut buffer = [0; 6 * 3];
+        font.draw(&mut buffer, Size::new(6, 3), (-1, 1), "xab\td\ncdcdcdcd");
+
+        let mut expected = [0; 6 * 3];
+        expected[6] = 0xFF_00_00_0A;
+        expected[6 + 1] = 0xFF_00_00_0B;
+        expected[6 + 3] = 0xFF_00_00_0D;
+        expected[12..].copy_from_slice(&[
+            0xFF_00_00_0D,
+            0xFF_00_00_0C,
+            0xFF_00_00_0D,
+            0xFF_00_00_0C,
+            0xFF_00_00_0D,
+            0xFF_00_00_0C,
+        ]);
+        assert_eq!(buffer, expected);
+    }
+
+    #[test]
+    fn metrics() {
+        // Glyphs of 4x1 pixels: a narrow one in the middle, a full one and an empty one
+        let image = BlitBuffer::from_buffer(
+            &[
+                0,
+                0xFF_00_00_0A,
+                0xFF_00_00_0A,
+                0, //
+                0xFF_00_00_0B,
+                0,
+                0,
+                0xFF_00_00_0B, //
+                0,
+                0,
+                0,
+                0,
+            ],
+            4,
+            127,
+        );
+        let font = BitmapFont::new(image, Size::new(4, 1), 'a').with_detected_metrics(1);
+        assert_eq!(font.glyph_metrics('a'), Some(GlyphMetrics::new(-1, 3)));
+        assert_eq!(font.glyph_metrics('b'), Some(GlyphMetrics::new(0, 5)));
+        assert_eq!(font.glyph_metrics('c'), Some(GlyphMetrics::new(0, 4)));
+        assert_eq!(font.glyph_metrics('d'), None);
+
+        let mut buffer = [0; 12];
+        font.draw(&mut buffer, Size::new(12, 1), (0, 0), "acab");
+        assert_eq!(
+            buffer,
+            [
+                0xFF_00_00_0A,
+                0xFF_00_00_0A,
+                0,
+                0,
+                0,
+                0,
+                0,
+                0xFF_00_00_0A,
+                0xFF_00_00_0A,
+                0,
+                0xFF_00_00_0B,
+                0
+            ]
+        );
+
+        // A table only overwrites the glyphs it contains
+        let font = font.with_metrics(&[GlyphMetrics::new(0, 2)]);
+        assert_eq!(font.glyph_metrics('a'), Some(GlyphMetrics::new(0, 2)));
+        assert_eq!(font.glyph_metrics('b'), Some(GlyphMetrics::new(0, 5)));
+    }
+
+    #[test]
+    fn wrap() {
+        let font = BitmapFont::new(
+            BlitBuffer::from_buffer(&[0xFF_FF_FF_FF; 26], 26, 127),
+            Size::new(1, 1),
+            'a',
+        );
+
+        assert_eq!(
+            font.wrap("ab cd  ef\n\nabcdefg hi", 5),
+            ["ab cd", "ef", "", "abcde", "fg hi"]
+        );
+        assert_eq!(font.wrap("abc", 0), ["a", "b", "c"]);
+        assert_eq!(font.text_width("ab\tc\nabcdefg"), 7);
+        assert_eq!(font.text_width("ab\tc"), 5);
+
+        // Every line is aligned by itself
+        for (align, expected) in [
+            (Align::Left, ["###..", "##...", "#...."]),
+            (Align::Center, ["###..", "##...", ".#..."]),
+            (Align::Right, ["###..", ".##..", "..#.."]),
+        ] {
+            let mut buffer = [0; 5 * 3];
+            let lines =
+                font.draw_wrapped(&mut buffer, Size::new(5, 3), (0, 0), "abc de f", 3, align);
+            assert_eq!(lines, 3);
+
+            let rows = buffer
+                .chunks_exact(5)
+                .map(|row| {
+                    row.iter()
+                        .map(|pixel| if *pixel == 0 { '.' } else { '#' })
+                        .collect::<String>()
+                })
+                .collect::<Vec<_>>();
+            assert_eq!(rows, expected, "{align:?}");
+        }
+    }
+}
@@ -6,6 +6,17 @@ use num_traits::ToPrimitive;
 #[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
 
+/// Convert a position to whole pixels, rounding down so moving across zero doesn't skip a pixel.
+///
+/// Positions that don't fit are clamped and positions that aren't a number become zero.
+/// All positions and coordinates accepting any number are converted with this.
+pub(crate) fn position<T>(value: T) -> i32
+where
+    T: ToPrimitive,
+{
+    value.to_f64().map_or(0, |value| value.floor() as i32)
+}
+
 /// Helper struct for defining sizes.
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -44,14 +55,111 @@ impl Size {
         self.width as usize * self.height as usize
     }
 
-    /// Calculate the size from the length of a buffer and the width.
+    /// Calculate the size from the length of a buffer and the width, a width of zero has no rows.
     pub(crate) const fn from_len(len: usize, width: usize) -> Self {
+        let height = match len.checked_div(width) {
+            Some(height) => height,
+            None => 0,
+        };
+
         Self {
             width: width as u32,
-            height: (len / width) as u32,
+            height: height as u32,
         }
     }
 
+    /// Add both dimensions, `None` when either overflows.
+    pub fn checked_add<S>(&self, rhs: S) -> Option<Self>
+    where
+        S: Into<Size>,
+    {
+        self.checked(rhs.into(), u32::checked_add)
+    }
+
+    /// Subtract both dimensions, `None` when either would become negative.
+    pub fn checked_sub<S>(&self, rhs: S) -> Option<Self>
+    where
+        S: Into<Size>,
+    {
+        self.checked(rhs.into(), u32::checked_sub)
+    }
+
+    /// Multiply both dimensions, `None` when either overflows.
+    pub fn checked_mul<S>(&self, rhs: S) -> Option<Self>
+    where
+        S: Into<Size>,
+    {
+        self.checked(rhs.into(), u32::checked_mul)
+    }
+
+    /// Divide both dimensions, `None` when dividing by zero.
+    pub fn checked_div<S>(&self, rhs: S) -> Option<Self>
+    where
+        S: Into<Size>,
+    {
+        self.checked(rhs.into(), u32::checked_div)
+    }
+
+    /// Remainder of dividing both dimensions, `None` when dividing by zero.
+    pub fn checked_rem<S>(&self, rhs: S) -> Option<Self>
+    where
+        S: Into<Size>,
+    {
+        self.checked(rhs.into(), u32::checked_rem)
+    }
+
+    /// Add both dimensions, clamping at the maximum instead of overflowing.
+    pub fn saturating_add<S>(&self, rhs: S) -> Self
+    where
+        S: Into<Size>,
+    {
+        let rhs = rhs.into();
+
+        Self {
+            width: self.width.saturating_add(rhs.width),
+            height: self.height.saturating_add(rhs.height),
+        }
+    }
+
+    /// Subtract both dimensions, clamping at zero instead of underflowing.
+    ///
+    /// ```rust
+    /// # use blit::geom::Size;
+    /// assert_eq!(Size::new(4, 2).saturating_sub((3, 3)), Size::new(1, 0));
+    /// ```
+    pub fn saturating_sub<S>(&self, rhs: S) -> Self
+    where
+        S: Into<Size>,
+    {
+        let rhs = rhs.into();
+
+        Self {
+            width: self.width.saturating_sub(rhs.width),
+            height: self.height.saturating_sub(rhs.height),
+        }
+    }
+
+    /// Multiply both dimensions, clamping at the maximum instead of overflowing.
+    pub fn saturating_mul<S>(&self, rhs: S) -> Self
+    where
+        S: Into<Size>,
+    {
+        let rhs = rhs.into();
+
+        Self {
+            width: self.width.saturating_mul(rhs.width),
+            height: self.height.saturating_mul(rhs.height),
+        }
+    }
+
+    /// Apply a checked operation to both dimensions.
+    fn checked(&self, rhs: Self, operation: fn(u32, u32) -> Option<u32>) -> Option<Self> {
+        Some(Self {
+            width: operation(self.width, rhs.width)?,
+            height: operation(self.height, rhs.height)?,
+        })
+    }
+
     /// Set the size to the `min()` of another size.
     pub(crate) fn min(&self, other: Self) -> Self {
         Self {
@@ -171,6 +279,126 @@ impl<T: ToPrimitive> Rem<T> for Size {
     }
 }
 
+/// Helper struct for defining positions.
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Coordinate {
+    /// X position in pixels.
+    pub x: i32,
+    /// Y position in pixels.
+    pub y: i32,
+}
+
+impl Coordinate {
+    /// Create a new coordinate.
+    ///
+    /// Fractional positions are rounded down, positions that don't fit are clamped.
+    pub fn new<X, Y>(x: X, y: Y) -> Self
+    where
+        X: ToPrimitive,
+        Y: ToPrimitive,
+    {
+        let (x, y) = (position(x), position(y));
+
+        Self { x, y }
+    }
+
+    /// Tuple of `(x, y)`.
+    pub const fn as_tuple(&self) -> (i32, i32) {
+        (self.x, self.y)
+    }
+
+    /// Add both axes, `None` when either overflows.
+    pub fn checked_add<C>(&self, rhs: C) -> Option<Self>
+    where
+        C: Into<Coordinate>,
+    {
+        let rhs = rhs.into();
+
+        Some(Self {
+            x: self.x.checked_add(rhs.x)?,
+            y: self.y.checked_add(rhs.y)?,
+        })
+    }
+
+    /// Subtract both axes, `None` when either overflows.
+    pub fn checked_sub<C>(&self, rhs: C) -> Option<Self>
+    where
+        C: Into<Coordinate>,
+    {
+        let rhs = rhs.into();
+
+        Some(Self {
+            x: self.x.checked_sub(rhs.x)?,
+            y: self.y.checked_sub(rhs.y)?,
+        })
+    }
+
+    /// Add both axes, clamping at the bounds of `i32` instead of overflowing.
+    pub fn saturating_add<C>(&self, rhs: C) -> Self
+    where
+        C: Into<Coordinate>,
+    {
+        let rhs = rhs.into();
+
+        Self {
+            x: self.x.saturating_add(rhs.x),
+            y: self.y.saturating_add(rhs.y),
+        }
+    }
+
+    /// Subtract both axes, clamping at the bounds of `i32` instead of overflowing.
+    pub fn saturating_sub<C>(&self, rhs: C) -> Self
+    where
+        C: Into<Coordinate>,
+    {
+        let rhs = rhs.into();
+
+        Self {
+            x: self.x.saturating_sub(rhs.x),
+            y: self.y.saturating_sub(rhs.y),
+        }
+    }
+}
+
+impl<X, Y> From<(X, Y)> for Coordinate
+where
+    X: ToPrimitive,
+    Y: ToPrimitive,
+{
+    fn from((x, y): (X, Y)) -> Self {
+        Self::new(x, y)
+    }
+}
+
+impl From<Coordinate> for (i32, i32) {
+    fn from(coord: Coordinate) -> Self {
+        coord.as_tuple()
+    }
+}
+
+impl Add<Coordinate> for Coordinate {
+    type Output = Self;
+
+    fn add(self, rhs: Coordinate) -> Self::Output {
+        Self {
+            x: self.x + rhs.x,
+            y: self.y + rhs.y,
+        }
+    }
+}
+
+impl Sub<Coordinate> for Coordinate {
+    type Output = Self;
+
+    fn sub(self, rhs: Coordinate) -> Self::Output {
+        Self {
+            x: self.x - rhs.x,
+            y: self.y - rhs.y,
+        }
+    }
+}
+
 /// Helper struct for defining sub-rectangles.
 ///
 /// A sub-rectangle is a rectangle that's part of a bigger rectangle.
@@ -187,14 +415,15 @@ pub struct SubRect {
 
 impl SubRect {
     /// Create a new sub-rectangle.
+    ///
+    /// Fractional positions are rounded down, positions that don't fit are clamped.
     pub fn new<X, Y, S>(x: X, y: Y, size: S) -> Self
     where
         X: ToPrimitive,
         Y: ToPrimitive,
         S: Into<Size>,
     {
-        let x = x.to_i32().unwrap_or_default();
-        let y = y.to_i32().unwrap_or_default();
+        let (x, y) = (position(x), position(y));
         let size = size.into();
 
         Self { x, y, size }
@@ -217,8 +446,7 @@ impl SubRect {
         X: ToPrimitive,
         Y: ToPrimitive,
     {
-        let new_x = new_x.to_i32().unwrap_or_default();
-        let new_y = new_y.to_i32().unwrap_or_default();
+        let (new_x, new_y) = (position(new_x), position(new_y));
 
         let (right, bottom) = (self.right(), self.bottom());
         let (x, y) = (new_x.min(right), new_y.min(bottom));
@@ -252,6 +480,110 @@ impl SubRect {
     pub fn as_slice(&self) -> (i32, i32, u32, u32) {
         (self.x, self.y, self.size.width, self.size.height)
     }
+
+    /// Whether the rectangle doesn't contain any pixels.
+    pub fn is_empty(&self) -> bool {
+        self.width() == 0 || self.height() == 0
+    }
+
+    /// Part of the rectangle that's also inside the other rectangle, `None` when they don't overlap.
+    ///
+    /// ```rust
+    /// # use blit::geom::SubRect;
+    /// let rect = SubRect::new(0, 0, (4, 4));
+    ///
+    /// assert_eq!(rect.intersection((2, -1, 4, 2)), Some(SubRect::new(2, 0, (2, 1))));
+    /// assert_eq!(rect.intersection((4, 0, 1, 1)), None);
+    /// ```
+    pub fn intersection<R>(&self, other: R) -> Option<Self>
+    where
+        R: Into<SubRect>,
+    {
+        let rect = self.clamped_to(other.into());
+
+        (!rect.is_empty()).then_some(rect)
+    }
+
+    /// Smallest rectangle containing both rectangles, empty rectangles are ignored.
+    ///
+    /// ```rust
+    /// # use blit::geom::SubRect;
+    /// let rect = SubRect::new(0, 0, (2, 2));
+    ///
+    /// assert_eq!(rect.union((3, -1, 1, 1)), SubRect::new(0, -1, (4, 3)));
+    /// ```
+    pub fn union<R>(&self, other: R) -> Self
+    where
+        R: Into<SubRect>,
+    {
+        let other = other.into();
+        if other.is_empty() {
+            return *self;
+        } else if self.is_empty() {
+            return other;
+        }
+
+        let (x, y) = (self.x.min(other.x), self.y.min(other.y));
+
+        Self::new(
+            x,
+            y,
+            (
+                self.right().max(other.right()) - x,
+                self.bottom().max(other.bottom()) - y,
+            ),
+        )
+    }
+
+    /// Whether the position is inside of the rectangle.
+    pub fn contains_point<C>(&self, position: C) -> bool
+    where
+        C: Into<Coordinate>,
+    {
+        let position = position.into();
+
+        position.x >= self.x
+            && position.y >= self.y
+            && position.x < self.right()
+            && position.y < self.bottom()
+    }
+
+    /// Whether the other rectangle is completely inside of this rectangle.
+    pub fn contains_rect<R>(&self, other: R) -> bool
+    where
+        R: Into<SubRect>,
+    {
+        let other = other.into();
+
+        other.x >= self.x
+            && other.y >= self.y
+            && other.right() <= self.right()
+            && other.bottom() <= self.bottom()
+    }
+
+    /// Whether the rectangles share at least a single pixel.
+    pub fn overlaps<R>(&self, other: R) -> bool
+    where
+        R: Into<SubRect>,
+    {
+        self.intersection(other).is_some()
+    }
+
+    /// Clip the rectangle to fit in the other rectangle.
+    ///
+    /// When they don't overlap the position is moved inside of the other rectangle as much as possible and the size becomes zero.
+    pub(crate) fn clamped_to(&self, other: SubRect) -> Self {
+        let (x, y) = (self.x.max(other.x), self.y.max(other.y));
+
+        Self::new(
+            x,
+            y,
+            (
+                (self.right().min(other.right()) - x).max(0),
+                (self.bottom().min(other.bottom()) - y).max(0),
+            ),
+        )
+    }
 }
 
 impl<X, Y, W, H> From<(X, Y, W, H)> for SubRect
@@ -262,10 +594,302 @@ where
     H: ToPrimitive,
 {
     fn from((x, y, width, height): (X, Y, W, H)) -> Self {
-        let x = x.to_i32().unwrap_or(0);
-        let y = y.to_i32().unwrap_or(0);
+        let (x, y) = (position(x), position(y));
         let size = Size::from((width, height));
 
         Self { x, y, size }
     }
 }
+
+/// Area made of multiple rectangles, used to clip a blit to a shape that's not a single rectangle.
+///
+/// The rectangles never overlap, overlapping parts of added rectangles are removed so every pixel is drawn at most once.
+/// When deserialized the rectangles are added one by one, so overlapping rectangles are split the same way.
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(from = "SerializedClipRegion"))]
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct ClipRegion {
+    /// Rectangles that don't overlap each other.
+    rects: Vec<SubRect>,
+}
+
+/// Unchecked rectangles of a deserialized [`ClipRegion`], they might overlap.
+#[cfg(feature = "serde")]
+#[derive(Deserialize)]
+struct SerializedClipRegion {
+    /// Rectangles in the order they are added.
+    rects: Vec<SubRect>,
+}
+
+#[cfg(feature = "serde")]
+impl From<SerializedClipRegion> for ClipRegion {
+    fn from(SerializedClipRegion { rects }: SerializedClipRegion) -> Self {
+        rects.into_iter().collect()
+    }
+}
+
+impl ClipRegion {
+    /// Create an empty region.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a rectangle to the region.
+    ///
+    /// Only the parts of the rectangle that are not already in the region are added.
+    #[must_use]
+    pub fn with_rect<R>(mut self, rect: R) -> Self
+    where
+        R: Into<SubRect>,
+    {
+        self.add(rect);
+
+        self
+    }
+
+    /// Add a rectangle to the region.
+    ///
+    /// Only the parts of the rectangle that are not already in the region are added.
+    pub fn add<R>(&mut self, rect: R)
+    where
+        R: Into<SubRect>,
+    {
+        let mut parts = vec![rect.into()];
+        for existing in &self.rects {
+            parts = parts
+                .into_iter()
+                .flat_map(|part| Self::subtract(part, *existing))
+                .collect();
+        }
+
+        self.rects.extend(
+            parts
+                .into_iter()
+                .filter(|part| part.width() > 0 && part.height() > 0),
+        );
+    }
+
+    /// Remove a rectangle from the region, making a hole in it.
+    #[must_use]
+    pub fn without_rect<R>(mut self, rect: R) -> Self
+    where
+        R: Into<SubRect>,
+    {
+        self.remove(rect);
+
+        self
+    }
+
+    /// Remove a rectangle from the region, making a hole in it.
+    pub fn remove<R>(&mut self, rect: R)
+    where
+        R: Into<SubRect>,
+    {
+        let rect = rect.into();
+
+        self.rects = self
+            .rects
+            .iter()
+            .flat_map(|existing| Self::subtract(*existing, rect))
+            .filter(|part| part.width() > 0 && part.height() > 0)
+            .collect();
+    }
+
+    /// Rectangles that don't overlap each other.
+    pub fn rects(&self) -> &[SubRect] {
+        &self.rects
+    }
+
+    /// Whether the region doesn't contain any pixels.
+    pub fn is_empty(&self) -> bool {
+        self.rects.is_empty()
+    }
+
+    /// Whether the position is inside of the region.
+    pub fn contains<C>(&self, position: C) -> bool
+    where
+        C: Into<Coordinate>,
+    {
+        let position = position.into();
+
+        self.rects.iter().any(|rect| rect.contains_point(position))
+    }
+
+    /// Parts of the rectangle that are not inside the other rectangle, at most four.
+    fn subtract(rect: SubRect, other: SubRect) -> Vec<SubRect> {
+        // Nothing is removed when they don't overlap
+        if !rect.overlaps(other) {
+            return vec![rect];
+        }
+
+        let mut parts = Vec::with_capacity(4);
+
+        // Full width above and below the other rectangle
+        if other.y > rect.y {
+            parts.push(SubRect::new(
+                rect.x,
+                rect.y,
+                (rect.width(), other.y - rect.y),
+            ));
+        }
+        if other.bottom() < rect.bottom() {
+            parts.push(SubRect::new(
+                rect.x,
+                other.bottom(),
+                (rect.width(), rect.bottom() - other.bottom()),
+            ));
+        }
+
+        // Left and right of the other rectangle in the rows they share
+        let (top, bottom) = (rect.y.max(other.y), rect.bottom().min(other.bottom()));
+        if other.x > rect.x {
+            parts.push(SubRect::new(rect.x, top, (other.x - rect.x, bottom - top)));
+        }
+        if other.right() < rect.right() {
+            parts.push(SubRect::new(
+                other.right(),
+                top,
+                (rect.right() - other.right(), bottom - top),
+            ));
+        }
+
+        parts
+    }
+}
+
+impl<R> FromIterator<R> for ClipRegion
+where
+    R: Into<SubRect>,
+{
+    fn from_iter<I>(iter: I) -> Self
+    where
+        I: IntoIterator<Item = R>,
+    {
+        let mut region = Self::new();
+        iter.into_iter().for_each(|rect| region.add(rect));
+
+        region
+    }
+}
+
+/// View of a world drawn on a rectangle of the screen, converting world positions to screen positions.
+///
+/// ```rust
+/// # use blit::geom::{Camera, Coordinate};
+/// let camera = Camera::new((10, 0, 64, 48)).with_offset((100, 20)).with_zoom(2);
+///
+/// assert_eq!(camera.world_to_screen((101, 20)), Coordinate::new(12, 0));
+/// assert_eq!(camera.screen_to_world((13, 1)), Coordinate::new(101, 20));
+/// assert!(!camera.is_visible((0, 0, 16, 16)));
+/// ```
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Camera {
+    /// World position drawn at the top left of the viewport.
+    pub offset: Coordinate,
+    /// Amount of screen pixels in both directions for every world pixel, zero is treated as one.
+    pub zoom: u32,
+    /// Rectangle on the screen the world is drawn on.
+    pub viewport: SubRect,
+}
+
+impl Camera {
+    /// Create a camera at the origin of the world without zooming.
+    pub fn new<R>(viewport: R) -> Self
+    where
+        R: Into<SubRect>,
+    {
+        Self {
+            offset: Coordinate::default(),
+            zoom: 1,
+            viewport: viewport.into(),
+        }
+    }
+
+    /// Set the world position drawn at the top left of the viewport.
+    #[must_use]
+    pub fn with_offset<C>(mut self, offset: C) -> Self
+    where
+        C: Into<Coordinate>,
+    {
+        self.offset = offset.into();
+
+        self
+    }
+
+    /// Set the amount of screen pixels in both directions for every world pixel.
+    #[must_use]
+    pub fn with_zoom(mut self, zoom: u32) -> Self {
+        self.zoom = zoom;
+
+        self
+    }
+
+    /// Convert a world position to a position on the screen.
+    pub fn world_to_screen<C>(&self, position: C) -> Coordinate
+    where
+        C: Into<Coordinate>,
+    {
+        let position = position.into();
+
+        Coordinate {
+            x: self.viewport.x + (position.x - self.offset.x) * self.scale(),
+            y: self.viewport.y + (position.y - self.offset.y) * self.scale(),
+        }
+    }
+
+    /// Convert a position on the screen to the world position of the pixel that's drawn there.
+    pub fn screen_to_world<C>(&self, position: C) -> Coordinate
+    where
+        C: Into<Coordinate>,
+    {
+        let position = position.into();
+
+        Coordinate {
+            x: self.offset.x + (position.x - self.viewport.x).div_euclid(self.scale()),
+            y: self.offset.y + (position.y - self.viewport.y).div_euclid(self.scale()),
+        }
+    }
+
+    /// Convert a world rectangle to the rectangle on the screen it covers.
+    pub fn world_to_screen_rect<R>(&self, rect: R) -> SubRect
+    where
+        R: Into<SubRect>,
+    {
+        let rect = rect.into();
+        let position = self.world_to_screen((rect.x, rect.y));
+
+        SubRect::new(
+            position.x,
+            position.y,
+            rect.size.saturating_mul((self.scale(), self.scale())),
+        )
+    }
+
+    /// Rectangle of the world that's visible in the viewport, including partially visible pixels.
+    pub fn visible_rect(&self) -> SubRect {
+        let scale = self.scale() as u32;
+
+        SubRect::new(
+            self.offset.x,
+            self.offset.y,
+            (
+                self.viewport.width().div_ceil(scale),
+                self.viewport.height().div_ceil(scale),
+            ),
+        )
+    }
+
+    /// Whether any part of a world rectangle is visible in the viewport.
+    pub fn is_visible<R>(&self, rect: R) -> bool
+    where
+        R: Into<SubRect>,
+    {
+        self.visible_rect().overlaps(rect)
+    }
+
+    /// Zoom as a factor that's at least one.
+    fn scale(&self) -> i32 {
+        self.zoom.max(1) as i32
+    }
+}
@@ -0,0 +1,250 @@
+//! Load animated GIF images into a spritesheet with the duration of every frame.
+//!
+//! The frames are composited the way a browser shows them, so every frame in the spritesheet is a complete image that can be drawn on its own.
+//!
+//! # Example
+//!
+//! ```rust,no_run
+//! use std::time::Duration;
+//!
+//! use blit::{gif::GifAnimation, Blit, BlitOptions, geom::Size};
+//!
+//! let animation = GifAnimation::from_bytes(&std::fs::read("explosion.gif").unwrap()).unwrap();
+//!
+//! // Draw the frame that should be shown after one second
+//! let frame = animation.frame_at(Duration::from_secs(1));
+//! let mut buffer = vec![0; 64 * 64];
+//! animation.buffer().blit(
+//!     &mut buffer,
+//!     Size::new(64, 64),
+//!     &BlitOptions::new().with_sub_rect(animation.frame_rect(frame).unwrap()),
+//! );
+//! ```
+
+use std::{io::Read, time::Duration};
+
+use ::gif::{ColorOutput, DecodeOptions, DecodingError, DisposalMethod};
+
+use crate::{geom::SubRect, rgba_pixels, Blit, BlitBuffer, BlitOptions, Size};
+
+/// Frames of an animated GIF next to each other in a single buffer.
+#[derive(Debug, Clone)]
+pub struct GifAnimation {
+    /// Spritesheet with all frames in a single row.
+    buffer: BlitBuffer,
+    /// Size of a single frame.
+    frame_size: Size,
+    /// How long every frame is shown.
+    durations: Vec<Duration>,
+}
+
+impl GifAnimation {
+    /// Decode a GIF from memory.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, DecodingError> {
+        Self::from_reader(bytes)
+    }
+
+    /// Decode a GIF from a reader, such as a file.
+    ///
+    /// A GIF without any frames results in an empty animation.
+    pub fn from_reader<R>(reader: R) -> Result<Self, DecodingError>
+    where
+        R: Read,
+    {
+        let mut options = DecodeOptions::new();
+        options.set_color_output(ColorOutput::RGBA);
+        let mut decoder = options.read_info(reader)?;
+
+        let frame_size = Size::new(decoder.width(), decoder.height());
+        let width = frame_size.width as usize;
+
+        // Every frame is drawn over the result of the previous frames
+        let mut canvas = vec![0; frame_size.pixels()];
+        let mut frames = Vec::new();
+        let mut durations = Vec::new();
+        while let Some(frame) = decoder.read_next_frame()? {
+            let previous = (frame.dispose == DisposalMethod::Previous).then(|| canvas.clone());
+
+            let pixels = rgba_pixels(&frame.buffer).collect::<Vec<_>>();
+            BlitBuffer::from_buffer_with_alpha(&pixels, frame.width).blit(
+                &mut canvas,
+                frame_size,
+                &BlitOptions::new_position(frame.left, frame.top),
+            );
+
+            frames.push(canvas.clone());
+            // The delay is in hundredths of a second
+            durations.push(Duration::from_millis(frame.delay as u64 * 10));
+
+            match frame.dispose {
+                DisposalMethod::Background => {
+                    // Clear the area of the frame to transparent
+                    let area = SubRect::new(frame.left, frame.top, (frame.width, frame.height))
+                        .intersection(SubRect::from_size(frame_size));
+                    if let Some(area) = area {
+                        for y in area.y as usize..area.bottom() as usize {
+                            canvas[y * width + area.x as usize..y * width + area.right() as usize]
+                                .fill(0);
+                        }
+                    }
+                }
+                DisposalMethod::Previous => {
+                    if let Some(previous) = previous {
+                        canvas = previous;
+                    }
+                }
+                _ => (),
+            }
+        }
+
+        // Place the rows of all frames next to each other
+        let data = (0..frame_size.height as usize)
+            .flat_map(|y| {
+                frames
+                    .iter()
+                    .flat_map(move |frame| &frame[y * width..(y + 1) * width])
+            })
+            .copied()
+            .collect::<Vec<_>>();
+
+        Ok(Self {
+            buffer: BlitBuffer::from_buffer_with_alpha(&data, width * frames.len()),
+            frame_size,
+            durations,
+        })
+    }
+
+    /// Spritesheet with all frames next to each other in a single row.
+    pub fn buffer(&self) -> &BlitBuffer {
+        &self.buffer
+    }
+
+    /// Size of a single frame.
+    pub fn frame_size(&self) -> Size {
+        self.frame_size
+    }
+
+    /// Amount of frames.
+    pub fn len(&self) -> usize {
+        self.durations.len()
+    }
+
+    /// Whether there are no frames.
+    pub fn is_empty(&self) -> bool {
+        self.durations.is_empty()
+    }
+
+    /// Rectangle of a frame in the spritesheet, to be used as [`BlitOptions::sub_rect`].
+    pub fn frame_rect(&self, index: usize) -> Option<SubRect> {
+        (index < self.len())
+            .then(|| SubRect::new(index as u32 * self.frame_size.width, 0, self.frame_size))
+    }
+
+    /// How long a frame is shown.
+    pub fn duration(&self, index: usize) -> Option<Duration> {
+        self.durations.get(index).copied()
+    }
+
+    /// How long all frames are shown together.
+    pub fn total_duration(&self) -> Duration {
+        self.durations.iter().sum()
+    }
+
+    /// Index of the frame shown after the time since the start of the animation, looping forever.
+    ///
+    /// Returns the first frame when all frames have no duration.
+    pub fn frame_at(&self, elapsed: Duration) -> usize {
+        let total = self.total_duration();
+        if total.is_zero() {
+            return 0;
+        }
+
+        // Time in the current loop
+        let mut elapsed = Duration::from_nanos((elapsed.as_nanos() % total.as_nanos()) as u64);
+
+        self.durations
+            .iter()
+            .position(|duration| match elapsed.checked_sub(*duration) {
+                Some(rest) => {
+                    elapsed = rest;
+                    false
+                }
+                None => true,
+            })
+            .unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::borrow::Cow;
+
+    use ::gif::{Encoder, Frame};
+
+    use super::*;
+
+    #[test]
+    fn decode() {
+        let mut bytes = Vec::new();
+        {
+            let mut encoder = Encoder::new(&mut bytes, 2, 1, &[]).unwrap();
+            let palette = vec![0, 0, 0, 0xFF, 0, 0, 0, 0xFF, 0];
+            // Both pixels, cleared after it's shown
+            encoder
+                .write_frame(&Frame {
+                    delay: 10,
+                    dispose: DisposalMethod::Background,
+                    transparent: Some(0),
+                    width: 2,
+                    height: 1,
+                    palette: Some(palette.clone()),
+                    buffer: Cow::Borrowed(&[1, 2]),
+                    ..Frame::default()
+                })
+                .unwrap();
+            // Only the right pixel, the left pixel stays transparent
+            encoder
+                .write_frame(&Frame {
+                    delay: 30,
+                    transparent: Some(0),
+                    left: 1,
+                    width: 1,
+                    height: 1,
+                    palette: Some(palette),
+                    buffer: Cow::Borrowed(&[1]),
+                    ..Frame::default()
+                })
+                .unwrap();
+        }
+
+        let animation = GifAnimation::from_bytes(&bytes).unwrap();
+        assert_eq!(animation.len(), 2);
+        assert_eq!(animation.frame_size(), Size::new(2, 1));
+        assert_eq!(
+            animation.buffer().pixels(),
+            [0xFF_FF_00_00, 0xFF_00_FF_00, 0, 0xFF_FF_00_00]
+        );
+        assert_eq!(animation.frame_rect(1), Some(SubRect::new(2, 0, (2, 1))));
+        assert_eq!(animation.frame_rect(2), None);
+
+        assert_eq!(animation.total_duration(), Duration::from_millis(400));
+        assert_eq!(animation.frame_at(Duration::from_millis(50)), 0);
+        assert_eq!(animation.frame_at(Duration::from_millis(100)), 1);
+        assert_eq!(animation.frame_at(Duration::from_millis(450)), 0);
+
+        assert!(GifAnimation::from_bytes(&bytes[1..]).is_err());
+    }
+
+    #[test]
+    fn no_frames() {
+        // A screen of 2x1 pixels without a palette, a graphic control extension without an image and the trailer
+        let bytes = b"GIF89a\x02\x00\x01\x00\x00\x00\x00\x21\xF9\x04\x00\x0A\x00\x00\x00\x3B";
+
+        let animation = GifAnimation::from_bytes(bytes).unwrap();
+        assert!(animation.is_empty());
+        assert_eq!(animation.frame_size(), Size::new(2, 1));
+        assert!(animation.buffer().pixels().is_empty());
+        assert_eq!(animation.frame_rect(0), None);
+        assert_eq!(animation.frame_at(Duration::from_secs(1)), 0);
+    }
+}
@@ -1,9 +1,9 @@
 use std::ops::Deref;
 
-use image::{ImageBuffer, Pixel};
+use image::{DynamicImage, ImageBuffer, Pixel};
 use num_traits::ToPrimitive;
 
-use crate::{BlitBuffer, Color, ToBlitBuffer};
+use crate::{BlitBuffer, Color, ColorKey, ToBlitBuffer};
 
 impl<P, Container> ToBlitBuffer for ImageBuffer<P, Container>
 where
@@ -11,31 +11,25 @@ where
     Container: Deref<Target = [P::Subpixel]>,
 {
     fn to_blit_buffer_with_mask_color(&self, mask_color: u32) -> BlitBuffer {
-        let (width, _height) = self.dimensions();
+        self.to_blit_buffer_with_color_key(&ColorKey::new(mask_color))
+    }
 
-        // Remove the alpha channel
-        let mask_color = mask_color | 0xFF_00_00_00;
+    fn to_blit_buffer_with_color_key(&self, key: &ColorKey) -> BlitBuffer {
+        let (width, _height) = self.dimensions();
 
-        BlitBuffer::from_iter(
+        BlitBuffer::from_iter_with_color_key(
             self.pixels().map(|pixel| {
                 let pixel = pixel.channels();
 
-                let pixel = color_from_u64(
+                color_from_u64(
                     0xFF,
                     ToPrimitive::to_u64(&pixel[0]).unwrap_or(0x0),
                     ToPrimitive::to_u64(&pixel[1]).unwrap_or(0x0),
                     ToPrimitive::to_u64(&pixel[2]).unwrap_or(0x0),
-                );
-
-                // If the pixel matches the mask color return nothing
-                if pixel == mask_color {
-                    0x0
-                } else {
-                    pixel
-                }
+                )
             }),
             width,
-            127,
+            key,
         )
     }
 
@@ -60,6 +54,30 @@ where
     }
 }
 
+impl ToBlitBuffer for DynamicImage {
+    fn to_blit_buffer_with_mask_color(&self, mask_color: u32) -> BlitBuffer {
+        self.to_blit_buffer_with_color_key(&ColorKey::new(mask_color))
+    }
+
+    fn to_blit_buffer_with_color_key(&self, key: &ColorKey) -> BlitBuffer {
+        match self {
+            DynamicImage::ImageRgb8(image) => image.to_blit_buffer_with_color_key(key),
+            // Only the color channels are read
+            DynamicImage::ImageRgba8(image) => image.to_blit_buffer_with_color_key(key),
+            // Grayscale is expanded and 16-bit and floating point channels are scaled down to 8 bits
+            image => image.to_rgb8().to_blit_buffer_with_color_key(key),
+        }
+    }
+
+    fn to_blit_buffer_with_alpha(&self, alpha_treshold: u8) -> BlitBuffer {
+        match self {
+            DynamicImage::ImageRgba8(image) => image.to_blit_buffer_with_alpha(alpha_treshold),
+            // Images without an alpha channel become fully opaque
+            image => image.to_rgba8().to_blit_buffer_with_alpha(alpha_treshold),
+        }
+    }
+}
+
 /// Convert separate u8 color components into a single packed color.
 ///
 /// The type is `u64` because that's the base conversion type of the `num_traits` crate.
@@ -67,3 +85,30 @@ where
 fn color_from_u64(a: u64, r: u64, g: u64, b: u64) -> Color {
     ((a << 24) | (r << 16) | (g << 8) | b) as Color
 }
+
+#[cfg(test)]
+mod tests {
+    use image::{ImageBuffer, LumaA, Rgb};
+
+    use super::*;
+
+    #[test]
+    fn dynamic_image() {
+        let rgb = ImageBuffer::from_fn(2, 1, |x, _| Rgb([x as u8 * 0xFF, 0x00, 0xFF]));
+        assert_eq!(
+            DynamicImage::ImageRgb8(rgb.clone())
+                .to_blit_buffer_with_mask_color(0xFF_00_FF)
+                .pixels(),
+            rgb.to_blit_buffer_with_mask_color(0xFF_00_FF).pixels()
+        );
+
+        // 16-bit grayscale with alpha
+        let gray = ImageBuffer::from_fn(3, 1, |x, _| LumaA([0x80_80_u16, x as u16 * 0x7F_FF]));
+        assert_eq!(
+            DynamicImage::ImageLumaA16(gray)
+                .to_blit_buffer_with_alpha(0x80)
+                .pixels(),
+            [0, 0, 0xFF_80_80_80]
+        );
+    }
+}
@@ -0,0 +1,319 @@
+//! Buffers storing palette indices instead of colors, so the colors can be swapped without touching the pixels.
+//!
+//! # Example
+//!
+//! ```rust
+//! use blit::{indexed::{IndexedBlitBuffer, Palette}, Blit, BlitOptions, geom::Size};
+//!
+//! // Index 0 is transparent, index 1 is the color of the player
+//! let red = Palette::new().with_color(1, 0xFF_FF_00_00);
+//! let blue = red.clone().with_color(1, 0xFF_00_00_FF);
+//!
+//! let sprite = IndexedBlitBuffer::from_indices(&[0, 1, 1, 0], 2, red);
+//! let mut canvas = [0; 8];
+//!
+//! // Draw the same pixels with both palettes
+//! sprite.blit(&mut canvas, Size::new(4, 2), &BlitOptions::new());
+//! sprite.blit_with_palette(&blue, &mut canvas, Size::new(4, 2), &BlitOptions::new_position(2, 0));
+//! assert_eq!(
+//!     canvas,
+//!     [0, 0xFF_FF_00_00, 0, 0xFF_00_00_FF, 0xFF_FF_00_00, 0, 0xFF_00_00_FF, 0]
+//! );
+//! ```
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use std::ops::{Bound, RangeBounds};
+
+use num_traits::ToPrimitive;
+
+use crate::{Blit, BlitBuffer, BlitOptions, Color, Size};
+
+/// Amount of colors in a palette, every 8-bit index has a color.
+const PALETTE_SIZE: usize = 256;
+
+/// List of 256 colors indexed by the pixels of an [`IndexedBlitBuffer`].
+///
+/// The colors are RGBA packed in a single `u32` with the full alpha channel, like [`BlitBuffer::from_buffer_with_alpha`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Palette {
+    /// Always exactly 256 colors.
+    colors: Vec<Color>,
+}
+
+impl Palette {
+    /// Create a palette with all colors transparent.
+    pub fn new() -> Self {
+        Self {
+            colors: vec![0; PALETTE_SIZE],
+        }
+    }
+
+    /// Create a palette from a list of colors, the first color is index zero.
+    ///
+    /// Only the first 256 colors are used, when there are less the remaining colors are transparent.
+    pub fn from_colors(colors: &[u32]) -> Self {
+        let mut palette = Self::new();
+        colors
+            .iter()
+            .zip(palette.colors.iter_mut())
+            .for_each(|(color, palette_color)| *palette_color = *color);
+
+        palette
+    }
+
+    /// Set the color of a single index.
+    #[must_use]
+    pub fn with_color(mut self, index: u8, color: u32) -> Self {
+        self.set_color(index, color);
+
+        self
+    }
+
+    /// Set the color of a single index.
+    pub fn set_color(&mut self, index: u8, color: u32) {
+        self.colors[index as usize] = color;
+    }
+
+    /// Color of a single index.
+    #[inline(always)]
+    pub fn color(&self, index: u8) -> u32 {
+        self.colors[index as usize]
+    }
+
+    /// All 256 colors.
+    pub fn colors(&self) -> &[u32] {
+        &self.colors
+    }
+
+    /// Rotate the colors of a range of indices by an amount of steps, for animating water or fire without changing the pixels.
+    ///
+    /// With a positive amount every color moves to a higher index and the colors at the end of the range wrap around to the start, negative amounts move the other way.
+    /// Calling this every frame with a single step cycles through all colors of the range.
+    ///
+    /// ```rust
+    /// # use blit::indexed::Palette;
+    /// let mut palette = Palette::from_colors(&[0, 1, 2, 3, 4]);
+    ///
+    /// palette.cycle(1..=3, 1);
+    /// assert_eq!(&palette.colors()[..5], [0, 3, 1, 2, 4]);
+    ///
+    /// palette.cycle(1..=3, -1);
+    /// assert_eq!(&palette.colors()[..5], [0, 1, 2, 3, 4]);
+    /// ```
+    pub fn cycle<R>(&mut self, range: R, steps: i32)
+    where
+        R: RangeBounds<u8>,
+    {
+        let start = match range.start_bound() {
+            Bound::Included(start) => *start as usize,
+            Bound::Excluded(start) => *start as usize + 1,
+            Bound::Unbounded => 0,
+        };
+        let end = match range.end_bound() {
+            Bound::Included(end) => *end as usize + 1,
+            Bound::Excluded(end) => *end as usize,
+            Bound::Unbounded => PALETTE_SIZE,
+        };
+        if start >= end {
+            return;
+        }
+
+        let colors = &mut self.colors[start..end];
+        let steps = steps.rem_euclid(colors.len() as i32) as usize;
+        colors.rotate_right(steps);
+    }
+}
+
+impl Default for Palette {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Buffer of 8-bit palette indices with the palette used for drawing.
+///
+/// The indices are converted to colors while drawing, so the palette can be changed or another palette can be used with [`IndexedBlitBuffer::blit_with_palette`] without copying the pixels.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct IndexedBlitBuffer {
+    /// Image size in pixels.
+    size: Size,
+    /// Palette index of every pixel.
+    indices: Vec<u8>,
+    /// Palette used when drawing with [`Blit::blit`].
+    palette: Palette,
+}
+
+impl IndexedBlitBuffer {
+    /// Create a instance from a buffer of palette indices.
+    pub fn from_indices<S>(indices: &[u8], width: S, palette: Palette) -> Self
+    where
+        S: ToPrimitive,
+    {
+        // We can calculate the height from the total buffer
+        let size = Size::from_len(indices.len(), width.to_usize().unwrap_or_default());
+        let indices = indices.to_vec();
+
+        Self {
+            size,
+            indices,
+            palette,
+        }
+    }
+
+    /// Width of the buffer in pixels.
+    pub fn width(&self) -> u32 {
+        self.size.width
+    }
+
+    /// Height of the buffer in pixels.
+    pub fn height(&self) -> u32 {
+        self.size.height
+    }
+
+    /// Size of the buffer in pixels.
+    pub fn size(&self) -> Size {
+        self.size
+    }
+
+    /// Get a reference to the palette indices.
+    pub fn indices(&self) -> &[u8] {
+        &self.indices
+    }
+
+    /// Get a mutable reference to the palette indices.
+    pub fn indices_mut(&mut self) -> &mut [u8] {
+        &mut self.indices
+    }
+
+    /// Palette used when drawing with [`Blit::blit`].
+    pub fn palette(&self) -> &Palette {
+        &self.palette
+    }
+
+    /// Get a mutable reference to the palette used when drawing with [`Blit::blit`].
+    pub fn palette_mut(&mut self) -> &mut Palette {
+        &mut self.palette
+    }
+
+    /// Replace the palette used when drawing with [`Blit::blit`].
+    pub fn set_palette(&mut self, palette: Palette) {
+        self.palette = palette;
+    }
+
+    /// Convert the indices to a buffer of colors from the palette.
+    pub fn to_blit_buffer(&self, palette: &Palette) -> BlitBuffer {
+        BlitBuffer::from_iter_with_alpha(
+            self.indices.iter().map(|index| palette.color(*index)),
+            self.size.width,
+        )
+    }
+
+    /// Draw the buffer like [`Blit::blit`] with another palette.
+    ///
+    /// Exact blits convert the indices while drawing, other options draw a converted copy.
+    pub fn blit_with_palette(
+        &self,
+        palette: &Palette,
+        dst: &mut [u32],
+        dst_size: Size,
+        options: &BlitOptions,
+    ) {
+        if !options.is_exact(self.size) {
+            self.to_blit_buffer(palette).blit(dst, dst_size, options);
+
+            return;
+        }
+
+        if options.opacity_alpha() == 0 {
+            return;
+        }
+
+        let Some((dst_area, src_x, src_y)) = options.exact_placement(self.size, dst_size) else {
+            return;
+        };
+
+        let (width, row_width) = (self.size.width as usize, dst_area.width() as usize);
+        dst_area
+            .parent_ranges_iter(dst_size)
+            .zip(src_y..)
+            .for_each(|(dst_range, y)| {
+                let start = y * width + src_x;
+                let blit_iter = self.indices[start..start + row_width]
+                    .iter()
+                    .map(|index| palette.color(*index));
+
+                BlitBuffer::blit_pixels(dst[dst_range].iter_mut(), blit_iter, options);
+            });
+    }
+}
+
+impl Blit for IndexedBlitBuffer {
+    fn blit(&self, dst: &mut [u32], dst_size: Size, options: &BlitOptions) {
+        self.blit_with_palette(&self.palette, dst, dst_size, options);
+    }
+
+    fn source_size(&self) -> Option<Size> {
+        Some(self.size)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::BlendMode;
+
+    use super::*;
+
+    #[test]
+    fn cycle() {
+        let mut palette = Palette::from_colors(&(0..=255).collect::<Vec<_>>());
+
+        palette.cycle(250.., 8);
+        assert_eq!(
+            &palette.colors()[248..],
+            [248, 249, 254, 255, 250, 251, 252, 253]
+        );
+
+        palette.cycle(..2, 3);
+        assert_eq!(&palette.colors()[..3], [1, 0, 2]);
+
+        // Empty ranges are ignored
+        let unchanged = palette.clone();
+        palette.cycle(5..5, 1);
+        assert_eq!(palette, unchanged);
+    }
+
+    #[test]
+    fn same_as_buffer() {
+        let indices = (0..36).map(|i| (i % 5) as u8).collect::<Vec<_>>();
+        let palette = Palette::from_colors(&[0, 0xFF_11_22_33, 0x80_44_55_66, 0xFF_77_88_99]);
+        let indexed = IndexedBlitBuffer::from_indices(&indices, 6, palette.clone());
+        let buffer = indexed.to_blit_buffer(&palette);
+        let dst_size = Size::new(10, 10);
+
+        // Index 4 is outside of the colors so it's transparent
+        assert_eq!(buffer.pixels()[4], 0);
+
+        for (x, y) in [(2, 2), (-3, 1), (7, -2), (12, 0)] {
+            for options in [
+                BlitOptions::new_position(x, y),
+                BlitOptions::new_position(x, y).with_mask((1, 2, 6, 5)),
+                BlitOptions::new_position(x, y).with_sub_rect((1, 2, 4, 3)),
+                BlitOptions::new_position(x, y).with_blend(BlendMode::Alpha),
+                BlitOptions::new_position(x, y).with_area((9, 8)),
+                BlitOptions::new_position(x, y).with_flip_vertical(true),
+            ] {
+                let mut expected = [0xFF_10_20_30; 100];
+                buffer.blit(&mut expected, dst_size, &options);
+
+                let mut result = [0xFF_10_20_30; 100];
+                indexed.blit(&mut result, dst_size, &options);
+
+                assert_eq!(result, expected, "{options:?}");
+            }
+        }
+    }
+}
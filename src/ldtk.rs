@@ -0,0 +1,672 @@
+//! Levels made with the [LDtk](https://ldtk.io) editor, loaded from the JSON of a `.ldtk` project.
+//!
+//! The tiles of auto-layers are resolved by the editor when saving, so rule based layers are loaded the same as hand placed tiles.
+//! When the project saves levels separately the levels in the project have no layers, they are loaded from the `.ldtkl` files with [`Level::from_json`].
+//!
+//! # Example
+//!
+//! ```rust
+//! use blit::{ldtk::LdtkProject, BlitBuffer, BlitOptions, geom::Size};
+//!
+//! let project = LdtkProject::from_json(
+//!     r#"{
+//!         "defs": { "tilesets": [{ "uid": 1, "identifier": "Terrain", "relPath": "terrain.png", "pxWid": 2, "pxHei": 1, "tileGridSize": 1, "spacing": 0, "padding": 0 }] },
+//!         "levels": [{
+//!             "identifier": "Level_0", "worldX": 0, "worldY": 0, "pxWid": 3, "pxHei": 1,
+//!             "layerInstances": [{
+//!                 "__identifier": "Ground", "__type": "Tiles", "__cWid": 3, "__cHei": 1, "__gridSize": 1,
+//!                 "__tilesetDefUid": 1, "__pxTotalOffsetX": 0, "__pxTotalOffsetY": 0, "visible": true,
+//!                 "gridTiles": [{ "px": [0, 0], "src": [1, 0], "f": 0, "t": 1 }, { "px": [2, 0], "src": [0, 0], "f": 1, "t": 0 }]
+//!             }]
+//!         }]
+//!     }"#,
+//! )
+//! .unwrap();
+//!
+//! // The pixels of every tileset are loaded separately
+//! let terrain = BlitBuffer::from_buffer(&[0xFF_00_00_01, 0xFF_00_00_02], 2, 127);
+//!
+//! let mut canvas = [0; 3];
+//! for layer in project.level("Level_0").unwrap().layers() {
+//!     layer.blit(&terrain, &mut canvas, Size::new(3, 1), &BlitOptions::new());
+//! }
+//! assert_eq!(canvas, [0xFF_00_00_02, 0, 0xFF_00_00_01]);
+//! ```
+
+use serde::{de::Error as _, Deserialize};
+
+use crate::{
+    geom::{Coordinate, SubRect},
+    Blit, BlitBuffer, BlitOptions, Size,
+};
+
+/// Tilesets and levels of an LDtk project.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LdtkProject {
+    /// Definitions of all tilesets.
+    tilesets: Vec<LdtkTileset>,
+    /// Levels in the order they are defined.
+    levels: Vec<Level>,
+}
+
+impl LdtkProject {
+    /// Read the tilesets and levels from the JSON of a `.ldtk` file.
+    ///
+    /// Fails when the JSON is invalid or when the amount of values of an integer grid layer doesn't match its size.
+    pub fn from_json(json: &str) -> Result<Self, serde_json::Error> {
+        let project = serde_json::from_str::<JsonProject>(json)?;
+
+        Ok(Self {
+            tilesets: project
+                .defs
+                .tilesets
+                .into_iter()
+                .map(LdtkTileset::from)
+                .collect(),
+            levels: project
+                .levels
+                .into_iter()
+                .map(Level::try_from)
+                .collect::<Result<_, _>>()?,
+        })
+    }
+
+    /// Definitions of all tilesets.
+    pub fn tilesets(&self) -> &[LdtkTileset] {
+        &self.tilesets
+    }
+
+    /// Tileset with the unique ID, see [`Layer::tileset`].
+    pub fn tileset(&self, uid: u32) -> Option<&LdtkTileset> {
+        self.tilesets.iter().find(|tileset| tileset.uid == uid)
+    }
+
+    /// All levels in the order they are defined.
+    pub fn levels(&self) -> &[Level] {
+        &self.levels
+    }
+
+    /// Level with the identifier.
+    pub fn level(&self, identifier: &str) -> Option<&Level> {
+        self.levels
+            .iter()
+            .find(|level| level.identifier == identifier)
+    }
+}
+
+/// Image divided into tiles of the same size.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LdtkTileset {
+    /// Unique ID referenced by the layers.
+    pub uid: u32,
+    /// Name of the tileset.
+    pub identifier: String,
+    /// Path of the image relative to the project file, `None` for the embedded icons of the editor.
+    pub path: Option<String>,
+    /// Size of the image in pixels.
+    pub size: Size,
+    /// Width and height of a single tile in pixels.
+    pub tile_size: u32,
+    /// Space in pixels between the tiles.
+    pub spacing: u32,
+    /// Space in pixels around the tiles at the edges of the image.
+    pub padding: u32,
+}
+
+/// Single level with its layers.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Level {
+    /// Name of the level.
+    pub identifier: String,
+    /// Position of the top left of the level in the world in pixels.
+    pub world_position: Coordinate,
+    /// Size of the level in pixels.
+    pub size: Size,
+    /// Path of the `.ldtkl` file with the layers when the project saves levels separately.
+    pub external_path: Option<String>,
+    /// Layers in the order they are drawn.
+    layers: Vec<Layer>,
+}
+
+impl Level {
+    /// Read a level saved separately from the JSON of a `.ldtkl` file.
+    ///
+    /// Fails when the JSON is invalid or when the amount of values of an integer grid layer doesn't match its size.
+    pub fn from_json(json: &str) -> Result<Self, serde_json::Error> {
+        serde_json::from_str::<JsonLevel>(json)?.try_into()
+    }
+
+    /// All layers in the order they are drawn, the bottom layer first.
+    ///
+    /// This is the reverse of the order in the editor and the file, where the top layer is first.
+    pub fn layers(&self) -> &[Layer] {
+        &self.layers
+    }
+
+    /// Layer with the identifier.
+    pub fn layer(&self, identifier: &str) -> Option<&Layer> {
+        self.layers
+            .iter()
+            .find(|layer| layer.identifier == identifier)
+    }
+}
+
+/// What a layer contains.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+pub enum LayerKind {
+    /// Integer values in a grid, with tiles when it has auto-layer rules.
+    IntGrid,
+    /// Entities placed freely.
+    Entities,
+    /// Tiles placed by hand.
+    Tiles,
+    /// Tiles placed by rules based on the integer grid of another layer.
+    AutoLayer,
+}
+
+/// Layer of a level with its tiles, entities or integer grid.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Layer {
+    /// Name of the layer.
+    pub identifier: String,
+    /// What the layer contains.
+    pub kind: LayerKind,
+    /// Amount of grid cells horizontally and vertically.
+    pub size: Size,
+    /// Width and height of a single grid cell in pixels.
+    pub grid_size: u32,
+    /// Offset in pixels of everything in the layer.
+    pub offset: Coordinate,
+    /// Whether the layer is visible in the editor.
+    pub visible: bool,
+    /// Opacity of the layer in the range `0.0..=1.0`.
+    pub opacity: f32,
+    /// Unique ID of the tileset of the tiles, see [`LdtkProject::tileset`].
+    pub tileset: Option<u32>,
+    /// Tiles placed by hand or by rules.
+    tiles: Vec<LayerTile>,
+    /// Entities placed in the layer.
+    entities: Vec<Entity>,
+    /// Integer values of every grid cell row by row, zero for empty cells.
+    int_grid: Vec<i32>,
+}
+
+impl Layer {
+    /// All tiles in the order they are drawn, placed by hand or by the auto-layer rules.
+    pub fn tiles(&self) -> &[LayerTile] {
+        &self.tiles
+    }
+
+    /// All entities.
+    pub fn entities(&self) -> &[Entity] {
+        &self.entities
+    }
+
+    /// Integer values of every grid cell row by row, empty when the layer is not an integer grid.
+    pub fn int_grid(&self) -> &[i32] {
+        &self.int_grid
+    }
+
+    /// Integer value at the grid position, `None` when it's outside of the layer or the layer is not an integer grid.
+    pub fn int_value(&self, x: u32, y: u32) -> Option<i32> {
+        if x >= self.size.width || y >= self.size.height {
+            return None;
+        }
+
+        self.int_grid
+            .get(y as usize * self.size.width as usize + x as usize)
+            .copied()
+    }
+
+    /// Draw all tiles of the layer from the image of its tileset.
+    ///
+    /// The opacity of the layer and of the tiles is applied, the visibility is not.
+    /// The layer is drawn at the position of the options and clipped by the mask of the options, the same as the parent of [`BlitOptions::compose`].
+    pub fn blit(
+        &self,
+        tileset: &BlitBuffer,
+        dst: &mut [u32],
+        dst_size: Size,
+        options: &BlitOptions,
+    ) {
+        for tile in &self.tiles {
+            let mut tile_options = tile.options(self.grid_size).offset_by(self.offset);
+            if self.opacity < 1.0 {
+                tile_options.set_opacity(tile.opacity * self.opacity);
+            }
+
+            tileset.blit(dst, dst_size, &BlitOptions::compose(options, &tile_options));
+        }
+    }
+}
+
+/// Tile of a layer.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LayerTile {
+    /// Position of the top left of the tile in the layer in pixels.
+    pub position: Coordinate,
+    /// Position of the top left of the tile in the tileset image in pixels.
+    pub source: Coordinate,
+    /// ID of the tile in the tileset.
+    pub id: u32,
+    /// Whether the tile is mirrored around the vertical axis.
+    pub flip_horizontal: bool,
+    /// Whether the tile is mirrored around the horizontal axis.
+    pub flip_vertical: bool,
+    /// Opacity of the tile in the range `0.0..=1.0`.
+    pub opacity: f32,
+}
+
+impl LayerTile {
+    /// Options drawing the tile with the grid size of its layer from the tileset image at the position of the tile.
+    pub fn options(&self, grid_size: u32) -> BlitOptions {
+        let options = BlitOptions::new_position(self.position.x, self.position.y)
+            .with_sub_rect(SubRect::new(
+                self.source.x,
+                self.source.y,
+                (grid_size, grid_size),
+            ))
+            .with_flip_horizontal(self.flip_horizontal)
+            .with_flip_vertical(self.flip_vertical);
+
+        if self.opacity < 1.0 {
+            options.with_opacity(self.opacity)
+        } else {
+            options
+        }
+    }
+}
+
+/// Entity placed in a layer.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Entity {
+    /// Name of the entity definition.
+    pub identifier: String,
+    /// Unique instance ID.
+    pub iid: String,
+    /// Position of the pivot of the entity in the layer in pixels.
+    pub position: Coordinate,
+    /// Grid cell of the pivot of the entity.
+    pub grid: Coordinate,
+    /// Size of the entity in pixels.
+    pub size: Size,
+    /// Pivot relative to the size of the entity, `(0.0, 0.0)` is the top left and `(1.0, 1.0)` the bottom right.
+    pub pivot: (f32, f32),
+}
+
+impl Entity {
+    /// Rectangle the entity covers in the layer in pixels.
+    pub fn rect(&self) -> SubRect {
+        SubRect::new(
+            self.position.x as f32 - self.pivot.0 * self.size.width as f32,
+            self.position.y as f32 - self.pivot.1 * self.size.height as f32,
+            self.size,
+        )
+    }
+}
+
+/// Root of a `.ldtk` file.
+#[derive(Deserialize)]
+struct JsonProject {
+    defs: JsonDefs,
+    #[serde(default)]
+    levels: Vec<JsonLevel>,
+}
+
+/// Definitions of a project.
+#[derive(Deserialize)]
+struct JsonDefs {
+    #[serde(default)]
+    tilesets: Vec<JsonTileset>,
+}
+
+/// Tileset definition.
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct JsonTileset {
+    uid: u32,
+    identifier: String,
+    rel_path: Option<String>,
+    px_wid: u32,
+    px_hei: u32,
+    tile_grid_size: u32,
+    #[serde(default)]
+    spacing: u32,
+    #[serde(default)]
+    padding: u32,
+}
+
+impl From<JsonTileset> for LdtkTileset {
+    fn from(tileset: JsonTileset) -> Self {
+        Self {
+            uid: tileset.uid,
+            identifier: tileset.identifier,
+            path: tileset.rel_path,
+            size: Size::new(tileset.px_wid, tileset.px_hei),
+            tile_size: tileset.tile_grid_size,
+            spacing: tileset.spacing,
+            padding: tileset.padding,
+        }
+    }
+}
+
+/// Level in a project or the root of a `.ldtkl` file.
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct JsonLevel {
+    identifier: String,
+    world_x: i32,
+    world_y: i32,
+    px_wid: u32,
+    px_hei: u32,
+    layer_instances: Option<Vec<JsonLayer>>,
+    external_rel_path: Option<String>,
+}
+
+impl TryFrom<JsonLevel> for Level {
+    type Error = serde_json::Error;
+
+    fn try_from(level: JsonLevel) -> Result<Self, serde_json::Error> {
+        let mut layers = level
+            .layer_instances
+            .unwrap_or_default()
+            .into_iter()
+            .map(Layer::try_from)
+            .collect::<Result<Vec<_>, _>>()?;
+        // The top layer is the first
+        layers.reverse();
+
+        Ok(Self {
+            identifier: level.identifier,
+            world_position: Coordinate::new(level.world_x, level.world_y),
+            size: Size::new(level.px_wid, level.px_hei),
+            external_path: level.external_rel_path,
+            layers,
+        })
+    }
+}
+
+/// Layer instance of a level.
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct JsonLayer {
+    #[serde(rename = "__identifier")]
+    identifier: String,
+    #[serde(rename = "__type")]
+    kind: LayerKind,
+    #[serde(rename = "__cWid")]
+    width: u32,
+    #[serde(rename = "__cHei")]
+    height: u32,
+    #[serde(rename = "__gridSize")]
+    grid_size: u32,
+    #[serde(rename = "__opacity", default = "opaque")]
+    opacity: f32,
+    #[serde(rename = "__pxTotalOffsetX", default)]
+    offset_x: i32,
+    #[serde(rename = "__pxTotalOffsetY", default)]
+    offset_y: i32,
+    #[serde(rename = "__tilesetDefUid")]
+    tileset: Option<u32>,
+    #[serde(default = "visible")]
+    visible: bool,
+    #[serde(default)]
+    int_grid_csv: Vec<i32>,
+    #[serde(default)]
+    auto_layer_tiles: Vec<JsonTile>,
+    #[serde(default)]
+    grid_tiles: Vec<JsonTile>,
+    #[serde(default)]
+    entity_instances: Vec<JsonEntity>,
+}
+
+impl TryFrom<JsonLayer> for Layer {
+    type Error = serde_json::Error;
+
+    fn try_from(layer: JsonLayer) -> Result<Self, serde_json::Error> {
+        let size = Size::new(layer.width, layer.height);
+        if !layer.int_grid_csv.is_empty() && layer.int_grid_csv.len() != size.pixels() {
+            return Err(serde_json::Error::custom(format!(
+                "layer {} has {} integer grid values instead of {}",
+                layer.identifier,
+                layer.int_grid_csv.len(),
+                size.pixels()
+            )));
+        }
+
+        Ok(Self {
+            identifier: layer.identifier,
+            kind: layer.kind,
+            size,
+            grid_size: layer.grid_size,
+            offset: Coordinate::new(layer.offset_x, layer.offset_y),
+            visible: layer.visible,
+            opacity: layer.opacity.clamp(0.0, 1.0),
+            tileset: layer.tileset,
+            // Only one of them is used, depending on the kind of layer
+            tiles: layer
+                .auto_layer_tiles
+                .into_iter()
+                .chain(layer.grid_tiles)
+                .map(LayerTile::from)
+                .collect(),
+            entities: layer
+                .entity_instances
+                .into_iter()
+                .map(Entity::from)
+                .collect(),
+            int_grid: layer.int_grid_csv,
+        })
+    }
+}
+
+/// Tile instance of a layer.
+#[derive(Deserialize)]
+struct JsonTile {
+    px: [i32; 2],
+    src: [i32; 2],
+    #[serde(default)]
+    f: u8,
+    t: u32,
+    #[serde(default = "opaque")]
+    a: f32,
+}
+
+impl From<JsonTile> for LayerTile {
+    fn from(tile: JsonTile) -> Self {
+        Self {
+            position: Coordinate::new(tile.px[0], tile.px[1]),
+            source: Coordinate::new(tile.src[0], tile.src[1]),
+            id: tile.t,
+            // The first bit mirrors horizontally and the second bit vertically
+            flip_horizontal: tile.f & 1 != 0,
+            flip_vertical: tile.f & 2 != 0,
+            opacity: tile.a.clamp(0.0, 1.0),
+        }
+    }
+}
+
+/// Entity instance of a layer.
+#[derive(Deserialize)]
+struct JsonEntity {
+    #[serde(rename = "__identifier")]
+    identifier: String,
+    #[serde(default)]
+    iid: String,
+    px: [i32; 2],
+    #[serde(rename = "__grid")]
+    grid: [i32; 2],
+    #[serde(rename = "__pivot")]
+    pivot: [f32; 2],
+    width: u32,
+    height: u32,
+}
+
+impl From<JsonEntity> for Entity {
+    fn from(entity: JsonEntity) -> Self {
+        Self {
+            identifier: entity.identifier,
+            iid: entity.iid,
+            position: Coordinate::new(entity.px[0], entity.px[1]),
+            grid: Coordinate::new(entity.grid[0], entity.grid[1]),
+            size: Size::new(entity.width, entity.height),
+            pivot: (entity.pivot[0], entity.pivot[1]),
+        }
+    }
+}
+
+/// Default opacity of layers and tiles.
+fn opaque() -> f32 {
+    1.0
+}
+
+/// Default visibility of layers.
+fn visible() -> bool {
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Project with a single level, trimmed from a file saved by the editor.
+    const PROJECT: &str = r#"{
+        "__header__": { "fileType": "LDtk Project JSON", "app": "LDtk", "appVersion": "1.5.3" },
+        "jsonVersion": "1.5.3",
+        "externalLevels": false,
+        "defs": {
+            "layers": [],
+            "entities": [],
+            "tilesets": [
+                { "__cWid": 2, "__cHei": 2, "identifier": "Terrain", "uid": 3, "relPath": "terrain.png", "pxWid": 5, "pxHei": 5, "tileGridSize": 2, "spacing": 1, "padding": 0, "tags": [] },
+                { "__cWid": 1, "__cHei": 1, "identifier": "Internal_Icons", "uid": 4, "relPath": null, "embedAtlas": "LdtkIcons", "pxWid": 16, "pxHei": 16, "tileGridSize": 16, "spacing": 0, "padding": 0, "tags": [] }
+            ]
+        },
+        "levels": [{
+            "identifier": "Level_0",
+            "iid": "a3a0b3c0-0000-0000-0000-000000000000",
+            "uid": 0,
+            "worldX": -16,
+            "worldY": 32,
+            "pxWid": 6,
+            "pxHei": 4,
+            "externalRelPath": null,
+            "layerInstances": [
+                {
+                    "__identifier": "Entities", "__type": "Entities", "__cWid": 3, "__cHei": 2, "__gridSize": 2,
+                    "__opacity": 1, "__pxTotalOffsetX": 0, "__pxTotalOffsetY": 0, "__tilesetDefUid": null, "__tilesetRelPath": null,
+                    "visible": true, "intGridCsv": [], "autoLayerTiles": [], "gridTiles": [],
+                    "entityInstances": [{
+                        "__identifier": "Player", "__grid": [1, 1], "__pivot": [0.5, 1], "__tags": [], "__tile": null,
+                        "__worldX": -13, "__worldY": 36, "iid": "b1", "width": 2, "height": 4, "defUid": 5, "px": [3, 4], "fieldInstances": []
+                    }]
+                },
+                {
+                    "__identifier": "Decoration", "__type": "Tiles", "__cWid": 3, "__cHei": 2, "__gridSize": 2,
+                    "__opacity": 0.5, "__pxTotalOffsetX": 1, "__pxTotalOffsetY": 0, "__tilesetDefUid": 3, "__tilesetRelPath": "terrain.png",
+                    "visible": false, "intGridCsv": [], "autoLayerTiles": [],
+                    "gridTiles": [{ "px": [0, 0], "src": [3, 0], "f": 3, "t": 1, "d": [0], "a": 1 }],
+                    "entityInstances": []
+                },
+                {
+                    "__identifier": "Ground", "__type": "IntGrid", "__cWid": 3, "__cHei": 2, "__gridSize": 2,
+                    "__opacity": 1, "__pxTotalOffsetX": 0, "__pxTotalOffsetY": 0, "__tilesetDefUid": 3, "__tilesetRelPath": "terrain.png",
+                    "visible": true, "intGridCsv": [0, 0, 0, 1, 1, 2],
+                    "autoLayerTiles": [
+                        { "px": [0, 2], "src": [0, 0], "f": 0, "t": 0, "d": [10, 3], "a": 1 },
+                        { "px": [2, 2], "src": [0, 3], "f": 1, "t": 2, "d": [10, 4], "a": 1 },
+                        { "px": [4, 2], "src": [3, 3], "f": 0, "t": 3, "d": [11, 5], "a": 0.5 }
+                    ],
+                    "gridTiles": [], "entityInstances": []
+                }
+            ]
+        }]
+    }"#;
+
+    #[test]
+    fn load() {
+        let project = LdtkProject::from_json(PROJECT).unwrap();
+
+        assert_eq!(project.tilesets().len(), 2);
+        let terrain = project.tileset(3).unwrap();
+        assert_eq!(terrain.path.as_deref(), Some("terrain.png"));
+        assert_eq!((terrain.tile_size, terrain.spacing), (2, 1));
+        assert_eq!(project.tileset(4).unwrap().path, None);
+
+        let level = project.level("Level_0").unwrap();
+        assert_eq!(level.world_position, Coordinate::new(-16, 32));
+        assert_eq!(level.size, Size::new(6, 4));
+
+        // The bottom layer is drawn first
+        assert_eq!(
+            level
+                .layers()
+                .iter()
+                .map(|layer| layer.identifier.as_str())
+                .collect::<Vec<_>>(),
+            ["Ground", "Decoration", "Entities"]
+        );
+
+        let ground = level.layer("Ground").unwrap();
+        assert_eq!(ground.kind, LayerKind::IntGrid);
+        assert_eq!(ground.tileset, Some(3));
+        assert_eq!(ground.int_value(2, 1), Some(2));
+        assert_eq!(ground.int_value(3, 1), None);
+        assert_eq!(ground.tiles().len(), 3);
+        assert_eq!(ground.tiles()[1].source, Coordinate::new(0, 3));
+        assert!(ground.tiles()[1].flip_horizontal && !ground.tiles()[1].flip_vertical);
+
+        let decoration = level.layer("Decoration").unwrap();
+        assert!(!decoration.visible);
+        assert_eq!(decoration.opacity, 0.5);
+        assert!(decoration.tiles()[0].flip_horizontal && decoration.tiles()[0].flip_vertical);
+
+        let entities = level.layer("Entities").unwrap();
+        assert_eq!(entities.tileset, None);
+        let player = &entities.entities()[0];
+        assert_eq!(player.identifier, "Player");
+        assert_eq!(player.grid, Coordinate::new(1, 1));
+        assert_eq!(player.rect(), SubRect::new(2, 0, (2, 4)));
+
+        // The amount of integer grid values must match the size
+        assert!(LdtkProject::from_json(&PROJECT.replace("[0, 0, 0, 1, 1, 2]", "[0, 1]")).is_err());
+    }
+
+    #[test]
+    fn blit() {
+        let project = LdtkProject::from_json(PROJECT).unwrap();
+        let ground = project.level("Level_0").unwrap().layer("Ground").unwrap();
+
+        // Four 2x2 tiles with a unique color per tile and per pixel, separated by a spacing of 1
+        let source = (0..25)
+            .map(|i| {
+                let (x, y) = (i % 5, i / 5);
+                if x == 2 || y == 2 {
+                    0
+                } else {
+                    let tile = (y / 3) * 2 + x / 3;
+                    0xFF_00_00_00 | ((tile + 1) << 4) | ((y % 3) * 2 + x % 3)
+                }
+            })
+            .collect::<Vec<_>>();
+        let tileset = BlitBuffer::from_buffer(&source, 5, 127);
+
+        let mut canvas = [0xFF_00_00_00; 6 * 4];
+        ground.blit(&tileset, &mut canvas, Size::new(6, 4), &BlitOptions::new());
+
+        let mut expected = [0xFF_00_00_00; 6 * 4];
+        for tile in ground.tiles() {
+            tileset.blit(&mut expected, Size::new(6, 4), &tile.options(2));
+        }
+        assert_eq!(canvas, expected);
+
+        // Tiles are taken from the tileset and mirrored, the last tile is half transparent over black
+        assert_eq!(
+            canvas[2 * 6..3 * 6]
+                .iter()
+                .map(|pixel| pixel & 0xFF)
+                .collect::<Vec<_>>(),
+            [0x10, 0x11, 0x31, 0x30, 0x20, 0x20]
+        );
+    }
+}
@@ -3,8 +3,9 @@
 //! # [Interactive Demo](https://tversteeg.nl/blit/showcase)
 //!
 //! This crate works with RGBA `u32` buffers.
-//! The alpha channel can only be read with a singular treshold, converting it to a binary transparent or opaque color.
+//! By default the alpha channel is read with a singular treshold, converting it to a binary transparent or opaque color.
 //! The reason this limitation is in place is that it allows efficient rendering optimizations.
+//! When smooth edges are needed the full alpha channel can be kept with [`BlitBuffer::from_buffer_with_alpha`] and drawn with [`BlendMode::Alpha`].
 //!
 //! For ergonomic use of this crate without needing to type convert everything most functions accepting numbers are generic with the number types being [`num_traits::ToPrimitive`], this might seem confusing but any number can be passed to these functions immediately.
 //!
@@ -24,7 +25,7 @@
 //! let mut canvas: Vec<u32> = vec![0xFF_FF_FF_FF; CANVAS_SIZE.pixels()];
 //!
 //! // Load the image from disk using the `image` crate
-//! let img = image::open("examples/smiley_rgb.png").unwrap().into_rgb8();
+//! let img = image::open("examples/smiley_rgb.png").unwrap();
 //!
 //! // Blit by creating a special blitting buffer first where the MASK_COLOR will be the color that will be made transparent
 //! let blit_buffer = img.to_blit_buffer_with_mask_color(MASK_COLOR);
@@ -35,11 +36,47 @@
 //! # }}
 //! ```
 
+#[cfg(feature = "atlas")]
+pub mod atlas;
+pub mod autotile;
+pub mod background;
+pub mod batch;
+pub mod cache;
+pub mod canvas;
+pub mod command;
+#[cfg(feature = "serde")]
+pub mod compact;
+pub mod composite;
+pub mod dirty;
+pub mod draw;
+#[cfg(feature = "ffi")]
+pub mod ffi;
+mod filter;
+pub mod font;
 pub mod geom;
+#[cfg(feature = "gif")]
+pub mod gif;
 #[cfg(feature = "image")]
 mod image;
+pub mod indexed;
+#[cfg(feature = "ldtk")]
+pub mod ldtk;
+pub mod ops;
+#[cfg(feature = "png")]
+pub mod png;
+#[cfg(any(test, feature = "reference"))]
+pub mod reference;
+pub mod rle;
+pub mod scanline;
+mod simd;
 pub mod slice;
+#[cfg(feature = "softbuffer")]
+pub mod softbuffer;
+#[cfg(feature = "tiled")]
+pub mod tiled;
 mod view;
+#[cfg(feature = "web")]
+pub mod web;
 
 /// Commonly used imports.
 ///
@@ -50,20 +87,21 @@ pub mod prelude {
     #[cfg(feature = "image")]
     pub use crate::ToBlitBuffer;
     pub use crate::{
-        geom::{Size, SubRect},
+        geom::{Coordinate, Size, SubRect},
         slice::Slice,
         Blit, BlitBuffer,
     };
 }
 
-use geom::{Size, SubRect};
+use geom::{position, Coordinate, Size, SubRect};
 
-use std::ops::Range;
+use std::{borrow::Cow, iter::StepBy, ops::Range};
 
+use filter::Sample;
 use num_traits::ToPrimitive;
 #[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
-use slice::{Slice, SliceProjection};
+use slice::{Slice, SliceFill, SliceProjection};
 use view::ImageView;
 
 /// Internal representation of a color.
@@ -77,6 +115,177 @@ pub trait Blit {
     ///
     /// The pixels will be drawn to the destination buffer in RGBA format.
     fn blit(&self, dst: &mut [u32], dst_size: Size, options: &BlitOptions);
+
+    /// Size of the source in pixels, `None` when it's unknown.
+    ///
+    /// Used by [`Blit::try_blit`] to report which part got drawn, when it's unknown the source is assumed to be as big as the destination.
+    fn source_size(&self) -> Option<Size> {
+        None
+    }
+
+    /// Draw the source input like [`Blit::blit`], but fail when the destination is invalid and report what got drawn.
+    ///
+    /// Fails when the amount of pixels doesn't match the size of the destination or when the destination has no pixels.
+    ///
+    /// ```rust
+    /// # use blit::{Blit, BlitBuffer, BlitOptions, Error, Visibility, geom::{Size, SubRect}};
+    /// let blit = BlitBuffer::from_buffer(&[0xFF_FF_FF_FF; 4], 2, 127);
+    /// let mut buffer = [0; 9];
+    ///
+    /// let stats = blit.try_blit(&mut buffer, Size::new(3, 3), &BlitOptions::new_position(2, 0)).unwrap();
+    /// assert_eq!(stats.rect, Some(SubRect::new(2, 0, (1, 2))));
+    /// assert_eq!(stats.visibility, Visibility::Partial);
+    ///
+    /// // The width is wrong
+    /// assert!(blit.try_blit(&mut buffer, Size::new(4, 3), &BlitOptions::new()).is_err());
+    /// ```
+    fn try_blit(
+        &self,
+        dst: &mut [u32],
+        dst_size: Size,
+        options: &BlitOptions,
+    ) -> Result<BlitStats, Error> {
+        if dst.len() != dst_size.pixels() {
+            return Err(Error::SizeMismatch {
+                size: dst_size,
+                len: dst.len(),
+            });
+        }
+        if dst_size.width == 0 || dst_size.height == 0 {
+            return Err(Error::EmptyDestination);
+        }
+
+        let source_size = self.source_size().unwrap_or(dst_size);
+        let rect = options.effective_rect(source_size, dst_size);
+        if rect.is_some() {
+            self.blit(dst, dst_size, options);
+        }
+
+        Ok(BlitStats {
+            rect,
+            visibility: options.visibility(source_size, rect),
+        })
+    }
+
+    /// Draw only a single row of the destination image.
+    ///
+    /// The destination buffer only contains the pixels of row `y` of an image with size `dst_size`.
+    /// This is useful for rendering to hardware that can't hold a full image, see [`scanline::render_row`].
+    fn blit_row(&self, dst_row: &mut [u32], dst_size: Size, options: &BlitOptions, y: u32) {
+        // Move everything up so the row is the only row of a destination with a height of one pixel
+        let row_options = options.clone().offset_by((0, -(y as i32)));
+
+        self.blit(dst_row, Size::new(dst_size.width, 1), &row_options);
+    }
+
+    /// Draw the source input clipped to a region of multiple rectangles, every pixel of the region is drawn at most once.
+    ///
+    /// The region is intersected with [`BlitOptions::mask`] if it's set.
+    ///
+    /// ```rust
+    /// # use blit::{Blit, BlitBuffer, BlitOptions, geom::{ClipRegion, Size}};
+    /// let blit = BlitBuffer::from_buffer(&[0xFF_FF_FF_FF; 4], 4, 127);
+    /// let mut buffer = [0; 4];
+    ///
+    /// let region = ClipRegion::new().with_rect((0, 0, 1, 1)).with_rect((2, 0, 1, 1));
+    /// blit.blit_region(&mut buffer, Size::new(4, 1), &BlitOptions::new(), &region);
+    /// assert_eq!(buffer, [0xFF_FF_FF_FF, 0, 0xFF_FF_FF_FF, 0]);
+    /// ```
+    fn blit_region(
+        &self,
+        dst: &mut [u32],
+        dst_size: Size,
+        options: &BlitOptions,
+        region: &geom::ClipRegion,
+    ) {
+        region.rects().iter().for_each(|rect| {
+            self.blit(dst, dst_size, &options.clone().clipped_by(*rect));
+        });
+    }
+
+    /// Draw the source input everywhere except inside of the rectangle on the destination.
+    ///
+    /// This is the inverse of [`BlitOptions::mask`], which can still be set to limit the drawing further.
+    ///
+    /// ```rust
+    /// # use blit::{Blit, BlitBuffer, BlitOptions, geom::{Size, SubRect}};
+    /// let blit = BlitBuffer::from_buffer(&[0xFF_FF_FF_FF; 9], 3, 127);
+    /// let mut buffer = [0; 9];
+    ///
+    /// // Draw around a hole in the center
+    /// blit.blit_excluding(&mut buffer, Size::new(3, 3), &BlitOptions::new(), SubRect::new(1, 1, (1, 1)));
+    /// assert_eq!(buffer.iter().filter(|pixel| **pixel == 0).count(), 1);
+    /// assert_eq!(buffer[4], 0);
+    /// ```
+    fn blit_excluding(
+        &self,
+        dst: &mut [u32],
+        dst_size: Size,
+        options: &BlitOptions,
+        exclude: SubRect,
+    ) {
+        let region = geom::ClipRegion::new()
+            .with_rect(SubRect::from_size(dst_size))
+            .without_rect(exclude);
+
+        self.blit_region(dst, dst_size, options, &region);
+    }
+
+    /// Draw the source input on a canvas, see [`canvas::Canvas::blit`].
+    fn blit_canvas(&self, canvas: &mut canvas::Canvas<'_>, options: &BlitOptions) {
+        canvas.blit(self, options);
+    }
+
+    /// Draw the source input on a destination image with 4 bytes per pixel in RGBA order, such as the frame of the `pixels` crate.
+    ///
+    /// The destination doesn't need to be aligned, the rows that can be drawn on are converted to `u32` pixels and back which makes this slower than [`Blit::blit`].
+    /// When the [`Blit::source_size`] is unknown all rows are converted.
+    /// [`BlitOptions::channel_order`] is ignored because the order of the bytes is always RGBA.
+    ///
+    /// ```rust
+    /// # use blit::{Blit, BlitBuffer, BlitOptions, geom::Size};
+    /// let blit = BlitBuffer::from_buffer(&[0xFF_11_22_33], 1, 127);
+    /// let mut frame = [0; 8];
+    ///
+    /// blit.blit_u8(&mut frame, Size::new(2, 1), &BlitOptions::new_position(1, 0));
+    /// assert_eq!(frame, [0, 0, 0, 0, 0x11, 0x22, 0x33, 0xFF]);
+    /// ```
+    fn blit_u8(&self, dst: &mut [u8], dst_size: Size, options: &BlitOptions) {
+        // Only convert the rows that can be drawn on
+        let rows = match self.source_size() {
+            Some(source_size) => match options.effective_rect(source_size, dst_size) {
+                Some(rect) => rect,
+                None => return,
+            },
+            None => SubRect::from_size(dst_size),
+        };
+        let row_bytes = dst_size.width as usize * 4;
+        let (start, end) = (
+            rows.y as usize * row_bytes,
+            rows.bottom() as usize * row_bytes,
+        );
+        let Some(dst) = dst.get_mut(start..end.min(dst.len())) else {
+            return;
+        };
+
+        // Alpha in the highest bits followed by the colors is the packing used for drawing
+        let mut pixels = rgba_pixels(dst).collect::<Vec<_>>();
+
+        // Move everything up so the first converted row is the top of the destination
+        let options = BlitOptions {
+            channel_order: ChannelOrder::Argb,
+            ..options.clone().offset_by((0, -rows.y))
+        };
+        self.blit(
+            &mut pixels,
+            Size::new(dst_size.width, rows.height()),
+            &options,
+        );
+
+        dst.chunks_exact_mut(4)
+            .zip(pixels)
+            .for_each(|(bytes, pixel)| bytes.copy_from_slice(&rgba_bytes(pixel)));
+    }
 }
 
 /// Convert external image types to a specialized buffer optimized for blitting.
@@ -93,6 +302,16 @@ pub trait ToBlitBuffer {
     ///
     /// Ignore the alpha channel if set and use only a single color for transparency.
     fn to_blit_buffer_with_mask_color(&self, mask_color: u32) -> BlitBuffer;
+
+    /// Convert the image to a custom `BlitBuffer` type which is optimized for applying the blitting operations.
+    ///
+    /// Ignore the alpha channel if set and use the colors matching the key for transparency, see [`ColorKey`].
+    fn to_blit_buffer_with_color_key(&self, key: &ColorKey) -> BlitBuffer {
+        // Every pixel is opaque with a treshold of zero
+        let opaque = self.to_blit_buffer_with_alpha(0);
+
+        BlitBuffer::from_buffer_with_color_key(opaque.pixels(), opaque.width(), key)
+    }
 }
 
 /// How, where and which part of the image to render.
@@ -107,11 +326,17 @@ pub struct BlitOptions {
     /// Vertical position on the destination buffer.
     pub y: i32,
 
+    /// Point of the drawn area that is placed at the position.
+    ///
+    /// The area is moved so the anchor is placed at the position, by default the top left of the area is placed at the position.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub anchor: Anchor,
+
     /// Size of the area `(width, height)` on the destination buffer.
     ///
     /// - When `None` is used, the size of the source buffer or of the subrectangle if set will be used.
     /// - When the area is smaller than the source buffer it effectively functions as the width and height parameters of [`BlitOptions::sub_rect`].
-    /// - When the area is bigger than the source buffer the default behaviour will be tiling.
+    /// - When the area is bigger than the source buffer the default behaviour will be tiling, see [`BlitOptions::scale`].
     ///
     /// ```rust
     /// # use blit::BlitOptions;
@@ -145,6 +370,113 @@ pub struct BlitOptions {
     ///
     /// This is only used when [`BlitOptions::area`] is set.
     pub horizontal_slice: Option<Slice>,
+
+    /// What to do with the leftover space when the area is not a multiple of the tile size.
+    ///
+    /// This is only used when [`BlitOptions::area`] is bigger than the source and tiling is applied.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub remainder: RemainderMode,
+
+    /// Scroll the source inside of every tile by an amount of source pixels, wrapping around the source rectangle.
+    ///
+    /// A positive offset moves the source to the left and up, negative and large offsets wrap around so the tiles can be scrolled endlessly in both directions.
+    /// This is only used when tiling with [`ScaleMode::Tile`], with slices every section is scrolled separately.
+    ///
+    /// ```rust
+    /// # use blit::{Blit, BlitBuffer, BlitOptions, geom::Size};
+    /// let blit = BlitBuffer::from_buffer(&[0xFF_00_00_01, 0xFF_00_00_02, 0xFF_00_00_03], 3, 127);
+    /// let mut buffer = [0; 4];
+    ///
+    /// blit.blit(&mut buffer, Size::new(4, 1), &BlitOptions::new().with_area((4, 1)).with_uv_offset((-1, 0)));
+    /// assert_eq!(buffer.map(|pixel| pixel & 0xFF), [3, 1, 2, 3]);
+    /// ```
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub uv_offset: Coordinate,
+
+    /// Empty space `(horizontal, vertical)` between the tiles, nothing is drawn in it.
+    ///
+    /// - When `None` is used, the tiles are directly next to each other.
+    ///
+    /// This is only used when tiling with [`ScaleMode::Tile`], the leftover space handled with [`BlitOptions::remainder`] includes the spacing.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub tile_spacing: Option<Size>,
+
+    /// Empty space between the edges of the area and the tiles, `(horizontal, vertical)` is applied to both the left and right and the top and bottom edges.
+    ///
+    /// - When `None` is used, the tiles start at the edges of the area.
+    ///
+    /// This is only used when tiling with [`ScaleMode::Tile`].
+    ///
+    /// ```rust
+    /// # use blit::{Blit, BlitBuffer, BlitOptions, RemainderMode, geom::Size};
+    /// let blit = BlitBuffer::from_buffer(&[0xFF_00_00_01, 0xFF_00_00_02], 2, 127);
+    /// let mut buffer = [0; 8];
+    ///
+    /// // Draw a row of whole tiles with a gap of a pixel between them and around them
+    /// let options = BlitOptions::new()
+    ///     .with_area((8, 1))
+    ///     .with_tile_spacing((1, 0))
+    ///     .with_tile_margin((1, 0))
+    ///     .with_remainder(RemainderMode::None);
+    /// blit.blit(&mut buffer, Size::new(8, 1), &options);
+    /// assert_eq!(buffer.map(|pixel| pixel & 0xFF), [0, 1, 2, 0, 1, 2, 0, 0]);
+    /// ```
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub tile_margin: Option<Size>,
+
+    /// How the source pixels are combined with the destination pixels.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub blend: BlendMode,
+
+    /// Fade the whole source by multiplying the alpha of every pixel, in the range `0.0..=1.0`.
+    ///
+    /// - When `None` is used, the source is drawn fully opaque.
+    /// - When the opacity is lower than `1.0` [`BlendMode::Mask`] is drawn as [`BlendMode::Alpha`], otherwise the fade would be lost.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub opacity: Option<f32>,
+
+    /// Alpha value from which source pixels are drawn, overriding the alpha channel of the source.
+    ///
+    /// - When `None` is used, the alpha channel of the source is used as is.
+    /// - When set, source pixels with a lower alpha are fully transparent and the others fully opaque, the same as the alpha treshold of [`BlitBuffer::from_buffer`] but applied while drawing.
+    ///   Buffers created with [`BlitBuffer::from_buffer_with_alpha`] keep their original alpha, so the same buffer can be drawn with different tresholds.
+    /// - Fully transparent pixels are never drawn, so a treshold of `0` is the same as `1`.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub alpha_threshold: Option<u8>,
+
+    /// Mirror the drawn area around the vertical axis.
+    ///
+    /// The whole area is mirrored, so with tiling and slices the order of the tiles and slices is mirrored too.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub flip_horizontal: bool,
+
+    /// Mirror the drawn area around the horizontal axis.
+    ///
+    /// The whole area is mirrored, so with tiling and slices the order of the tiles and slices is mirrored too.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub flip_vertical: bool,
+
+    /// How the source is scaled when the area is a different size than the source.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub scale: ScaleMode,
+
+    /// How the source is sampled when it's stretched with [`ScaleMode::Stretch`].
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub filter: Filter,
+
+    /// Rotate the source in quarter turns.
+    ///
+    /// The whole area is rotated, so with tiling and slices the tiles and slices are rotated as a whole too.
+    /// When rotating a quarter turn the width and height of the area are swapped, [`BlitOptions::area`] is the size after rotating.
+    /// Rotation is applied before flipping.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub rotation: Rotation,
+
+    /// How the channels of the destination pixels are packed.
+    ///
+    /// The source is always packed as [`ChannelOrder::Argb`], the destination pixels are converted when blending.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub channel_order: ChannelOrder,
 }
 
 impl BlitOptions {
@@ -159,6 +491,7 @@ impl BlitOptions {
     /// Setup options for blitting at position `(x, y)`.
     ///
     /// When no other fields are changed or methods are called this will render the full source.
+    /// Fractional positions are rounded down, use [`BlitBuffer::blit_subpixel`] to draw between pixels.
     ///
     /// # Sets field(s)
     ///
@@ -170,7 +503,7 @@ impl BlitOptions {
         X: ToPrimitive,
         Y: ToPrimitive,
     {
-        let (x, y) = (x.to_i32().unwrap_or(0), y.to_i32().unwrap_or(0));
+        let (x, y) = (position(x), position(y));
 
         Self {
             x,
@@ -193,7 +526,7 @@ impl BlitOptions {
         X: ToPrimitive,
         Y: ToPrimitive,
     {
-        let (x, y) = (x.to_i32().unwrap_or(0), y.to_i32().unwrap_or(0));
+        let (x, y) = (position(x), position(y));
 
         Self {
             x,
@@ -245,7 +578,7 @@ impl BlitOptions {
     /// # Sets field(s)
     ///
     /// - [`BlitOptions::sub_rect`]
-    /// - [`BlitOptions::area`] to `(width, height)` if it's `None`
+    /// - [`BlitOptions::area`] to `(width, height)` if it's `None`, swapped when [`BlitOptions::rotation`] is a quarter turn
     #[must_use]
     pub fn with_sub_rect<R>(mut self, sub_rect: R) -> Self
     where
@@ -256,6 +589,23 @@ impl BlitOptions {
         self
     }
 
+    /// Set which part of the source buffer to render when it's stored rotated a quarter turn clockwise, see [`BlitOptions::set_rotated_sub_rect`].
+    ///
+    /// # Sets field(s)
+    ///
+    /// - [`BlitOptions::sub_rect`]
+    /// - [`BlitOptions::rotation`] rotated back a quarter turn
+    /// - [`BlitOptions::area`] to the size after rotating if it's `None`
+    #[must_use]
+    pub fn with_rotated_sub_rect<R>(mut self, sub_rect: R) -> Self
+    where
+        R: Into<SubRect>,
+    {
+        self.set_rotated_sub_rect(sub_rect);
+
+        self
+    }
+
     /// Draw as a scalable [9-slice graphic](https://en.wikipedia.org/wiki/9-slice_scaling).
     ///
     /// The sub-rectangle of the center piece that will be scaled needs to be passed.
@@ -275,6 +625,50 @@ impl BlitOptions {
         self
     }
 
+    /// Draw as a horizontally scalable bar, such as a health bar or a button, where the left and right ends keep their width.
+    ///
+    /// The widths of the left and right ends are passed, the middle is scaled.
+    ///
+    /// ```rust
+    /// # use blit::{BlitOptions, slice::Slice};
+    /// assert_eq!(
+    ///     BlitOptions::new().with_hslice3(2, 3).vertical_slice,
+    ///     Some(Slice::ends(2, 3))
+    /// );
+    /// ```
+    ///
+    /// # Sets field(s)
+    ///
+    /// - [`BlitOptions::vertical_slice`]
+    #[must_use]
+    pub fn with_hslice3<L, R>(mut self, left: L, right: R) -> Self
+    where
+        L: ToPrimitive,
+        R: ToPrimitive,
+    {
+        self.vertical_slice = Some(Slice::ends(left, right));
+
+        self
+    }
+
+    /// Draw as a vertically scalable bar where the top and bottom ends keep their height.
+    ///
+    /// The heights of the top and bottom ends are passed, the middle is scaled.
+    ///
+    /// # Sets field(s)
+    ///
+    /// - [`BlitOptions::horizontal_slice`]
+    #[must_use]
+    pub fn with_vslice3<T, B>(mut self, top: T, bottom: B) -> Self
+    where
+        T: ToPrimitive,
+        B: ToPrimitive,
+    {
+        self.horizontal_slice = Some(Slice::ends(top, bottom));
+
+        self
+    }
+
     /// Scale a single horizontal piece of the buffer while keeping the other parts the same height.
     ///
     /// See [`crate::slice::Slice`] for more information.
@@ -301,6 +695,8 @@ impl BlitOptions {
 
     /// Set the render position on the target `(x, y)`.
     ///
+    /// Fractional positions are rounded down, use [`BlitBuffer::blit_subpixel`] to draw between pixels.
+    ///
     /// # Sets field(s)
     ///
     /// - [`BlitOptions::x`]
@@ -311,539 +707,5803 @@ impl BlitOptions {
         X: ToPrimitive,
         Y: ToPrimitive,
     {
-        self.x = x.to_i32().unwrap_or_default();
-        self.y = y.to_i32().unwrap_or_default();
+        self.x = position(x);
+        self.y = position(y);
 
         self
     }
 
-    /// Set the position `(x, y)`.
+    /// Place a point of the drawn area at the position instead of the top left.
+    ///
+    /// Either one of the standard anchors or a custom pivot `(x, y)` relative to the top left of the area on the destination can be passed.
+    ///
+    /// ```rust
+    /// # use blit::{Anchor, Blit, BlitBuffer, BlitOptions, geom::Size};
+    /// let blit = BlitBuffer::from_buffer(&[0xFF_FF_FF_FF; 4], 2, 127);
+    /// let mut buffer = [0; 9];
+    ///
+    /// // Draw with the bottom right pixel at the center
+    /// blit.blit(&mut buffer, Size::new(3, 3), &BlitOptions::new_position(1, 1).with_anchor((1, 1)));
+    /// assert_eq!(buffer[0], 0xFF_FF_FF_FF);
+    /// assert_eq!(buffer[8], 0);
+    ///
+    /// // Draw centered on the bottom right pixel
+    /// let mut buffer = [0; 9];
+    /// blit.blit(&mut buffer, Size::new(3, 3), &BlitOptions::new_position(2, 2).with_anchor(Anchor::Center));
+    /// assert_eq!(buffer[0], 0);
+    /// assert_eq!(buffer[4], 0xFF_FF_FF_FF);
+    /// ```
     ///
     /// # Sets field(s)
     ///
-    /// - [`BlitOptions::x`]
-    /// - [`BlitOptions::y`]
-    pub fn set_position<P>(&mut self, position: P)
+    /// - [`BlitOptions::anchor`]
+    #[must_use]
+    pub fn with_anchor<A>(mut self, anchor: A) -> Self
     where
-        P: Into<(i32, i32)>,
+        A: Into<Anchor>,
     {
-        let (x, y) = position.into();
-
-        self.x = x;
-        self.y = y;
-    }
+        self.set_anchor(anchor);
 
-    /// Get the position `(x, y)`.
-    pub fn position(&self) -> (i32, i32) {
-        (self.x, self.y)
+        self
     }
 
-    /// Get the destination area `(width, height)`.
+    /// Move the render position and the mask by `(x, y)`.
     ///
-    /// If [`BlitOptions::area`] is `None` the size of the source will be returned.
-    pub fn area<S>(&self, source_size: S) -> Size
+    /// Useful for converting options relative to a parent into absolute options.
+    /// The coordinates saturate instead of overflowing.
+    ///
+    /// # Sets field(s)
+    ///
+    /// - [`BlitOptions::x`]
+    /// - [`BlitOptions::y`]
+    /// - [`BlitOptions::mask`] if it's set
+    #[must_use]
+    pub fn offset_by<C>(mut self, delta: C) -> Self
     where
-        S: Into<Size>,
+        C: Into<Coordinate>,
     {
-        self.area.unwrap_or(source_size.into())
+        let delta = delta.into();
+
+        self.x = self.x.saturating_add(delta.x);
+        self.y = self.y.saturating_add(delta.y);
+
+        if let Some(mask) = &mut self.mask {
+            mask.x = mask.x.saturating_add(delta.x);
+            mask.y = mask.y.saturating_add(delta.y);
+        }
+
+        self
     }
 
-    /// Set which part of the source buffer to render.
-    ///
-    /// - When `None` is used, `(0, 0, source_width, source_height)` is set instead.
-    /// - With `Some(..)`, the values in the tuple are `(x, y, width, height)`.
+    /// Only show the part of the blit that falls inside the rectangle on the destination buffer.
     ///
-    /// This is similar to UV coordinates but instead of relative positions in the range of `0..1` this takes absolute positions in the range `0..width` for horizontal positions and `0..height` for vertical positions.
+    /// If a mask is already set it will be intersected with the rectangle.
     ///
     /// # Sets field(s)
     ///
-    /// - [`BlitOptions::sub_rect`]
-    /// - [`BlitOptions::area`] to `(width, height)` if it's `None`
-    pub fn set_sub_rect<R>(&mut self, sub_rect: R)
+    /// - [`BlitOptions::mask`]
+    #[must_use]
+    pub fn clipped_by<R>(mut self, rect: R) -> Self
     where
         R: Into<SubRect>,
     {
-        let sub_rect = sub_rect.into();
-        self.sub_rect = Some(sub_rect);
+        let rect = rect.into();
 
-        // Don't tile the image when only the subrectangle is set
-        if self.area.is_none() {
-            self.area = Some(sub_rect.size);
-        }
+        self.mask = Some(match self.mask {
+            Some(mask) => ImageView(mask).clip(rect).as_sub_rect(),
+            None => rect,
+        });
+
+        self
     }
 
-    /// Get the source area sub rectangle `(x, y, width, height)`.
+    /// Combine the options of a parent with the options of a child positioned relative to it.
     ///
-    /// - If [`BlitOptions::sub_rect`] is `None` the size of the source will be returned with `(0, 0)` as the position.
-    /// - If [`BlitOptions::sub_rect`] and [`BlitOptions::area`] are set it, the `width` and `height` will be shrunk to match those of the area.
-    pub fn sub_rect<S>(&self, source_size: S) -> SubRect
-    where
-        S: Into<Size>,
-    {
-        // Get the sub rectangle defined or from the source
-        let mut sub_rect = self
-            .sub_rect
-            .unwrap_or_else(|| SubRect::from_size(source_size));
-
-        // The sub rectangle is never allowed to be bigger than the area
-        sub_rect.size = match self.area {
-            Some(area) => sub_rect.size.min(area),
-            None => sub_rect.size,
-        };
+    /// The rules for each field are:
+    ///
+    /// - [`BlitOptions::x`] & [`BlitOptions::y`]: the child position is offset by the parent position.
+    /// - [`BlitOptions::mask`]: the child mask is offset by the parent position and intersected with the parent mask, when only one of them is set that one is used.
+    /// - [`BlitOptions::anchor`]: taken from the child, it anchors the child at its composed position.
+    /// - [`BlitOptions::area`], [`BlitOptions::sub_rect`], [`BlitOptions::vertical_slice`], [`BlitOptions::horizontal_slice`], [`BlitOptions::remainder`], [`BlitOptions::uv_offset`], [`BlitOptions::tile_spacing`] & [`BlitOptions::tile_margin`]: taken from the child, they describe the source being drawn.
+    /// - [`BlitOptions::blend`], [`BlitOptions::opacity`], [`BlitOptions::alpha_threshold`], [`BlitOptions::flip_horizontal`], [`BlitOptions::flip_vertical`], [`BlitOptions::scale`], [`BlitOptions::filter`], [`BlitOptions::rotation`] & [`BlitOptions::channel_order`]: taken from the child, they describe how the source is drawn.
+    ///
+    /// Every field taken from the child is taken as is, also when it's `None` or the default while the parent sets it, the parent only contributes its position and mask.
+    ///
+    /// ```rust
+    /// # use blit::{BlendMode, BlitOptions};
+    /// let parent = BlitOptions::new_position(10, 10)
+    ///     .with_mask((0, 0, 50, 50))
+    ///     .with_blend(BlendMode::Alpha)
+    ///     .with_opacity(0.5);
+    /// let child = BlitOptions::new_position(5, 5).with_mask((0, 0, 100, 100));
+    ///
+    /// // The blend mode and opacity of the parent are not used
+    /// assert_eq!(
+    ///     BlitOptions::compose(&parent, &child),
+    ///     BlitOptions::new_position(15, 15).with_mask((10, 10, 40, 40))
+    /// );
+    /// ```
+    #[must_use]
+    pub fn compose(parent: &BlitOptions, child: &BlitOptions) -> Self {
+        let mut composed = child.clone().offset_by(parent.position());
+        if let Some(parent_mask) = parent.mask {
+            composed = composed.clipped_by(parent_mask);
+        }
 
-        sub_rect
+        composed
     }
 
-    /// Set the size of the area `(width, height)` on the destination buffer.
-    ///
-    /// - When the area is smaller than the source buffer it effectively functions as the width and height parameters of [`BlitOptions::sub_rect`].
-    /// - When the area is bigger than the source buffer the default behaviour will be tiling.
+    /// Set what to do with the leftover space when the area is not a multiple of the tile size.
     ///
     /// # Sets field(s)
     ///
-    /// - [`BlitOptions::area`]
-    pub fn set_area<S>(&mut self, area: S)
-    where
-        S: Into<Size>,
-    {
-        self.area = Some(area.into());
+    /// - [`BlitOptions::remainder`]
+    #[must_use]
+    pub fn with_remainder(mut self, remainder: RemainderMode) -> Self {
+        self.remainder = remainder;
+
+        self
     }
 
-    /// Draw as a scalable [9-slice graphic](https://en.wikipedia.org/wiki/9-slice_scaling).
-    ///
-    /// The sub-rectangle of the center piece that will be scaled needs to be passed.
-    /// Note that the rectangle has a width and a height instead of the absolute coordinates the other slice functions accept.
+    /// Scroll the source inside of every tile.
     ///
     /// # Sets field(s)
     ///
-    /// - [`BlitOptions::vertical_slice`]
-    /// - [`BlitOptions::horizontal_slice`]
-    pub fn set_slice9<R>(&mut self, center: R)
+    /// - [`BlitOptions::uv_offset`]
+    #[must_use]
+    pub fn with_uv_offset<C>(mut self, offset: C) -> Self
     where
-        R: Into<SubRect>,
+        C: Into<Coordinate>,
     {
-        let center = center.into();
+        self.set_uv_offset(offset);
 
-        self.vertical_slice = Some(Slice::ternary(center.x, center.right()));
-        self.horizontal_slice = Some(Slice::ternary(center.y, center.bottom()));
+        self
     }
 
-    /// Set the size of the area `(width, height)` to only show on the destination buffer.
+    /// Scroll the source inside of every tile.
     ///
     /// # Sets field(s)
     ///
-    /// - [`BlitOptions::mask`]
-    pub fn set_mask<R>(&mut self, mask: R)
+    /// - [`BlitOptions::uv_offset`]
+    pub fn set_uv_offset<C>(&mut self, offset: C)
     where
-        R: Into<SubRect>,
+        C: Into<Coordinate>,
     {
-        self.mask = Some(mask.into());
+        self.uv_offset = offset.into();
     }
 
-    /// Scale a single horizontal piece of the buffer while keeping the other parts the same height.
-    ///
-    /// See [`crate::slice::Slice`] for more information.
+    /// Set the empty space between the tiles.
     ///
     /// # Sets field(s)
     ///
-    /// - [`BlitOptions::horizontal_slice`]
-    pub fn set_horizontal_slice(&mut self, slice: Slice) {
-        self.horizontal_slice = Some(slice);
+    /// - [`BlitOptions::tile_spacing`]
+    #[must_use]
+    pub fn with_tile_spacing<S>(mut self, spacing: S) -> Self
+    where
+        S: Into<Size>,
+    {
+        self.tile_spacing = Some(spacing.into());
+
+        self
     }
 
-    /// Scale a single vertical piece of the buffer while keeping the other parts the same height.
+    /// Set the empty space between the edges of the area and the tiles.
     ///
     /// # Sets field(s)
     ///
-    /// - [`BlitOptions::vertical_slice`]
-    pub fn set_vertical_slice(&mut self, slice: Slice) {
-        self.vertical_slice = Some(slice);
+    /// - [`BlitOptions::tile_margin`]
+    #[must_use]
+    pub fn with_tile_margin<S>(mut self, margin: S) -> Self
+    where
+        S: Into<Size>,
+    {
+        self.tile_margin = Some(margin.into());
+
+        self
     }
-}
 
-/// A data structure holding a color and a mask buffer to make blitting on a buffer real fast.
-#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
-#[derive(Clone)]
-pub struct BlitBuffer {
-    /// Image size in pixels.
-    size: Size,
+    /// Empty space between the tiles and around the tiles, `(spacing, margin)` with zeroes when not set.
+    pub(crate) fn tile_gaps(&self) -> (Size, Size) {
+        let zero = Size::new(0, 0);
 
-    /// Vector of colors, the highest 8 bits are alpha and the remaining 24 bits the RGB color channels.
-    data: Vec<Color>,
-}
+        (
+            self.tile_spacing.unwrap_or(zero),
+            self.tile_margin.unwrap_or(zero),
+        )
+    }
 
-impl BlitBuffer {
-    /// Create a instance from a buffer of RGBA data packed in a single `u32`.
+    /// Whether the source rectangle of the size is drawn once covering the whole area, without tiling, scrolling or margins.
+    pub(crate) fn fills_area_exactly(&self, tile_size: Size, area: Size) -> bool {
+        tile_size == area
+            && (self.scale != ScaleMode::Tile
+                || (self.uv_phase(tile_size) == (0, 0) && self.tile_gaps().1 == Size::new(0, 0)))
+    }
+
+    /// Position in a tile of the size where the source starts after wrapping [`BlitOptions::uv_offset`], `(0, 0)` when not tiling.
+    pub(crate) fn uv_phase(&self, tile_size: Size) -> (u32, u32) {
+        if self.scale != ScaleMode::Tile {
+            return (0, 0);
+        }
+
+        (
+            self.uv_offset.x.rem_euclid(tile_size.width.max(1) as i32) as u32,
+            self.uv_offset.y.rem_euclid(tile_size.height.max(1) as i32) as u32,
+        )
+    }
+
+    /// Set how the source pixels are combined with the destination pixels.
     ///
-    /// It's assumed that the alpha channel in the resulting pixel is properly set.
-    /// The alpha treshold is the offset point at which an alpha value will be used as either a transparent pixel or a colored one.
+    /// # Sets field(s)
+    ///
+    /// - [`BlitOptions::blend`]
     #[must_use]
-    pub fn from_buffer<S>(src: &[Color], width: S, alpha_treshold: u8) -> Self
+    pub fn with_blend(mut self, blend: BlendMode) -> Self {
+        self.blend = blend;
+
+        self
+    }
+
+    /// Mirror the drawn area around the vertical axis, so the left side is drawn at the right.
+    ///
+    /// # Sets field(s)
+    ///
+    /// - [`BlitOptions::flip_horizontal`]
+    #[must_use]
+    pub fn with_flip_horizontal(mut self, flip: bool) -> Self {
+        self.flip_horizontal = flip;
+
+        self
+    }
+
+    /// Mirror the drawn area around the horizontal axis, so the top side is drawn at the bottom.
+    ///
+    /// # Sets field(s)
+    ///
+    /// - [`BlitOptions::flip_vertical`]
+    #[must_use]
+    pub fn with_flip_vertical(mut self, flip: bool) -> Self {
+        self.flip_vertical = flip;
+
+        self
+    }
+
+    /// Set how the source is scaled when the area is a different size than the source.
+    ///
+    /// # Sets field(s)
+    ///
+    /// - [`BlitOptions::scale`]
+    #[must_use]
+    pub fn with_scale(mut self, scale: ScaleMode) -> Self {
+        self.scale = scale;
+
+        self
+    }
+
+    /// Set how the source is sampled when it's stretched with [`ScaleMode::Stretch`].
+    ///
+    /// # Sets field(s)
+    ///
+    /// - [`BlitOptions::filter`]
+    #[must_use]
+    pub fn with_filter(mut self, filter: Filter) -> Self {
+        self.filter = filter;
+
+        self
+    }
+
+    /// Rotate the source in quarter turns.
+    ///
+    /// # Sets field(s)
+    ///
+    /// - [`BlitOptions::rotation`]
+    #[must_use]
+    pub fn with_rotation(mut self, rotation: Rotation) -> Self {
+        self.rotation = rotation;
+
+        self
+    }
+
+    /// Set how the channels of the destination pixels are packed.
+    ///
+    /// # Sets field(s)
+    ///
+    /// - [`BlitOptions::channel_order`]
+    #[must_use]
+    pub fn with_channel_order(mut self, channel_order: ChannelOrder) -> Self {
+        self.channel_order = channel_order;
+
+        self
+    }
+
+    /// Fade the whole source by multiplying the alpha of every pixel.
+    ///
+    /// The opacity is clamped to the range `0.0..=1.0`.
+    ///
+    /// # Sets field(s)
+    ///
+    /// - [`BlitOptions::opacity`]
+    #[must_use]
+    pub fn with_opacity(mut self, opacity: f32) -> Self {
+        self.set_opacity(opacity);
+
+        self
+    }
+
+    /// Only draw source pixels with an alpha of at least the treshold, fully opaque.
+    ///
+    /// ```rust
+    /// # use blit::{Blit, BlitBuffer, BlitOptions, geom::Size};
+    /// let blit = BlitBuffer::from_buffer_with_alpha(&[0x40_00_00_01, 0xC0_00_00_02], 2);
+    /// let mut buffer = [0; 2];
+    ///
+    /// blit.blit(&mut buffer, Size::new(2, 1), &BlitOptions::new().with_alpha_threshold(0x80));
+    /// assert_eq!(buffer, [0, 0xFF_00_00_02]);
+    /// ```
+    ///
+    /// # Sets field(s)
+    ///
+    /// - [`BlitOptions::alpha_threshold`]
+    #[must_use]
+    pub fn with_alpha_threshold(mut self, alpha_threshold: u8) -> Self {
+        self.set_alpha_threshold(alpha_threshold);
+
+        self
+    }
+
+    /// Set the position `(x, y)`.
+    ///
+    /// # Sets field(s)
+    ///
+    /// - [`BlitOptions::x`]
+    /// - [`BlitOptions::y`]
+    pub fn set_position<P>(&mut self, position: P)
     where
-        S: ToPrimitive,
+        P: Into<(i32, i32)>,
     {
-        Self::from_iter(src.iter().copied(), width, alpha_treshold)
+        let (x, y) = position.into();
+
+        self.x = x;
+        self.y = y;
     }
 
-    /// Create a instance from a iterator of RGBA data packed in a single `u32`.
+    /// Place a point of the drawn area at the position instead of the top left.
     ///
-    /// It's assumed that the alpha channel in the resulting pixel is properly set.
-    /// The alpha treshold is the offset point at which an alpha value will be used as either a transparent pixel or a colored one.
-    #[must_use]
-    pub fn from_iter<I, S>(iter: I, width: S, alpha_treshold: u8) -> Self
+    /// # Sets field(s)
+    ///
+    /// - [`BlitOptions::anchor`]
+    pub fn set_anchor<A>(&mut self, anchor: A)
     where
-        I: Iterator<Item = Color>,
-        S: ToPrimitive,
+        A: Into<Anchor>,
     {
-        // Shift the alpha to the highest bits so we can do a direct comparison without needing to shift every pixel again
-        let alpha_treshold = (alpha_treshold as Color) << 24;
+        self.anchor = anchor.into();
+    }
+
+    /// Fade the whole source by multiplying the alpha of every pixel.
+    ///
+    /// The opacity is clamped to the range `0.0..=1.0`.
+    ///
+    /// # Sets field(s)
+    ///
+    /// - [`BlitOptions::opacity`]
+    pub fn set_opacity(&mut self, opacity: f32) {
+        self.opacity = Some(opacity.clamp(0.0, 1.0));
+    }
+
+    /// Only draw source pixels with an alpha of at least the treshold, fully opaque.
+    ///
+    /// # Sets field(s)
+    ///
+    /// - [`BlitOptions::alpha_threshold`]
+    pub fn set_alpha_threshold(&mut self, alpha_threshold: u8) {
+        self.alpha_threshold = Some(alpha_threshold);
+    }
+
+    /// Alpha treshold shifted to the alpha bits of a pixel, see [`BlitBuffer::apply_alpha_threshold`].
+    pub(crate) fn alpha_threshold_bits(&self) -> Option<Color> {
+        self.alpha_threshold
+            .map(|alpha_threshold| (alpha_threshold.max(1) as Color) << 24)
+    }
+
+    /// Opacity as an 8-bit alpha value.
+    pub(crate) fn opacity_alpha(&self) -> Color {
+        match self.opacity {
+            Some(opacity) => (opacity.clamp(0.0, 1.0) * 255.0).round() as Color,
+            None => 0xFF,
+        }
+    }
+
+    /// Get the position `(x, y)`.
+    pub fn position(&self) -> (i32, i32) {
+        (self.x, self.y)
+    }
+
+    /// Offset from the top left of a drawn area with the size to the anchor.
+    pub(crate) fn anchor_offset(&self, area: Size) -> Coordinate {
+        self.anchor.offset(area)
+    }
+
+    /// Rectangle on the destination buffer that will be touched when drawing a source of the size, `None` when nothing will be drawn.
+    pub(crate) fn effective_rect(&self, source_size: Size, dst_size: Size) -> Option<SubRect> {
+        let options = self.anchored(self.area(source_size));
+
+        // Nothing is drawn when the sub rectangle falls outside of the source
+        ImageView::full(source_size).sub(options.sub_rect(source_size))?;
+
+        let mut dst_area =
+            ImageView::full(dst_size).sub_i32(options.x, options.y, options.area(source_size))?;
+        if let Some(mask) = options.mask {
+            dst_area = dst_area.clip(mask);
+        }
+
+        if dst_area.width() == 0 || dst_area.height() == 0 {
+            None
+        } else {
+            Some(dst_area.as_sub_rect())
+        }
+    }
+
+    /// Compare the drawn rectangle of a source of the size with the rectangle that would be drawn without any clipping.
+    pub(crate) fn visibility(&self, source_size: Size, rect: Option<SubRect>) -> Visibility {
+        let area = self.area(source_size);
+        let anchor = self.anchor_offset(area);
+        let requested = SubRect::new(self.x - anchor.x, self.y - anchor.y, area);
+
+        match rect {
+            Some(rect) if rect == requested => Visibility::Full,
+            Some(_) => Visibility::Partial,
+            None => Visibility::Culled,
+        }
+    }
+
+    /// Options with the position moved so the top left of a drawn area with the size is at the position.
+    ///
+    /// The anchor is removed so moving it twice is impossible, the options are only cloned when the anchor moves the area.
+    pub(crate) fn anchored(&self, area: Size) -> Cow<'_, Self> {
+        let offset = self.anchor_offset(area);
+        if offset == Coordinate::default() {
+            return Cow::Borrowed(self);
+        }
+
+        let mut options = self.clone();
+        options.x -= offset.x;
+        options.y -= offset.y;
+        options.anchor = Anchor::TopLeft;
+
+        Cow::Owned(options)
+    }
+
+    /// Get the destination area `(width, height)`.
+    ///
+    /// If [`BlitOptions::area`] is `None` the size of the source will be returned, with the width and height swapped when rotated a quarter turn.
+    pub fn area<S>(&self, source_size: S) -> Size
+    where
+        S: Into<Size>,
+    {
+        self.area
+            .unwrap_or_else(|| self.rotated_size(source_size.into()))
+    }
+
+    /// Swap the width and height when rotated a quarter turn.
+    ///
+    /// Converts the size of the area before rotating to the size after rotating and vice versa.
+    pub(crate) fn rotated_size(&self, size: Size) -> Size {
+        if self.rotation.is_quarter_turn() {
+            Size::new(size.height, size.width)
+        } else {
+            size
+        }
+    }
+
+    /// How the area is transformed by the rotation and the flips, as `(transpose, flip_horizontal, flip_vertical)`.
+    ///
+    /// Every rotation can be expressed as swapping the axes followed by mirroring.
+    pub(crate) fn transform(&self) -> (bool, bool, bool) {
+        let (transpose, flip_horizontal, flip_vertical) = match self.rotation {
+            Rotation::None => (false, false, false),
+            Rotation::Clockwise90 => (true, true, false),
+            Rotation::Clockwise180 => (false, true, true),
+            Rotation::Clockwise270 => (true, false, true),
+        };
+
+        (
+            transpose,
+            flip_horizontal ^ self.flip_horizontal,
+            flip_vertical ^ self.flip_vertical,
+        )
+    }
+
+    /// Map a rectangle relative to the area before it's rotated and flipped to the transformed area.
+    pub(crate) fn place_in_area(&self, rect: SubRect, area: Size) -> SubRect {
+        let (transpose, flip_horizontal, flip_vertical) = self.transform();
+
+        let mut rect = if transpose {
+            SubRect::new(rect.y, rect.x, (rect.height(), rect.width()))
+        } else {
+            rect
+        };
+        if flip_horizontal {
+            rect.x = area.width as i32 - rect.right();
+        }
+        if flip_vertical {
+            rect.y = area.height as i32 - rect.bottom();
+        }
+
+        rect
+    }
+
+    /// Whether the source rectangle is drawn pixel for pixel, without slicing, tiling, scaling, flipping or rotating.
+    pub(crate) fn is_exact(&self, size: Size) -> bool {
+        self.vertical_slice.is_none()
+            && self.horizontal_slice.is_none()
+            && self.transform() == (false, false, false)
+            && ImageView::full(size)
+                .sub(self.sub_rect(size))
+                .is_some_and(|sub_rect_view| {
+                    self.fills_area_exactly(sub_rect_view.size(), self.area(size))
+                })
+    }
+
+    /// Part of the destination drawn by an exact blit, see [`BlitOptions::is_exact`], with the position of its first pixel in the source.
+    ///
+    /// Returns `None` when nothing is drawn.
+    pub(crate) fn exact_placement(
+        &self,
+        size: Size,
+        dst_size: Size,
+    ) -> Option<(ImageView, usize, usize)> {
+        let options = self.anchored(self.area(size));
+        let sub_rect_view = ImageView::full(size).sub(options.sub_rect(size))?;
+        let mut dst_area =
+            ImageView::full(dst_size).sub_i32(options.x, options.y, options.area(size))?;
+        if let Some(mask) = options.mask {
+            dst_area = dst_area.clip(mask);
+
+            // When it's fully clipped do nothing
+            if dst_area.width() == 0 || dst_area.height() == 0 {
+                return None;
+            }
+        }
+
+        // Shift the source with the amount of clipped pixels
+        let (dst_x, dst_y) = dst_area.coord();
+        let (src_x, src_y) = sub_rect_view.coord();
+
+        Some((
+            dst_area,
+            (src_x + dst_x - options.x) as usize,
+            (src_y + dst_y - options.y) as usize,
+        ))
+    }
+
+    /// Set which part of the source buffer to render.
+    ///
+    /// - When `None` is used, `(0, 0, source_width, source_height)` is set instead.
+    /// - With `Some(..)`, the values in the tuple are `(x, y, width, height)`.
+    ///
+    /// This is similar to UV coordinates but instead of relative positions in the range of `0..1` this takes absolute positions in the range `0..width` for horizontal positions and `0..height` for vertical positions.
+    ///
+    /// # Sets field(s)
+    ///
+    /// - [`BlitOptions::sub_rect`]
+    /// - [`BlitOptions::area`] to `(width, height)` if it's `None`, swapped when [`BlitOptions::rotation`] is a quarter turn
+    pub fn set_sub_rect<R>(&mut self, sub_rect: R)
+    where
+        R: Into<SubRect>,
+    {
+        let sub_rect = sub_rect.into();
+        self.sub_rect = Some(sub_rect);
+
+        // Don't tile the image when only the subrectangle is set
+        if self.area.is_none() {
+            self.area = Some(self.rotated_size(sub_rect.size));
+        }
+    }
+
+    /// Set which part of the source buffer to render when it's stored rotated a quarter turn clockwise, as texture atlas packers do to fit frames tighter.
+    ///
+    /// The part is rotated back when drawing, on top of the rotation that's already set.
+    /// Set the rotation before calling this, setting it afterwards replaces the rotation back.
+    ///
+    /// ```rust
+    /// # use blit::{Blit, BlitBuffer, BlitOptions, geom::{Size, SubRect}};
+    /// // A 1x2 frame with 1 at the top stored rotated, next to another pixel
+    /// let blit = BlitBuffer::from_buffer(&[0xFF_00_00_02, 0xFF_00_00_01, 0xFF_00_00_03], 3, 127);
+    /// let mut buffer = [0; 2];
+    ///
+    /// let options = BlitOptions::new().with_rotated_sub_rect(SubRect::new(0, 0, (2, 1)));
+    /// blit.blit(&mut buffer, Size::new(1, 2), &options);
+    /// assert_eq!(buffer.map(|pixel| pixel & 0xFF), [1, 2]);
+    /// ```
+    ///
+    /// # Sets field(s)
+    ///
+    /// - [`BlitOptions::sub_rect`]
+    /// - [`BlitOptions::rotation`] rotated back a quarter turn
+    /// - [`BlitOptions::area`] to the size after rotating if it's `None`
+    pub fn set_rotated_sub_rect<R>(&mut self, sub_rect: R)
+    where
+        R: Into<SubRect>,
+    {
+        self.rotation = self.rotation.then(Rotation::Clockwise270);
+        self.set_sub_rect(sub_rect);
+    }
+
+    /// Get the source area sub rectangle `(x, y, width, height)`.
+    ///
+    /// - If [`BlitOptions::sub_rect`] is `None` the size of the source will be returned with `(0, 0)` as the position.
+    /// - If [`BlitOptions::sub_rect`] and [`BlitOptions::area`] are set it, the `width` and `height` will be shrunk to match those of the area before rotating.
+    ///   When stretching with [`ScaleMode::Stretch`] it's never shrunk.
+    pub fn sub_rect<S>(&self, source_size: S) -> SubRect
+    where
+        S: Into<Size>,
+    {
+        // Get the sub rectangle defined or from the source
+        let mut sub_rect = self
+            .sub_rect
+            .unwrap_or_else(|| SubRect::from_size(source_size));
+
+        // The sub rectangle is never allowed to be bigger than the area when tiling
+        sub_rect.size = match (self.area, self.scale) {
+            (Some(area), ScaleMode::Tile) => sub_rect.size.min(self.rotated_size(area)),
+            _ => sub_rect.size,
+        };
+
+        sub_rect
+    }
+
+    /// Set the size of the area `(width, height)` on the destination buffer.
+    ///
+    /// - When the area is smaller than the source buffer it effectively functions as the width and height parameters of [`BlitOptions::sub_rect`].
+    /// - When the area is bigger than the source buffer the default behaviour will be tiling.
+    ///
+    /// # Sets field(s)
+    ///
+    /// - [`BlitOptions::area`]
+    pub fn set_area<S>(&mut self, area: S)
+    where
+        S: Into<Size>,
+    {
+        self.area = Some(area.into());
+    }
+
+    /// Draw as a scalable [9-slice graphic](https://en.wikipedia.org/wiki/9-slice_scaling).
+    ///
+    /// The sub-rectangle of the center piece that will be scaled needs to be passed.
+    /// Note that the rectangle has a width and a height instead of the absolute coordinates the other slice functions accept.
+    ///
+    /// # Sets field(s)
+    ///
+    /// - [`BlitOptions::vertical_slice`]
+    /// - [`BlitOptions::horizontal_slice`]
+    pub fn set_slice9<R>(&mut self, center: R)
+    where
+        R: Into<SubRect>,
+    {
+        let center = center.into();
+
+        self.vertical_slice = Some(Slice::ternary(center.x, center.right()));
+        self.horizontal_slice = Some(Slice::ternary(center.y, center.bottom()));
+    }
+
+    /// Set the size of the area `(width, height)` to only show on the destination buffer.
+    ///
+    /// # Sets field(s)
+    ///
+    /// - [`BlitOptions::mask`]
+    pub fn set_mask<R>(&mut self, mask: R)
+    where
+        R: Into<SubRect>,
+    {
+        self.mask = Some(mask.into());
+    }
+
+    /// Scale a single horizontal piece of the buffer while keeping the other parts the same height.
+    ///
+    /// See [`crate::slice::Slice`] for more information.
+    ///
+    /// # Sets field(s)
+    ///
+    /// - [`BlitOptions::horizontal_slice`]
+    pub fn set_horizontal_slice(&mut self, slice: Slice) {
+        self.horizontal_slice = Some(slice);
+    }
+
+    /// Scale a single vertical piece of the buffer while keeping the other parts the same height.
+    ///
+    /// # Sets field(s)
+    ///
+    /// - [`BlitOptions::vertical_slice`]
+    pub fn set_vertical_slice(&mut self, slice: Slice) {
+        self.vertical_slice = Some(slice);
+    }
+}
+
+/// What to do with the leftover space when tiling an area that's not a multiple of the tile size.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum RemainderMode {
+    /// Start with a whole tile at the left and top, the tiles at the right and bottom are clipped.
+    #[default]
+    TrailingClip,
+    /// Center the tiles in the area, the tiles at all edges are clipped equally.
+    Centered,
+    /// Only draw whole tiles, leaving the leftover space untouched.
+    None,
+}
+
+impl RemainderMode {
+    /// Divide a single dimension of the area into `(target_offset, source_offset, length)` tiles.
+    ///
+    /// The tiles are separated by the spacing and kept away from both edges of the area by the margin, the source of every tile is scrolled by the offset.
+    pub(crate) fn tile_segments(
+        &self,
+        area_length: u32,
+        tile_length: u32,
+        spacing: u32,
+        margin: u32,
+        offset: u32,
+    ) -> impl Iterator<Item = (u32, u32, u32)> {
+        let inner_length = area_length.saturating_sub(margin.saturating_mul(2));
+        let start = self.first_whole_tile(inner_length, tile_length, spacing);
+        let period = tile_length.saturating_add(spacing);
+
+        // Partial tile before the first whole tile, showing the end of the tile
+        let leading = start.saturating_sub(spacing);
+        // The first whole tile and every tile after it, the last one can be clipped
+        let tiles = inner_length.saturating_sub(start).div_ceil(period);
+        let whole_only = *self == RemainderMode::None;
+
+        std::iter::once((0, tile_length - leading, leading))
+            .chain((0..tiles).map(move |tile| {
+                let target = start + tile * period;
+
+                (target, 0, tile_length.min(inner_length - target))
+            }))
+            .filter(move |(_, _, length)| !whole_only || *length == tile_length)
+            .map(move |(target, source, length)| (target + margin, source, length))
+            // Split the tiles where the scrolled source wraps around
+            .flat_map(move |(target, source, length)| {
+                let source = (source + offset) % tile_length;
+                let before_wrap = length.min(tile_length - source);
+
+                [
+                    (target, source, before_wrap),
+                    (target + before_wrap, 0, length - before_wrap),
+                ]
+            })
+            // Remove the empty partial tiles
+            .filter(|(_, _, length)| *length > 0)
+    }
+
+    /// Position of the first whole tile in the space between the margins.
+    pub(crate) fn first_whole_tile(
+        &self,
+        inner_length: u32,
+        tile_length: u32,
+        spacing: u32,
+    ) -> u32 {
+        match self {
+            RemainderMode::Centered => {
+                // Center the whole tiles with the spacing between them
+                let tiles = match inner_length.checked_sub(tile_length) {
+                    Some(leftover) => leftover / tile_length.saturating_add(spacing) + 1,
+                    None => 0,
+                };
+                let used = tiles * tile_length + tiles.saturating_sub(1) * spacing;
+
+                (inner_length - used) / 2
+            }
+            _ => 0,
+        }
+    }
+}
+
+/// How the source is scaled to fill an area with a different size.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum ScaleMode {
+    /// Repeat the source to fill the area, the leftover space is handled with [`BlitOptions::remainder`].
+    ///
+    /// When the area is smaller than the source only the top left part of the source is drawn.
+    #[default]
+    Tile,
+    /// Resample the source with nearest-neighbor sampling to fill the area exactly.
+    Stretch,
+}
+
+/// How a stretched source is sampled.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum Filter {
+    /// Use the nearest pixel, keeps the sharp edges of pixel art.
+    #[default]
+    Nearest,
+    /// Interpolate between the 2x2 nearest pixels before blending, makes scaled sprites look smooth.
+    ///
+    /// The interpolated pixels at transparent edges are partially transparent, combine with [`BlendMode::Alpha`] to draw them smoothly.
+    Bilinear,
+}
+
+/// Point of the drawn area that is placed at the position.
+///
+/// The edges are outside of the area, so with [`Anchor::BottomRight`] the bottom right pixel of the area is drawn left and above the position.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum Anchor {
+    /// Top left corner.
+    #[default]
+    TopLeft,
+    /// Center of the top edge.
+    Top,
+    /// Top right corner.
+    TopRight,
+    /// Center of the left edge.
+    Left,
+    /// Center of the area, rounded down for odd sizes.
+    Center,
+    /// Center of the right edge.
+    Right,
+    /// Bottom left corner.
+    BottomLeft,
+    /// Center of the bottom edge, useful for characters whose feet should be on the ground.
+    Bottom,
+    /// Bottom right corner.
+    BottomRight,
+    /// Custom point relative to the top left of the area.
+    Pivot(Coordinate),
+}
+
+impl Anchor {
+    /// Offset from the top left of an area with the size to the anchor.
+    ///
+    /// ```rust
+    /// # use blit::{Anchor, geom::{Coordinate, Size}};
+    /// assert_eq!(Anchor::Center.offset(Size::new(5, 4)), Coordinate::new(2, 2));
+    /// assert_eq!(Anchor::BottomRight.offset(Size::new(5, 4)), Coordinate::new(5, 4));
+    /// ```
+    pub fn offset(&self, area: Size) -> Coordinate {
+        let (width, height) = (area.width as i32, area.height as i32);
+
+        let (x, y) = match *self {
+            Anchor::TopLeft => (0, 0),
+            Anchor::Top => (width / 2, 0),
+            Anchor::TopRight => (width, 0),
+            Anchor::Left => (0, height / 2),
+            Anchor::Center => (width / 2, height / 2),
+            Anchor::Right => (width, height / 2),
+            Anchor::BottomLeft => (0, height),
+            Anchor::Bottom => (width / 2, height),
+            Anchor::BottomRight => (width, height),
+            Anchor::Pivot(pivot) => return pivot,
+        };
+
+        Coordinate::new(x, y)
+    }
+}
+
+impl From<Coordinate> for Anchor {
+    fn from(pivot: Coordinate) -> Self {
+        Anchor::Pivot(pivot)
+    }
+}
+
+impl<X, Y> From<(X, Y)> for Anchor
+where
+    X: ToPrimitive,
+    Y: ToPrimitive,
+{
+    fn from(pivot: (X, Y)) -> Self {
+        Anchor::Pivot(pivot.into())
+    }
+}
+
+/// Rotation of the source in clockwise quarter turns.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum Rotation {
+    /// Don't rotate.
+    #[default]
+    None,
+    /// Rotate a quarter turn clockwise, the left side of the source is drawn at the top.
+    Clockwise90,
+    /// Rotate half a turn, the source is drawn upside down.
+    Clockwise180,
+    /// Rotate three quarter turns clockwise, the left side of the source is drawn at the bottom.
+    Clockwise270,
+}
+
+impl Rotation {
+    /// Whether the width and height are swapped by the rotation.
+    pub fn is_quarter_turn(&self) -> bool {
+        matches!(self, Rotation::Clockwise90 | Rotation::Clockwise270)
+    }
+
+    /// Rotation resulting from rotating by this rotation followed by another rotation.
+    ///
+    /// ```rust
+    /// # use blit::Rotation;
+    /// assert_eq!(Rotation::Clockwise90.then(Rotation::Clockwise270), Rotation::None);
+    /// assert_eq!(Rotation::Clockwise180.then(Rotation::Clockwise270), Rotation::Clockwise90);
+    /// ```
+    #[must_use]
+    pub fn then(&self, rotation: Rotation) -> Self {
+        match (self.quarter_turns() + rotation.quarter_turns()) % 4 {
+            0 => Rotation::None,
+            1 => Rotation::Clockwise90,
+            2 => Rotation::Clockwise180,
+            _ => Rotation::Clockwise270,
+        }
+    }
+
+    /// Amount of clockwise quarter turns.
+    fn quarter_turns(&self) -> u8 {
+        match self {
+            Rotation::None => 0,
+            Rotation::Clockwise90 => 1,
+            Rotation::Clockwise180 => 2,
+            Rotation::Clockwise270 => 3,
+        }
+    }
+}
+
+/// Order of the color channels packed in a `u32` pixel, from the highest to the lowest 8 bits.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum ChannelOrder {
+    /// Alpha in the highest bits followed by red, green and blue, the packing used by this crate.
+    ///
+    /// As bytes on a little-endian machine this is BGRA, used by Windows GDI.
+    #[default]
+    Argb,
+    /// Alpha in the highest bits followed by blue, green and red.
+    ///
+    /// As bytes on a little-endian machine this is RGBA, used by the `pixels` crate.
+    Abgr,
+    /// Red in the highest bits followed by green, blue and alpha.
+    Rgba,
+    /// Blue in the highest bits followed by green, red and alpha.
+    Bgra,
+}
+
+impl ChannelOrder {
+    /// Convert a pixel packed as [`ChannelOrder::Argb`] to this order.
+    ///
+    /// ```rust
+    /// # use blit::ChannelOrder;
+    /// assert_eq!(ChannelOrder::Abgr.pack(0xAA_11_22_33), 0xAA_33_22_11);
+    /// assert_eq!(ChannelOrder::Rgba.pack(0xAA_11_22_33), 0x11_22_33_AA);
+    /// ```
+    #[inline(always)]
+    pub fn pack(&self, pixel: Color) -> Color {
+        match self {
+            ChannelOrder::Argb => pixel,
+            ChannelOrder::Abgr => {
+                (pixel & 0xFF_00_FF_00) | ((pixel >> 16) & 0xFF) | ((pixel & 0xFF) << 16)
+            }
+            ChannelOrder::Rgba => pixel.rotate_left(8),
+            ChannelOrder::Bgra => pixel.swap_bytes(),
+        }
+    }
+
+    /// Convert a pixel packed in this order to [`ChannelOrder::Argb`].
+    #[inline(always)]
+    pub fn unpack(&self, pixel: Color) -> Color {
+        match self {
+            ChannelOrder::Rgba => pixel.rotate_right(8),
+            // Swapping is the same in both directions
+            order => order.pack(pixel),
+        }
+    }
+}
+
+/// Convert bytes in RGBA order to pixels packed as [`ChannelOrder::Argb`], trailing bytes that don't form a whole pixel are ignored.
+pub(crate) fn rgba_pixels(bytes: &[u8]) -> impl Iterator<Item = Color> + '_ {
+    bytes.chunks_exact(4).map(|bytes| {
+        ChannelOrder::Rgba.unpack(u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+    })
+}
+
+/// Convert a pixel packed as [`ChannelOrder::Argb`] to bytes in RGBA order.
+#[inline(always)]
+pub(crate) fn rgba_bytes(pixel: Color) -> [u8; 4] {
+    ChannelOrder::Rgba.pack(pixel).to_be_bytes()
+}
+
+/// How the difference between a color and the colors of a [`ColorKey`] is measured.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum ColorDistance {
+    /// Biggest difference of the red, green and blue channels.
+    #[default]
+    Channel,
+    /// Straight line distance between the colors with the red, green and blue channels as axes.
+    Euclidean,
+}
+
+/// Colors that become transparent when converting pixels without an alpha channel.
+///
+/// Colors that are within the tolerance of a key color match too, so halos of near-matching colors from lossy compression or scaling are removed as well.
+///
+/// ```rust
+/// # use blit::{ColorDistance, ColorKey};
+/// let key = ColorKey::new(0xFF_00_FF).with_color(0x00_FF_00).with_tolerance(8);
+/// assert!(key.matches(0xF8_04_FF));
+/// assert!(key.matches(0x00_F8_00));
+/// assert!(!key.matches(0xF0_00_FF));
+///
+/// // Every channel is 6 off, which is about 10 in a straight line
+/// assert!(!key.clone().with_distance(ColorDistance::Euclidean).matches(0xF9_06_F9));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ColorKey {
+    /// Colors packed as `0xRRGGBB`.
+    colors: Vec<Color>,
+    /// Maximum distance of a color to a key color for it to match.
+    tolerance: u8,
+    /// How the distance is measured.
+    distance: ColorDistance,
+}
+
+impl ColorKey {
+    /// Match a single color exactly, the alpha channel is ignored.
+    pub fn new(color: u32) -> Self {
+        Self {
+            colors: vec![color & 0x00_FF_FF_FF],
+            tolerance: 0,
+            distance: ColorDistance::default(),
+        }
+    }
+
+    /// Match another color, the alpha channel is ignored.
+    #[must_use]
+    pub fn with_color(mut self, color: u32) -> Self {
+        self.add_color(color);
+
+        self
+    }
+
+    /// Match another color, the alpha channel is ignored.
+    pub fn add_color(&mut self, color: u32) {
+        self.colors.push(color & 0x00_FF_FF_FF);
+    }
+
+    /// Set the maximum distance of a color to a key color for it to match, `0` only matches exactly.
+    #[must_use]
+    pub fn with_tolerance(mut self, tolerance: u8) -> Self {
+        self.set_tolerance(tolerance);
+
+        self
+    }
+
+    /// Set the maximum distance of a color to a key color for it to match, `0` only matches exactly.
+    pub fn set_tolerance(&mut self, tolerance: u8) {
+        self.tolerance = tolerance;
+    }
+
+    /// Set how the distance between colors is measured.
+    #[must_use]
+    pub fn with_distance(mut self, distance: ColorDistance) -> Self {
+        self.set_distance(distance);
+
+        self
+    }
+
+    /// Set how the distance between colors is measured.
+    pub fn set_distance(&mut self, distance: ColorDistance) {
+        self.distance = distance;
+    }
+
+    /// Colors packed as `0xRRGGBB`.
+    pub fn colors(&self) -> &[u32] {
+        &self.colors
+    }
+
+    /// Maximum distance of a color to a key color for it to match.
+    pub fn tolerance(&self) -> u8 {
+        self.tolerance
+    }
+
+    /// How the distance between colors is measured.
+    pub fn distance(&self) -> ColorDistance {
+        self.distance
+    }
+
+    /// Whether the color is within the tolerance of any of the key colors, the alpha channel is ignored.
+    pub fn matches(&self, color: u32) -> bool {
+        let tolerance = self.tolerance as Color;
+
+        self.colors.iter().any(|key| {
+            let [red, green, blue] =
+                [16, 8, 0].map(|shift| ((color >> shift) & 0xFF).abs_diff((key >> shift) & 0xFF));
+
+            match self.distance {
+                ColorDistance::Channel => red.max(green).max(blue) <= tolerance,
+                ColorDistance::Euclidean => {
+                    red * red + green * green + blue * blue <= tolerance * tolerance
+                }
+            }
+        })
+    }
+}
+
+/// How the source pixels are combined with the destination pixels.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum BlendMode {
+    /// Pixels with an alpha value bigger than zero replace the destination pixel, others are skipped.
+    ///
+    /// This is the fastest mode and should be used for buffers with a binary alpha channel.
+    #[default]
+    Mask,
+    /// Source-over compositing using the full 8-bit alpha channel.
+    ///
+    /// Only useful for buffers created with [`BlitBuffer::from_buffer_with_alpha`] or [`BlitBuffer::from_iter_with_alpha`], other buffers only contain fully transparent or fully opaque pixels.
+    Alpha,
+    /// Add the color channels to the destination, useful for glow and particle effects.
+    Add,
+    /// Multiply the color channels with the destination, useful for shadows and lighting.
+    Multiply,
+    /// Invert, multiply and invert again, the opposite of [`BlendMode::Multiply`] which brightens the destination.
+    Screen,
+    /// Subtract the color channels from the destination.
+    Subtract,
+}
+
+impl BlendMode {
+    /// Combine a source pixel with a destination pixel.
+    #[inline(always)]
+    pub(crate) fn blend(&self, dst_pixel: Color, blit_pixel: Color) -> Color {
+        match self {
+            BlendMode::Mask => {
+                // Set the pixel from the blit image if the mask value is set
+                if (blit_pixel >> 24) > 0 {
+                    // Pixel from the blit buffer is not masked, use it
+                    blit_pixel
+                } else {
+                    // Pixel from the blit buffer is masked, use the original color
+                    dst_pixel
+                }
+            }
+            BlendMode::Alpha => {
+                let src_alpha = blit_pixel >> 24;
+                match src_alpha {
+                    0 => dst_pixel,
+                    0xFF => blit_pixel,
+                    _ => {
+                        // Contribution of the destination after being covered by the source
+                        let dst_alpha = (dst_pixel >> 24) * (0xFF - src_alpha) / 0xFF;
+                        let alpha = src_alpha + dst_alpha;
+
+                        let channel = |shift: u32| -> Color {
+                            let src = (blit_pixel >> shift) & 0xFF;
+                            let dst = (dst_pixel >> shift) & 0xFF;
+
+                            ((src * src_alpha + dst * dst_alpha) / alpha) << shift
+                        };
+
+                        (alpha << 24) | channel(16) | channel(8) | channel(0)
+                    }
+                }
+            }
+            _ => {
+                let src_alpha = blit_pixel >> 24;
+                if src_alpha == 0 {
+                    return dst_pixel;
+                }
+
+                // Only the color channels are changed, the alpha of the destination is kept
+                let channel = |shift: u32| -> Color {
+                    let src = (blit_pixel >> shift) & 0xFF;
+                    let dst = (dst_pixel >> shift) & 0xFF;
+
+                    self.blend_channel(dst, src, src_alpha) << shift
+                };
+
+                (dst_pixel & 0xFF_00_00_00) | channel(16) | channel(8) | channel(0)
+            }
+        }
+    }
+
+    /// Blend mode to use for pixels faded by an opacity, masking would lose the fade.
+    pub(crate) fn faded(&self) -> Self {
+        match self {
+            BlendMode::Mask => BlendMode::Alpha,
+            blend => *blend,
+        }
+    }
+
+    /// Multiply the alpha of the pixel with the 8-bit opacity.
+    #[inline(always)]
+    pub(crate) fn fade(pixel: Color, opacity: Color) -> Color {
+        (((pixel >> 24) * opacity / 0xFF) << 24) | (pixel & 0x00_FF_FF_FF)
+    }
+
+    /// Combine a single 8-bit color channel, weighted by the alpha of the source.
+    #[inline(always)]
+    fn blend_channel(&self, dst: Color, src: Color, src_alpha: Color) -> Color {
+        // Result when the source is fully opaque
+        let opaque = match self {
+            BlendMode::Mask | BlendMode::Alpha => src,
+            BlendMode::Add => (dst + src).min(0xFF),
+            BlendMode::Multiply => dst * src / 0xFF,
+            BlendMode::Screen => 0xFF - (0xFF - dst) * (0xFF - src) / 0xFF,
+            BlendMode::Subtract => dst.saturating_sub(src),
+        };
+
+        // Interpolate between the destination and the result
+        (opaque * src_alpha + dst * (0xFF - src_alpha)) / 0xFF
+    }
+}
+
+/// Pixel type of a destination buffer.
+///
+/// Buffers are always drawn as `0xAARRGGBB` pixels, destinations with another pixel type convert every pixel that's blended from and to it.
+/// Implemented for `u32` pixels packed as `0xAARRGGBB` and `u16` pixels packed as RGB565, which are fully opaque.
+pub trait Pixel: Copy {
+    /// Convert from a pixel packed as `0xAARRGGBB`.
+    fn from_argb(color: u32) -> Self;
+
+    /// Convert to a pixel packed as `0xAARRGGBB`.
+    fn to_argb(self) -> u32;
+
+    /// Convert and copy all source pixels, override when there's a faster way than converting every pixel.
+    #[inline(always)]
+    fn copy_from_argb(dst: &mut [Self], src: &[u32]) {
+        dst.iter_mut()
+            .zip(src)
+            .for_each(|(dst_pixel, src_pixel)| *dst_pixel = Self::from_argb(*src_pixel));
+    }
+
+    /// Convert and copy the source pixels that are not fully transparent, override when there's a faster way than converting every pixel.
+    #[inline(always)]
+    fn mask_from_argb(dst: &mut [Self], src: &[u32]) {
+        dst.iter_mut()
+            .zip(src)
+            .filter(|(_, src_pixel)| *src_pixel >> 24 > 0)
+            .for_each(|(dst_pixel, src_pixel)| *dst_pixel = Self::from_argb(*src_pixel));
+    }
+}
+
+impl Pixel for u32 {
+    #[inline(always)]
+    fn from_argb(color: u32) -> Self {
+        color
+    }
+
+    #[inline(always)]
+    fn to_argb(self) -> u32 {
+        self
+    }
+
+    #[inline(always)]
+    fn copy_from_argb(dst: &mut [Self], src: &[u32]) {
+        dst.copy_from_slice(src);
+    }
+
+    #[inline(always)]
+    fn mask_from_argb(dst: &mut [Self], src: &[u32]) {
+        simd::mask(dst, src);
+    }
+}
+
+impl Pixel for u16 {
+    #[inline(always)]
+    fn from_argb(color: u32) -> Self {
+        (((color >> 8) & 0xF8_00) | ((color >> 5) & 0x07_E0) | ((color >> 3) & 0x00_1F)) as u16
+    }
+
+    #[inline(always)]
+    fn to_argb(self) -> u32 {
+        let (red, green, blue) = (
+            (self as u32 >> 11) & 0x1F,
+            (self as u32 >> 5) & 0x3F,
+            self as u32 & 0x1F,
+        );
+
+        // Repeat the highest bits in the lowest bits so white stays white
+        0xFF_00_00_00
+            | (((red << 3) | (red >> 2)) << 16)
+            | (((green << 2) | (green >> 4)) << 8)
+            | ((blue << 3) | (blue >> 2))
+    }
+}
+
+/// Operation combining a source pixel with a destination pixel, used for every drawn pixel.
+///
+/// [`BlendMode`] implements it for the built-in modes, as does every `Fn(u32, u32) -> u32` taking the destination and the source pixel.
+/// Custom operations are drawn with [`BlitBuffer::blit_blend`], the blit path is generic over it so there's no dynamic dispatch per pixel.
+pub trait PixelBlend {
+    /// Combine the pixels, returning the new destination pixel.
+    ///
+    /// Both pixels are packed as `0xAARRGGBB`, the alpha of the source pixel is already multiplied with the [`BlitOptions::opacity`].
+    fn blend(&self, dst_pixel: u32, src_pixel: u32) -> u32;
+
+    /// Whether this behaves exactly like [`BlendMode::Mask`].
+    ///
+    /// Opaque runs are then copied directly without calling [`PixelBlend::blend`], and faded pixels are blended with [`BlendMode::Alpha`].
+    fn is_mask(&self) -> bool {
+        false
+    }
+
+    /// Change a source pixel before it's drawn, before the [`BlitOptions::alpha_threshold`] and the [`BlitOptions::opacity`] are applied.
+    ///
+    /// By default the pixel is not changed.
+    #[inline(always)]
+    fn remap(&self, src_pixel: u32) -> u32 {
+        src_pixel
+    }
+}
+
+impl PixelBlend for BlendMode {
+    #[inline(always)]
+    fn blend(&self, dst_pixel: u32, src_pixel: u32) -> u32 {
+        BlendMode::blend(self, dst_pixel, src_pixel)
+    }
+
+    fn is_mask(&self) -> bool {
+        *self == BlendMode::Mask
+    }
+}
+
+impl<F> PixelBlend for F
+where
+    F: Fn(u32, u32) -> u32,
+{
+    #[inline(always)]
+    fn blend(&self, dst_pixel: u32, src_pixel: u32) -> u32 {
+        self(dst_pixel, src_pixel)
+    }
+}
+
+/// Blend mode of the options drawing the pixels that are not fully transparent changed by a function first.
+struct Remap<F> {
+    /// Blend mode used for the changed pixels.
+    blend: BlendMode,
+    /// Function changing the pixels.
+    remap: F,
+}
+
+impl<F> PixelBlend for Remap<F>
+where
+    F: Fn(u32) -> u32,
+{
+    #[inline(always)]
+    fn blend(&self, dst_pixel: u32, src_pixel: u32) -> u32 {
+        self.blend.blend(dst_pixel, src_pixel)
+    }
+
+    #[inline(always)]
+    fn remap(&self, src_pixel: u32) -> u32 {
+        if src_pixel >> 24 > 0 {
+            (self.remap)(src_pixel)
+        } else {
+            src_pixel
+        }
+    }
+}
+
+/// How much of a blit is visible on the destination buffer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Visibility {
+    /// Everything is drawn.
+    Full,
+    /// Only a part is drawn because it's clipped by the edges of the destination or the mask.
+    Partial,
+    /// Nothing is drawn.
+    Culled,
+}
+
+/// What got drawn by [`Blit::try_blit`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BlitStats {
+    /// Rectangle on the destination buffer that got drawn, `None` when nothing is drawn.
+    pub rect: Option<SubRect>,
+    /// How much of the blit is visible on the destination buffer.
+    pub visibility: Visibility,
+}
+
+/// Errors that can occur when creating buffers or drawing on them.
+#[derive(Debug, thiserror::Error, PartialEq, Eq)]
+pub enum Error {
+    /// The amount of pixels doesn't match the size of the image.
+    #[error("buffer has {len} pixels but an image of {size:?} needs {} pixels", size.pixels())]
+    SizeMismatch {
+        /// Size of the image.
+        size: Size,
+        /// Amount of pixels in the buffer.
+        len: usize,
+    },
+    /// The stride is smaller than the width or the buffer is too short for the rows.
+    #[error(
+        "buffer has {len} pixels with a stride of {stride} which can't hold an image of {size:?}"
+    )]
+    StrideMismatch {
+        /// Size of the image.
+        size: Size,
+        /// Amount of pixels from the start of a row to the start of the next row.
+        stride: usize,
+        /// Amount of pixels in the buffer.
+        len: usize,
+    },
+    /// The destination has a width or height of zero so nothing can be drawn on it.
+    #[error("destination has no pixels")]
+    EmptyDestination,
+}
+
+/// A data structure holding a color and a mask buffer to make blitting on a buffer real fast.
+///
+/// When deserialized the amount of pixels is checked against the size and fully transparent pixels are cleared.
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(try_from = "SerializedBlitBuffer"))]
+#[derive(Clone)]
+pub struct BlitBuffer {
+    /// Image size in pixels.
+    size: Size,
+
+    /// Vector of colors, the highest 8 bits are alpha and the remaining 24 bits the RGB color channels.
+    data: Vec<Color>,
+
+    /// Sorted index ranges of the data within a single row where no pixel is transparent.
+    ///
+    /// These can be copied directly when masking, it's only a hint so it's always correct to leave ranges out.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    opaque_runs: Vec<Range<usize>>,
+
+    /// Index of the first opaque run of every row followed by the amount of runs, empty when the rows are not cached.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    row_runs: Vec<usize>,
+}
+
+/// Unchecked fields of a deserialized [`BlitBuffer`].
+#[cfg(feature = "serde")]
+#[derive(Deserialize)]
+struct SerializedBlitBuffer {
+    /// Image size in pixels.
+    size: Size,
+    /// Vector of colors.
+    data: Vec<Color>,
+}
+
+#[cfg(feature = "serde")]
+impl TryFrom<SerializedBlitBuffer> for BlitBuffer {
+    type Error = Error;
+
+    fn try_from(
+        SerializedBlitBuffer { size, mut data }: SerializedBlitBuffer,
+    ) -> Result<Self, Error> {
+        // Transparent pixels are never drawn, so clearing their colors doesn't change anything
+        data.iter_mut()
+            .filter(|pixel| **pixel >> 24 == 0)
+            .for_each(|pixel| *pixel = 0);
+
+        Self::from_raw(size, data)
+    }
+}
+
+impl BlitBuffer {
+    /// Create a instance from a buffer of RGBA data packed in a single `u32`.
+    ///
+    /// It's assumed that the alpha channel in the resulting pixel is properly set.
+    /// The alpha treshold is the offset point at which an alpha value will be used as either a transparent pixel or a colored one.
+    #[must_use]
+    pub fn from_buffer<S>(src: &[Color], width: S, alpha_treshold: u8) -> Self
+    where
+        S: ToPrimitive,
+    {
+        Self::from_iter(src.iter().copied(), width, alpha_treshold)
+    }
+
+    /// Create a instance from a iterator of RGBA data packed in a single `u32`.
+    ///
+    /// It's assumed that the alpha channel in the resulting pixel is properly set.
+    /// The alpha treshold is the offset point at which an alpha value will be used as either a transparent pixel or a colored one.
+    #[must_use]
+    pub fn from_iter<I, S>(iter: I, width: S, alpha_treshold: u8) -> Self
+    where
+        I: Iterator<Item = Color>,
+        S: ToPrimitive,
+    {
+        // Shift the alpha to the highest bits so we can do a direct comparison without needing to shift every pixel again
+        let alpha_treshold = (alpha_treshold as Color) << 24;
+
+        // Create the data buffer filled with transparent pixels
+        let data = iter
+            .map(|pixel| Self::apply_alpha_threshold(pixel, alpha_treshold))
+            .collect::<Vec<_>>();
+
+        // We can calculate the height from the total buffer
+        let size = Size::from_len(data.len(), width.to_usize().unwrap_or_default());
+        let opaque_runs = Self::find_opaque_runs(&data, size);
+
+        Self {
+            size,
+            data,
+            opaque_runs,
+            row_runs: Vec::new(),
+        }
+    }
+
+    /// Create a instance from a buffer of RGB data packed in a single `u32`, making the colors matching the key transparent.
+    ///
+    /// The alpha channel is ignored, all other pixels are fully opaque.
+    ///
+    /// ```rust
+    /// # use blit::{BlitBuffer, ColorKey};
+    /// let key = ColorKey::new(0xFF_00_FF).with_tolerance(4);
+    /// let blit = BlitBuffer::from_buffer_with_color_key(&[0xFF_00_FF, 0xFE_02_FD, 0x12_34_56], 3, &key);
+    /// assert_eq!(blit.pixels(), [0, 0, 0xFF_12_34_56]);
+    /// ```
+    #[must_use]
+    pub fn from_buffer_with_color_key<S>(src: &[Color], width: S, key: &ColorKey) -> Self
+    where
+        S: ToPrimitive,
+    {
+        Self::from_iter_with_color_key(src.iter().copied(), width, key)
+    }
+
+    /// Create a instance from a iterator of RGB data packed in a single `u32`, making the colors matching the key transparent.
+    ///
+    /// The alpha channel is ignored, all other pixels are fully opaque.
+    #[must_use]
+    pub fn from_iter_with_color_key<I, S>(iter: I, width: S, key: &ColorKey) -> Self
+    where
+        I: Iterator<Item = Color>,
+        S: ToPrimitive,
+    {
+        Self::from_iter(
+            iter.map(|pixel| {
+                if key.matches(pixel) {
+                    0x00_00_00_00
+                } else {
+                    pixel | 0xFF_00_00_00
+                }
+            }),
+            width,
+            0xFF,
+        )
+    }
+
+    /// Create a instance from a buffer of RGBA data packed in a single `u32`, keeping the full alpha channel.
+    ///
+    /// The alpha channel is only used when drawing with [`BlendMode::Alpha`], with [`BlendMode::Mask`] every pixel with an alpha value bigger than zero is drawn as opaque.
+    #[must_use]
+    pub fn from_buffer_with_alpha<S>(src: &[Color], width: S) -> Self
+    where
+        S: ToPrimitive,
+    {
+        Self::from_iter_with_alpha(src.iter().copied(), width)
+    }
+
+    /// Create a instance from a iterator of RGBA data packed in a single `u32`, keeping the full alpha channel.
+    ///
+    /// The alpha channel is only used when drawing with [`BlendMode::Alpha`], with [`BlendMode::Mask`] every pixel with an alpha value bigger than zero is drawn as opaque.
+    #[must_use]
+    pub fn from_iter_with_alpha<I, S>(iter: I, width: S) -> Self
+    where
+        I: Iterator<Item = Color>,
+        S: ToPrimitive,
+    {
+        let data = iter.collect::<Vec<_>>();
+
+        // We can calculate the height from the total buffer
+        let size = Size::from_len(data.len(), width.to_usize().unwrap_or_default());
+        let opaque_runs = Self::find_opaque_runs(&data, size);
+
+        Self {
+            size,
+            data,
+            opaque_runs,
+            row_runs: Vec::new(),
+        }
+    }
+
+    /// Create a instance from pixels that are already processed, taking ownership without copying or changing them.
+    ///
+    /// The pixels are used as is with the full alpha channel, like [`BlitBuffer::from_buffer_with_alpha`].
+    ///
+    /// ```rust
+    /// # use blit::{BlitBuffer, Error, geom::Size};
+    /// let blit = BlitBuffer::from_raw(Size::new(2, 1), vec![0xFF_00_00_00, 0]).unwrap();
+    /// assert_eq!(blit.into_raw(), [0xFF_00_00_00, 0]);
+    ///
+    /// assert_eq!(
+    ///     BlitBuffer::from_raw(Size::new(2, 2), vec![0]).unwrap_err(),
+    ///     Error::SizeMismatch { size: Size::new(2, 2), len: 1 }
+    /// );
+    /// ```
+    pub fn from_raw(size: Size, data: Vec<Color>) -> Result<Self, Error> {
+        if data.len() != size.pixels() {
+            return Err(Error::SizeMismatch {
+                size,
+                len: data.len(),
+            });
+        }
+
+        let opaque_runs = Self::find_opaque_runs(&data, size);
+
+        Ok(Self {
+            size,
+            data,
+            opaque_runs,
+            row_runs: Vec::new(),
+        })
+    }
+
+    /// Take the pixel data out of the buffer without copying it.
+    pub fn into_raw(self) -> Vec<Color> {
+        self.data
+    }
+
+    /// Width of the buffer in pixels.
+    pub fn width(&self) -> u32 {
+        self.size.width
+    }
+
+    /// Height of the buffer in pixels.
+    pub fn height(&self) -> u32 {
+        self.size.height
+    }
+
+    /// Size of the blitbuffer in pixels.
+    pub fn size(&self) -> Size {
+        self.size
+    }
+
+    /// Get a reference to the pixel data.
+    pub fn pixels(&self) -> &[Color] {
+        &self.data
+    }
+
+    /// Cache the opaque runs of every row, so drawing a row doesn't need to search for its runs.
+    ///
+    /// This uses a bit more memory, it's useful for buffers that are drawn many times every frame such as fonts and tiles.
+    /// The cache is kept up to date when the pixels are changed.
+    ///
+    /// ```rust
+    /// # use blit::{Blit, BlitBuffer, BlitOptions, geom::Size};
+    /// let tile = BlitBuffer::from_buffer(&[0xFF_FF_FF_FF, 0], 2, 127).with_cached_rows(true);
+    /// assert!(tile.has_cached_rows());
+    ///
+    /// let mut buffer = [0; 4];
+    /// tile.blit(&mut buffer, Size::new(4, 1), &BlitOptions::new_position(1, 0));
+    /// assert_eq!(buffer, [0, 0xFF_FF_FF_FF, 0, 0]);
+    /// ```
+    #[must_use]
+    pub fn with_cached_rows(mut self, cached: bool) -> Self {
+        self.set_cached_rows(cached);
+
+        self
+    }
+
+    /// Cache the opaque runs of every row, see [`Self::with_cached_rows`].
+    pub fn set_cached_rows(&mut self, cached: bool) {
+        self.row_runs = if cached {
+            Self::find_row_runs(&self.opaque_runs, self.size)
+        } else {
+            Vec::new()
+        };
+    }
+
+    /// Whether the opaque runs of every row are cached, see [`Self::with_cached_rows`].
+    pub fn has_cached_rows(&self) -> bool {
+        !self.row_runs.is_empty()
+    }
+
+    /// Borrow the buffer so it can be passed around without copying the pixels.
+    pub fn as_buffer_ref(&self) -> BlitBufferRef<'_> {
+        BlitBufferRef {
+            size: self.size,
+            stride: self.size.width as usize,
+            data: &self.data,
+            opaque_runs: &self.opaque_runs,
+            row_runs: &self.row_runs,
+        }
+    }
+
+    /// Change the pixel data in place with a closure, finding the opaque runs again afterwards.
+    ///
+    /// Unlike [`Self::pixels_mut`] the fast path for copying opaque pixels keeps working.
+    ///
+    /// ```rust
+    /// # use blit::{ops, BlitBuffer};
+    /// let mut blit = BlitBuffer::from_buffer(&[0xFF_80_80_80, 0xFF_40_40_40], 2, 127);
+    /// blit.modify_pixels(|pixels| ops::adjust_brightness(pixels, 0.25));
+    /// assert_eq!(blit.pixels(), [0xFF_C0_C0_C0, 0xFF_80_80_80]);
+    /// ```
+    pub fn modify_pixels<F>(&mut self, modify: F)
+    where
+        F: FnOnce(&mut [Color]),
+    {
+        modify(&mut self.data);
+        self.update_opaque_runs();
+    }
+
+    /// Get a mutable reference to the pixel data.
+    ///
+    /// This disables the fast path for copying opaque pixels for good, because it's unknown how the pixels will be changed.
+    /// Use [`Self::modify_pixels`] to keep it.
+    pub fn pixels_mut(&mut self) -> &mut [Color] {
+        self.opaque_runs.clear();
+        // Keep the rows cached, every row now has no runs
+        self.row_runs.fill(0);
+
+        &mut self.data
+    }
+
+    /// Mirror the pixels in place around the vertical axis.
+    pub fn flip_horizontal(&mut self) {
+        let width = self.size.width as usize;
+        if width == 0 {
+            return;
+        }
+
+        self.data
+            .chunks_exact_mut(width)
+            .for_each(|row| row.reverse());
+        self.update_opaque_runs();
+    }
+
+    /// Mirror the pixels in place around the horizontal axis.
+    pub fn flip_vertical(&mut self) {
+        let (width, height) = (self.size.width as usize, self.size.height as usize);
+
+        for y in 0..height / 2 {
+            let (top, bottom) = self.data.split_at_mut((height - y - 1) * width);
+            top[y * width..(y + 1) * width].swap_with_slice(&mut bottom[..width]);
+        }
+        self.update_opaque_runs();
+    }
+
+    /// Multiply the color channels of every pixel in place with the channels of the tint color.
+    ///
+    /// The alpha channel of the tint color is ignored.
+    pub fn tint(&mut self, color: u32) {
+        let multiply = |pixel: Color, shift: u32| -> Color {
+            (((pixel >> shift) & 0xFF) * ((color >> shift) & 0xFF) / 0xFF) << shift
+        };
+
+        self.data.iter_mut().for_each(|pixel| {
+            *pixel = (*pixel & 0xFF_00_00_00)
+                | multiply(*pixel, 16)
+                | multiply(*pixel, 8)
+                | multiply(*pixel, 0);
+        });
+    }
+
+    /// Copy of the buffer with every pixel that's not fully transparent set to the color.
+    ///
+    /// Useful for drawing a sprite as a solid shape, such as a flash when it's hit.
+    ///
+    /// ```rust
+    /// # use blit::BlitBuffer;
+    /// let blit = BlitBuffer::from_buffer_with_alpha(&[0, 0x80_12_34_56, 0xFF_12_34_56], 3);
+    /// assert_eq!(blit.silhouette(0xFF_FF_FF_FF).pixels(), [0, 0xFF_FF_FF_FF, 0xFF_FF_FF_FF]);
+    /// ```
+    pub fn silhouette(&self, color: u32) -> Self {
+        let data = self
+            .data
+            .iter()
+            .map(|pixel| if pixel >> 24 > 0 { color } else { 0 })
+            .collect::<Vec<_>>();
+        let opaque_runs = Self::find_opaque_runs(&data, self.size);
+
+        Self {
+            size: self.size,
+            data,
+            opaque_runs,
+            row_runs: Vec::new(),
+        }
+    }
+
+    /// Buffer containing only a border of the color around all pixels that are not fully transparent.
+    ///
+    /// The border is `thickness` pixels wide and includes the diagonal neighbors, all other pixels are transparent.
+    /// Because the border can be outside of this buffer the outline is bigger by `thickness` pixels on every side, draw it with [`BlitOptions::with_anchor`] set to `(thickness, thickness)` at the position of the sprite to surround it.
+    ///
+    /// ```rust
+    /// # use blit::{BlitBuffer, geom::Size};
+    /// let blit = BlitBuffer::from_buffer(&[0xFF_FF_FF_FF], 1, 127);
+    ///
+    /// let outline = blit.outline(0xFF_00_00_00, 1);
+    /// assert_eq!(outline.size(), Size::new(3, 3));
+    /// assert_eq!(outline.pixels()[4], 0);
+    /// assert!(outline.pixels().iter().enumerate().all(|(index, pixel)| index == 4 || *pixel == 0xFF_00_00_00));
+    /// ```
+    pub fn outline(&self, color: u32, thickness: u32) -> Self {
+        let size = Size::new(
+            self.size.width + thickness * 2,
+            self.size.height + thickness * 2,
+        );
+        let (width, thickness) = (size.width as usize, thickness as usize);
+
+        // Which pixels are not transparent, moved to the center of the bigger buffer
+        let mut opaque = vec![false; size.pixels()];
+        if self.size.width > 0 {
+            for (y, row) in self.data.chunks_exact(self.size.width as usize).enumerate() {
+                let start = (y + thickness) * width + thickness;
+                opaque[start..start + row.len()]
+                    .iter_mut()
+                    .zip(row)
+                    .for_each(|(opaque, pixel)| *opaque = pixel >> 24 > 0);
+            }
+        }
+
+        // Grow the opaque pixels horizontally and then vertically, which grows them into a square
+        let grow = |index: usize, step: usize, length: usize, mask: &[bool]| {
+            let position = index / step % length;
+            let (before, after) = (
+                position.min(thickness),
+                (length - position - 1).min(thickness),
+            );
+
+            (index - before * step..=index + after * step)
+                .step_by(step)
+                .any(|index| mask[index])
+        };
+        let horizontal = (0..opaque.len())
+            .map(|index| grow(index, 1, width, &opaque))
+            .collect::<Vec<_>>();
+        let data = (0..opaque.len())
+            .map(|index| {
+                if !opaque[index] && grow(index, width, size.height as usize, &horizontal) {
+                    color
+                } else {
+                    0
+                }
+            })
+            .collect::<Vec<_>>();
+        let opaque_runs = Self::find_opaque_runs(&data, size);
+
+        Self {
+            size,
+            data,
+            opaque_runs,
+            row_runs: Vec::new(),
+        }
+    }
+
+    /// Move a part of the buffer to another position in itself, overlapping areas are handled correctly like `memmove`.
+    ///
+    /// The pixels are copied without blending, so transparent pixels overwrite the destination too.
+    ///
+    /// The source and the destination of [`Blit::blit`] can't be the same buffer: the source is borrowed while the destination is borrowed mutably, which the borrow checker doesn't allow.
+    /// Even when they could alias, the blit reads pixels in an order that depends on the options so overlapping pixels would be read after being written.
+    /// Scrolling or moving the content of a buffer must use this function instead.
+    ///
+    /// ```rust
+    /// # use blit::BlitBuffer;
+    /// let mut buffer = BlitBuffer::from_buffer(&[0xFF_00_00_01, 0, 0xFF_00_00_03], 1, 127);
+    ///
+    /// // Scroll the content up by one row, the transparent pixel is moved too
+    /// buffer.self_blit((0, 1, 1, 2), (0, 0));
+    /// assert_eq!(buffer.pixels(), [0, 0xFF_00_00_03, 0xFF_00_00_03]);
+    /// ```
+    pub fn self_blit<R, C>(&mut self, src_rect: R, dst_position: C)
+    where
+        R: Into<SubRect>,
+        C: Into<Coordinate>,
+    {
+        let (src_rect, dst_position) = (src_rect.into(), dst_position.into());
+        let full_view = ImageView::full(self.size);
+
+        // Only the part of the source inside the buffer can be drawn
+        let src_view = match full_view.sub(src_rect) {
+            Some(src_view) => src_view,
+            None => return,
+        };
+
+        // Move the destination with the pixels clipped from the source
+        let (src_x, src_y) = src_view.coord();
+        let (dst_x, dst_y) = (
+            dst_position.x + src_x - src_rect.x,
+            dst_position.y + src_y - src_rect.y,
+        );
+        let dst_view = match full_view.sub_i32(dst_x, dst_y, src_view.size()) {
+            Some(dst_view) => dst_view,
+            None => return,
+        };
+
+        // Move the source with the pixels clipped from the destination
+        let (new_dst_x, new_dst_y) = dst_view.coord();
+        let (src_x, src_y) = (src_x + new_dst_x - dst_x, src_y + new_dst_y - dst_y);
+
+        let width = self.size.width as usize;
+        let (row_width, height) = (dst_view.width() as usize, dst_view.height());
+
+        for index in 0..height {
+            // When moving down start at the bottom so rows are never read after being written, like `memmove`
+            let index = if new_dst_y > src_y {
+                height - index - 1
+            } else {
+                index
+            } as i32;
+
+            // Overlapping pixels within a row are handled by the copy
+            let src_start = (src_y + index) as usize * width + src_x as usize;
+            let dst_start = (new_dst_y + index) as usize * width + new_dst_x as usize;
+            self.data
+                .copy_within(src_start..src_start + row_width, dst_start);
+        }
+        self.update_opaque_runs();
+    }
+
+    /// Divide the buffer into a grid of cells of the same size, such as the frames of a spritesheet.
+    ///
+    /// The cells are ordered left to right and top to bottom, cells that don't fully fit in the buffer are skipped.
+    /// The rectangles can be drawn with [`BlitOptions::with_sub_rect`].
+    ///
+    /// ```rust
+    /// # use blit::{BlitBuffer, geom::SubRect};
+    /// let sheet = BlitBuffer::from_buffer(&[0; 5 * 2], 5, 127);
+    ///
+    /// assert_eq!(
+    ///     sheet.split_grid((2, 2)),
+    ///     [SubRect::new(0, 0, (2, 2)), SubRect::new(2, 0, (2, 2))]
+    /// );
+    /// ```
+    pub fn split_grid<S>(&self, cell_size: S) -> Vec<SubRect>
+    where
+        S: Into<Size>,
+    {
+        let cell_size = cell_size.into();
+        if cell_size.width == 0 || cell_size.height == 0 {
+            return Vec::new();
+        }
+
+        let (columns, rows) = (
+            self.size.width / cell_size.width,
+            self.size.height / cell_size.height,
+        );
+
+        (0..rows)
+            .flat_map(|row| {
+                (0..columns).map(move |column| {
+                    SubRect::new(
+                        (column * cell_size.width) as i32,
+                        (row * cell_size.height) as i32,
+                        cell_size,
+                    )
+                })
+            })
+            .collect()
+    }
+
+    /// Copy a part of the buffer into a new buffer.
+    ///
+    /// The rectangle is clipped to the buffer, when it's fully outside of the buffer an empty buffer is returned.
+    ///
+    /// ```rust
+    /// # use blit::BlitBuffer;
+    /// let blit = BlitBuffer::from_buffer_with_alpha(&[1, 2, 3, 4, 5, 6], 3);
+    ///
+    /// assert_eq!(blit.crop((1, 0, 2, 2)).pixels(), [2, 3, 5, 6]);
+    /// assert_eq!(blit.crop((2, 1, 5, 5)).pixels(), [6]);
+    /// ```
+    pub fn crop<R>(&self, rect: R) -> Self
+    where
+        R: Into<SubRect>,
+    {
+        let (size, data) = match ImageView::full(self.size).sub(rect) {
+            Some(view) => (
+                view.size(),
+                view.parent_ranges_iter(self.size)
+                    .flat_map(|range| self.data[range].iter().copied())
+                    .collect::<Vec<_>>(),
+            ),
+            None => (Size::new(0, 0), Vec::new()),
+        };
+        let opaque_runs = Self::find_opaque_runs(&data, size);
+
+        Self {
+            size,
+            data,
+            opaque_runs,
+            row_runs: Vec::new(),
+        }
+    }
+
+    /// Copy the buffer without the fully transparent rows and columns at the borders.
+    ///
+    /// Also returns the position of the trimmed buffer in this buffer, drawing the trimmed buffer moved by it looks the same as drawing this buffer.
+    /// When all pixels are transparent an empty buffer is returned.
+    ///
+    /// ```rust
+    /// # use blit::{BlitBuffer, geom::Coordinate};
+    /// let blit = BlitBuffer::from_buffer_with_alpha(&[0, 0, 0, 0, 0xFF_00_00_00, 0, 0, 0, 0], 3);
+    ///
+    /// let (trimmed, offset) = blit.trim();
+    /// assert_eq!(trimmed.pixels(), [0xFF_00_00_00]);
+    /// assert_eq!(offset, Coordinate::new(1, 1));
+    /// ```
+    pub fn trim(&self) -> (Self, Coordinate) {
+        match self.opaque_bounds() {
+            Some(bounds) => (self.crop(bounds), Coordinate::new(bounds.x, bounds.y)),
+            None => (self.crop((0, 0, 0, 0)), Coordinate::new(0, 0)),
+        }
+    }
+
+    /// Smallest rectangle containing all pixels that are not fully transparent, `None` when all pixels are transparent.
+    ///
+    /// Useful for collision bounding boxes or centering sprites with transparent padding.
+    ///
+    /// ```rust
+    /// # use blit::{BlitBuffer, geom::SubRect};
+    /// let blit = BlitBuffer::from_buffer(&[0, 0xFF_FF_FF_FF, 0, 0, 0, 0xFF_FF_FF_FF], 3, 127);
+    /// assert_eq!(blit.opaque_bounds(), Some(SubRect::new(1, 0, (2, 2))));
+    ///
+    /// let empty = BlitBuffer::from_buffer(&[0, 0], 2, 127);
+    /// assert_eq!(empty.opaque_bounds(), None);
+    /// ```
+    pub fn opaque_bounds(&self) -> Option<SubRect> {
+        let width = self.size.width as usize;
+        if width == 0 {
+            return None;
+        }
+
+        let mut bounds: Option<(usize, usize, usize, usize)> = None;
+        for (y, row) in self.data.chunks_exact(width).enumerate() {
+            let Some(left) = row.iter().position(|pixel| pixel >> 24 > 0) else {
+                continue;
+            };
+            let right = row
+                .iter()
+                .rposition(|pixel| pixel >> 24 > 0)
+                .unwrap_or(left);
+
+            bounds = Some(match bounds {
+                Some((min_x, min_y, max_x, _)) => (min_x.min(left), min_y, max_x.max(right), y),
+                None => (left, y, right, y),
+            });
+        }
+
+        bounds.map(|(min_x, min_y, max_x, max_y)| {
+            SubRect::new(
+                min_x as i32,
+                min_y as i32,
+                (max_x - min_x + 1, max_y - min_y + 1),
+            )
+        })
+    }
+
+    /// Rectangle on the destination buffer that will be touched when blitting with the options.
+    ///
+    /// Returns `None` when nothing will be drawn.
+    pub fn effective_rect(&self, dst_size: Size, options: &BlitOptions) -> Option<SubRect> {
+        options.effective_rect(self.size, dst_size)
+    }
+
+    /// Draw the buffer like [`Blit::blit`] and report which part of the destination got drawn.
+    ///
+    /// The rectangle is the same as the one returned by [`BlitBuffer::effective_rect`].
+    pub fn blit_with_rect(
+        &self,
+        dst: &mut [u32],
+        dst_size: Size,
+        options: &BlitOptions,
+    ) -> (Option<SubRect>, Visibility) {
+        let rect = self.effective_rect(dst_size, options);
+        if rect.is_some() {
+            self.blit(dst, dst_size, options);
+        }
+
+        (rect, options.visibility(self.size, rect))
+    }
+
+    /// Draw the buffer like [`Blit::blit`] with the color of every pixel that's not fully transparent changed by a function first.
+    ///
+    /// This allows any per-pixel effect such as grayscale, flashing or team colors without changing the buffer.
+    /// The pixels are changed while drawing so opaque runs can't be copied directly, when drawing the same effect often it's faster to keep a changed copy.
+    ///
+    /// ```rust
+    /// # use blit::{BlitBuffer, BlitOptions, geom::Size};
+    /// let blit = BlitBuffer::from_buffer(&[0xFF_00_00_FF, 0], 2, 127);
+    /// let mut buffer = [0xFF_00_FF_00; 2];
+    ///
+    /// // Swap the red and blue channels
+    /// blit.blit_with(&mut buffer, Size::new(2, 1), &BlitOptions::new(), |color| {
+    ///     (color & 0xFF_00_FF_00) | ((color & 0xFF) << 16) | ((color >> 16) & 0xFF)
+    /// });
+    /// assert_eq!(buffer, [0xFF_FF_00_00, 0xFF_00_FF_00]);
+    /// ```
+    pub fn blit_with<F>(&self, dst: &mut [u32], dst_size: Size, options: &BlitOptions, remap: F)
+    where
+        F: Fn(u32) -> u32,
+    {
+        // Masking would lose the fade
+        let blend = if options.blend == BlendMode::Mask && options.opacity_alpha() != 0xFF {
+            BlendMode::Alpha
+        } else {
+            options.blend
+        };
+
+        self.blit_blend(dst, dst_size, options, &Remap { blend, remap });
+    }
+
+    /// Draw the buffer like [`Blit::blit`] on a destination with another pixel type, such as a `u16` RGB565 framebuffer.
+    ///
+    /// ```rust
+    /// # use blit::{BlitBuffer, BlitOptions, geom::Size};
+    /// let blit = BlitBuffer::from_buffer(&[0xFF_FF_00_00, 0], 2, 127);
+    /// let mut buffer = [0x00_1F_u16; 2];
+    ///
+    /// blit.blit_into(&mut buffer, Size::new(2, 1), &BlitOptions::new());
+    /// assert_eq!(buffer, [0xF8_00, 0x00_1F]);
+    /// ```
+    pub fn blit_into<P>(&self, dst: &mut [P], dst_size: Size, options: &BlitOptions)
+    where
+        P: Pixel,
+    {
+        self.as_buffer_ref().blit_into(dst, dst_size, options);
+    }
+
+    /// Draw the buffer like [`Blit::blit`] but combine every pixel with a custom operation instead of the blend mode of the options.
+    ///
+    /// The operation gets the destination and the source pixel and returns the new destination pixel, it's called for every drawn pixel including fully transparent ones.
+    /// The destination can have any pixel type, the operation always gets the pixels packed as `0xAARRGGBB`.
+    ///
+    /// ```rust
+    /// # use blit::{BlitBuffer, BlitOptions, geom::Size};
+    /// let blit = BlitBuffer::from_buffer(&[0xFF_80_80_80; 2], 2, 127);
+    /// let mut buffer = [0xFF_FF_FF_FF_u32, 0xFF_00_00_00];
+    ///
+    /// // Only darken the destination
+    /// blit.blit_blend(&mut buffer, Size::new(2, 1), &BlitOptions::new(), &|dst: u32, src: u32| {
+    ///     if src & 0xFF_FF_FF < dst & 0xFF_FF_FF { src } else { dst }
+    /// });
+    /// assert_eq!(buffer, [0xFF_80_80_80, 0xFF_00_00_00]);
+    /// ```
+    pub fn blit_blend<P, B>(&self, dst: &mut [P], dst_size: Size, options: &BlitOptions, blend: &B)
+    where
+        P: Pixel,
+        B: PixelBlend + ?Sized,
+    {
+        self.as_buffer_ref()
+            .blit_blend(dst, dst_size, options, blend);
+    }
+
+    /// Draw the buffer like [`Blit::blit`] but only on the destination pixels where the stencil is not fully transparent.
+    ///
+    /// The stencil is placed on the destination at the position, everything outside of the stencil is not drawn either.
+    /// Useful for drawing portraits in round frames or hiding parts behind fog.
+    ///
+    /// ```rust
+    /// # use blit::{BlitBuffer, BlitOptions, geom::Size};
+    /// let blit = BlitBuffer::from_buffer(&[0xFF_FF_FF_FF; 4], 4, 127);
+    /// let stencil = BlitBuffer::from_buffer(&[0xFF_00_00_00, 0, 0xFF_00_00_00], 3, 127);
+    /// let mut buffer = [0; 4];
+    ///
+    /// blit.blit_with_stencil(&mut buffer, Size::new(4, 1), &BlitOptions::new(), &stencil, (1, 0));
+    /// assert_eq!(buffer, [0, 0xFF_FF_FF_FF, 0, 0xFF_FF_FF_FF]);
+    /// ```
+    pub fn blit_with_stencil<C>(
+        &self,
+        dst: &mut [u32],
+        dst_size: Size,
+        options: &BlitOptions,
+        stencil: &BlitBuffer,
+        stencil_position: C,
+    ) where
+        C: Into<Coordinate>,
+    {
+        let stencil_position = stencil_position.into();
+
+        // Never draw outside of the stencil
+        let options = options.clone().clipped_by(SubRect::new(
+            stencil_position.x,
+            stencil_position.y,
+            stencil.size,
+        ));
+        let Some(rect) = self.effective_rect(dst_size, &options) else {
+            return;
+        };
+
+        // Remember the destination pixels where the stencil is transparent and restore them after drawing
+        let covered = ImageView(rect)
+            .parent_ranges_iter(dst_size)
+            .zip(rect.y..)
+            .flat_map(|(range, y)| range.zip(rect.x..).map(move |(index, x)| (index, x, y)))
+            .filter(|(_, x, y)| {
+                let stencil_index = (y - stencil_position.y) as usize * stencil.size.width as usize
+                    + (x - stencil_position.x) as usize;
+
+                stencil.data[stencil_index] >> 24 == 0
+            })
+            .map(|(index, _, _)| (index, dst[index]))
+            .collect::<Vec<_>>();
+
+        self.blit(dst, dst_size, &options);
+
+        covered
+            .into_iter()
+            .for_each(|(index, pixel)| dst[index] = pixel);
+    }
+
+    /// Draw the buffer like [`Blit::blit`] on top of a shadow, which is the [`BlitBuffer::silhouette`] in the color moved by the offset.
+    ///
+    /// The shadow is drawn with the same options as the buffer but always with [`BlendMode::Alpha`], so a translucent color darkens the destination.
+    /// The silhouette is created every call, when drawing the same shadow often it's faster to keep a silhouette and draw it separately.
+    ///
+    /// ```rust
+    /// # use blit::{BlitBuffer, BlitOptions, geom::Size};
+    /// let blit = BlitBuffer::from_buffer(&[0xFF_FF_FF_FF], 1, 127);
+    /// let mut buffer = [0xFF_FF_00_00; 2];
+    ///
+    /// blit.blit_with_shadow(&mut buffer, Size::new(2, 1), &BlitOptions::new(), (1, 0), 0x80_00_00_00);
+    /// assert_eq!(buffer, [0xFF_FF_FF_FF, 0xFF_7F_00_00]);
+    /// ```
+    pub fn blit_with_shadow<C>(
+        &self,
+        dst: &mut [u32],
+        dst_size: Size,
+        options: &BlitOptions,
+        offset: C,
+        color: u32,
+    ) where
+        C: Into<Coordinate>,
+    {
+        let offset = offset.into();
+
+        // The mask clips the shadow at the same place as the buffer
+        let mut shadow_options = options.clone().with_blend(BlendMode::Alpha);
+        shadow_options.x += offset.x;
+        shadow_options.y += offset.y;
+        self.silhouette(color).blit(dst, dst_size, &shadow_options);
+
+        self.blit(dst, dst_size, options);
+    }
+
+    /// Whether the pixel at the coordinate on the destination would be covered by a pixel that's not fully transparent when blitting with the options.
+    ///
+    /// The coordinate is mapped back to the source with all options, including tiling, slicing, scaling, rotating, flipping and clipping by the mask.
+    /// Nothing is hit when the options fade the source out completely.
+    ///
+    /// ```rust
+    /// # use blit::{BlitBuffer, BlitOptions};
+    /// let blit = BlitBuffer::from_buffer(&[0, 0xFF_FF_FF_FF], 2, 127);
+    /// let options = BlitOptions::new_position(10, 10).with_area((4, 1));
+    ///
+    /// // The source is tiled, so every odd column hits
+    /// assert!(!blit.hit_test(&options, (12, 10)));
+    /// assert!(blit.hit_test(&options, (13, 10)));
+    /// ```
+    pub fn hit_test<C>(&self, options: &BlitOptions, dst_coord: C) -> bool
+    where
+        C: Into<Coordinate>,
+    {
+        if options.opacity_alpha() == 0 {
+            return false;
+        }
+
+        // Draw only the requested pixel on a destination of a single transparent pixel, only visible pixels will be written
+        let dst_coord = dst_coord.into();
+        let mut hit_options = options
+            .clone()
+            .offset_by((-dst_coord.x, -dst_coord.y))
+            .with_blend(BlendMode::Mask)
+            .with_channel_order(ChannelOrder::Argb);
+        hit_options.opacity = None;
+
+        let mut pixel = [0];
+        self.blit(&mut pixel, Size::new(1, 1), &hit_options);
+
+        pixel[0] >> 24 > 0
+    }
+
+    /// Draw the buffer at multiple positions with the same options.
+    ///
+    /// This is the same as calling [`Blit::blit`] for each position with [`BlitOptions::x`] and [`BlitOptions::y`] replaced, but the source rectangle and the clipping area are only calculated once.
+    /// This makes it a lot faster for drawing many small sprites such as particles.
+    pub fn blit_positions(
+        &self,
+        dst: &mut [u32],
+        dst_size: Size,
+        base_options: &BlitOptions,
+        positions: &[Coordinate],
+    ) {
+        let area = base_options.area(self.size);
+        let sub_rect_view = match ImageView::full(self.size).sub(base_options.sub_rect(self.size)) {
+            Some(sub_rect_view) => sub_rect_view,
+            None => return,
+        };
+
+        // Tiling, slicing, flipping and rotating can't reuse the source rectangle
+        if !base_options.fills_area_exactly(sub_rect_view.size(), area)
+            || base_options.vertical_slice.is_some()
+            || base_options.horizontal_slice.is_some()
+            || base_options.flip_horizontal
+            || base_options.flip_vertical
+            || base_options.rotation != Rotation::None
+        {
+            let mut options = base_options.clone();
+            positions.iter().for_each(|position| {
+                options.set_position(*position);
+                self.blit(dst, dst_size, &options);
+            });
+
+            return;
+        }
+
+        // The unchecked inner loops rely on the destination containing all pixels of the size
+        #[cfg(feature = "unsafe-fast")]
+        assert!(
+            dst.len() >= dst_size.pixels(),
+            "destination has less pixels than its size"
+        );
+
+        // Area of the destination that can be drawn on by any of the positions
+        let mut clip_view = ImageView::full(dst_size);
+        if let Some(mask) = base_options.mask {
+            clip_view = clip_view.clip(mask);
+        }
+        if clip_view.width() == 0 || clip_view.height() == 0 {
+            return;
+        }
+
+        let (src_x, src_y) = sub_rect_view.coord();
+        let buffer_ref = self.as_buffer_ref();
+        let anchor = base_options.anchor_offset(area);
+        positions.iter().for_each(|position| {
+            let position = Coordinate::new(position.x - anchor.x, position.y - anchor.y);
+            let dst_area = match clip_view.sub_i32(position.x, position.y, area) {
+                Some(dst_area) => dst_area,
+                None => return,
+            };
+
+            // Shift the source with the amount of clipped pixels
+            let (new_x, new_y) = dst_area.coord();
+            let src_view = ImageView::new_unchecked(
+                src_x + new_x - position.x,
+                src_y + new_y - position.y,
+                dst_area.size(),
+            );
+
+            src_view
+                .parent_ranges_iter(self.size)
+                .zip(dst_area.parent_ranges_iter(dst_size))
+                .for_each(|(src_range, dst_range)| {
+                    buffer_ref.blit_horizontal(
+                        dst,
+                        dst_range,
+                        src_range,
+                        base_options,
+                        &base_options.blend,
+                    )
+                });
+        });
+    }
+
+    /// Draw the buffer rotated by an arbitrary angle around a pivot point, using nearest-neighbor sampling.
+    ///
+    /// The pivot is relative to the top left of the source rectangle and stays at the same position on the destination as when drawing without rotating.
+    /// Positive angles in radians rotate clockwise.
+    ///
+    /// This is a special case of [`BlitBuffer::blit_affine`], see it for which options are used.
+    ///
+    /// ```rust
+    /// # use blit::{Blit, BlitBuffer, BlitOptions, geom::Size};
+    /// let blit = BlitBuffer::from_buffer(&[0xFF_00_00_01, 0xFF_00_00_02, 0, 0], 2, 127);
+    /// let mut buffer = [0; 4];
+    ///
+    /// // Rotate a quarter turn clockwise around the center
+    /// blit.blit_rotated(&mut buffer, Size::new(2, 2), &BlitOptions::new(), std::f32::consts::FRAC_PI_2, (1.0, 1.0));
+    /// assert_eq!(buffer, [0, 0xFF_00_00_01, 0, 0xFF_00_00_02]);
+    /// ```
+    pub fn blit_rotated(
+        &self,
+        dst: &mut [u32],
+        dst_size: Size,
+        options: &BlitOptions,
+        angle: f32,
+        pivot: (f32, f32),
+    ) {
+        let (sin, cos) = angle.sin_cos();
+        let (pivot_x, pivot_y) = pivot;
+
+        // Rotate around the pivot by moving it to the origin, rotating and moving it back
+        self.blit_affine(
+            dst,
+            dst_size,
+            options,
+            [
+                [cos, -sin, pivot_x - cos * pivot_x + sin * pivot_y],
+                [sin, cos, pivot_y - sin * pivot_x - cos * pivot_y],
+            ],
+        );
+    }
+
+    /// Draw the buffer transformed by an affine matrix, using nearest-neighbor sampling.
+    ///
+    /// The matrix `[[a, b, tx], [c, d, ty]]` maps a position `(x, y)` relative to the top left of the source rectangle to the position `(a * x + b * y + tx, c * x + d * y + ty)` relative to [`BlitOptions::x`] and [`BlitOptions::y`] on the destination.
+    /// This allows any combination of scaling, rotating, shearing and translating.
+    /// Every destination pixel is mapped back to the source with the inverse of the matrix, nothing is drawn when the matrix can't be inverted.
+    ///
+    /// Only [`BlitOptions::x`], [`BlitOptions::y`], [`BlitOptions::anchor`], [`BlitOptions::sub_rect`], [`BlitOptions::mask`], [`BlitOptions::blend`], [`BlitOptions::opacity`], [`BlitOptions::alpha_threshold`] and [`BlitOptions::channel_order`] are used, tiling, slicing, flipping and quarter turn rotations are ignored.
+    /// The anchor is relative to the untransformed source rectangle.
+    /// This is a lot slower than [`Blit::blit`].
+    ///
+    /// ```rust
+    /// # use blit::{BlitBuffer, BlitOptions, geom::Size};
+    /// let blit = BlitBuffer::from_buffer(&[0xFF_00_00_01, 0xFF_00_00_02], 1, 127);
+    /// let mut buffer = [0; 4];
+    ///
+    /// // Shear horizontally, every row moves one pixel to the right
+    /// blit.blit_affine(&mut buffer, Size::new(2, 2), &BlitOptions::new(), [[1.0, 1.0, 0.0], [0.0, 1.0, 0.0]]);
+    /// assert_eq!(buffer.map(|pixel| pixel & 0xFF), [1, 0, 0, 2]);
+    /// ```
+    pub fn blit_affine(
+        &self,
+        dst: &mut [u32],
+        dst_size: Size,
+        options: &BlitOptions,
+        matrix: [[f32; 3]; 2],
+    ) {
+        let opacity = options.opacity_alpha();
+        if opacity == 0 {
+            return;
+        }
+
+        let [[a, b, translate_x], [c, d, translate_y]] = matrix;
+
+        // The matrix must be invertible to map the destination back to the source
+        let determinant = a * d - b * c;
+        if determinant == 0.0 || !determinant.is_finite() {
+            return;
+        }
+        let inverse = [
+            [d / determinant, -b / determinant],
+            [-c / determinant, a / determinant],
+        ];
+
+        // Only the part of the source rectangle inside of the buffer can be drawn
+        let sub_rect = options
+            .sub_rect
+            .unwrap_or_else(|| SubRect::from_size(self.size));
+        let Some(src_view) = ImageView::full(self.size).sub(sub_rect) else {
+            return;
+        };
+
+        // Position of the origin of the source rectangle on the destination, the anchor is relative to the untransformed source rectangle
+        let anchor = options.anchor_offset(sub_rect.size);
+        let (origin_x, origin_y) = (
+            (options.x - anchor.x) as f32 + translate_x,
+            (options.y - anchor.y) as f32 + translate_y,
+        );
+
+        // Bounding box of the transformed corners of the source rectangle
+        let corners = [
+            (0.0, 0.0),
+            (sub_rect.width() as f32, 0.0),
+            (0.0, sub_rect.height() as f32),
+            (sub_rect.width() as f32, sub_rect.height() as f32),
+        ]
+        .map(|(x, y)| (origin_x + a * x + b * y, origin_y + c * x + d * y));
+        let (left, top) = corners
+            .iter()
+            .fold((f32::MAX, f32::MAX), |(left, top), (x, y)| {
+                (left.min(*x), top.min(*y))
+            });
+        let (right, bottom) = corners
+            .iter()
+            .fold((f32::MIN, f32::MIN), |(right, bottom), (x, y)| {
+                (right.max(*x), bottom.max(*y))
+            });
+
+        let mut dst_area = match ImageView::full(dst_size).sub(SubRect::new(
+            left.floor(),
+            top.floor(),
+            Size::new(right.ceil() - left.floor(), bottom.ceil() - top.floor()),
+        )) {
+            Some(dst_area) => dst_area,
+            None => return,
+        };
+        if let Some(mask) = options.mask {
+            dst_area = dst_area.clip(mask);
+        }
+
+        // Masking would lose the fade
+        let blend = if opacity == 0xFF {
+            options.blend
+        } else {
+            options.blend.faded()
+        };
+
+        let (src_left, src_top) = src_view.coord();
+        let (src_right, src_bottom) = (src_view.0.right(), src_view.0.bottom());
+
+        let (dst_x, dst_y) = dst_area.coord();
+        for y in dst_y..dst_y + dst_area.height() as i32 {
+            for x in dst_x..dst_x + dst_area.width() as i32 {
+                // Transform the center of the destination pixel back to find the source pixel
+                let (offset_x, offset_y) = (x as f32 + 0.5 - origin_x, y as f32 + 0.5 - origin_y);
+                let src_x = (inverse[0][0] * offset_x + inverse[0][1] * offset_y).floor() as i32
+                    + sub_rect.x;
+                let src_y = (inverse[1][0] * offset_x + inverse[1][1] * offset_y).floor() as i32
+                    + sub_rect.y;
+
+                if src_x < src_left || src_y < src_top || src_x >= src_right || src_y >= src_bottom
+                {
+                    continue;
+                }
+
+                let mut blit_pixel =
+                    self.data[src_y as usize * self.size.width as usize + src_x as usize];
+                if let Some(alpha_threshold) = options.alpha_threshold_bits() {
+                    blit_pixel = Self::apply_alpha_threshold(blit_pixel, alpha_threshold);
+                }
+                let dst_pixel = &mut dst[y as usize * dst_size.width as usize + x as usize];
+                *dst_pixel = options.channel_order.pack(blend.blend(
+                    options.channel_order.unpack(*dst_pixel),
+                    BlendMode::fade(blit_pixel, opacity),
+                ));
+            }
+        }
+    }
+
+    /// Draw the buffer at a position between pixels, spreading every pixel over the destination pixels it partially covers.
+    ///
+    /// Slow moving sprites drawn like this don't visibly snap from one pixel to the next.
+    /// The position `(x, y)` replaces [`BlitOptions::x`] and [`BlitOptions::y`] and is precise to 1/256th of a pixel, whole positions draw the same as [`Blit::blit`].
+    /// The edges of the drawn rectangle become translucent, so draw with [`BlendMode::Alpha`] to blend them with the destination.
+    ///
+    /// Only [`BlitOptions::anchor`], [`BlitOptions::sub_rect`], [`BlitOptions::mask`], [`BlitOptions::blend`], [`BlitOptions::opacity`], [`BlitOptions::alpha_threshold`] and [`BlitOptions::channel_order`] are used.
+    ///
+    /// ```rust
+    /// # use blit::{BlitBuffer, BlitOptions, geom::Size};
+    /// let blit = BlitBuffer::from_buffer(&[0xFF_FF_FF_FF], 1, 127);
+    /// let mut buffer = [0; 2];
+    ///
+    /// // Both destination pixels are covered by half of the pixel
+    /// blit.blit_subpixel(&mut buffer, Size::new(2, 1), &BlitOptions::new(), 0.5, 0.0);
+    /// assert_eq!(buffer, [0x7F_FF_FF_FF; 2]);
+    /// ```
+    pub fn blit_subpixel(
+        &self,
+        dst: &mut [u32],
+        dst_size: Size,
+        options: &BlitOptions,
+        x: f32,
+        y: f32,
+    ) {
+        let sub_rect = options
+            .sub_rect
+            .unwrap_or_else(|| SubRect::from_size(self.size));
+        let Some(src_view) = ImageView::full(self.size).sub(sub_rect) else {
+            return;
+        };
+        let (src_x, src_y) = src_view.coord();
+        let src_size = src_view.size();
+
+        // Split the anchored position into whole pixels and a fraction in 1/256th of a pixel
+        let anchor = options.anchor_offset(sub_rect.size);
+        let split = |position: f32, anchor: i32| {
+            let fixed = ((position - anchor as f32) * 256.0).round() as i64;
+
+            ((fixed >> 8) as i32, (fixed & 0xFF) as u32)
+        };
+        let (whole_x, fraction_x) = split(x, anchor.x);
+        let (whole_y, fraction_y) = split(y, anchor.y);
+
+        // Every source pixel is moved right and down by the fraction, so the partially covered pixels add a column and a row
+        let size = Size::new(
+            src_size.width + u32::from(fraction_x > 0),
+            src_size.height + u32::from(fraction_y > 0),
+        );
+        let pixel = |x: i32, y: i32| {
+            if x < 0 || y < 0 || x >= src_size.width as i32 || y >= src_size.height as i32 {
+                0
+            } else {
+                self.data[(src_y + y) as usize * self.size.width as usize + (src_x + x) as usize]
+            }
+        };
+        let data = (0..size.height as i32)
+            .flat_map(|y| (0..size.width as i32).map(move |x| (x, y)))
+            .map(|(x, y)| {
+                filter::bilinear(
+                    [
+                        pixel(x - 1, y - 1),
+                        pixel(x, y - 1),
+                        pixel(x - 1, y),
+                        pixel(x, y),
+                    ],
+                    256 - fraction_x,
+                    256 - fraction_y,
+                )
+            })
+            .collect::<Vec<_>>();
+
+        BlitBuffer {
+            size,
+            opaque_runs: BlitBuffer::find_opaque_runs(&data, size),
+            row_runs: Vec::new(),
+            data,
+        }
+        .blit(
+            dst,
+            dst_size,
+            &BlitOptions {
+                x: whole_x,
+                y: whole_y,
+                mask: options.mask,
+                blend: options.blend,
+                opacity: options.opacity,
+                alpha_threshold: options.alpha_threshold,
+                channel_order: options.channel_order,
+                ..Default::default()
+            },
+        );
+    }
+
+    /// Draw the buffer upscaled by an integer factor, every pixel becomes a square of `factor` by `factor` pixels.
+    ///
+    /// This is the same as drawing with [`ScaleMode::Stretch`] and an area of the size of the source rectangle multiplied by the factor, but faster because every source row is only expanded once.
+    ///
+    /// Only [`BlitOptions::x`], [`BlitOptions::y`], [`BlitOptions::anchor`], [`BlitOptions::sub_rect`], [`BlitOptions::mask`], [`BlitOptions::blend`], [`BlitOptions::opacity`], [`BlitOptions::alpha_threshold`] and [`BlitOptions::channel_order`] are used, the anchor is relative to the scaled size.
+    ///
+    /// ```rust
+    /// # use blit::{BlitBuffer, BlitOptions, geom::Size};
+    /// let blit = BlitBuffer::from_buffer(&[0xFF_00_00_01, 0xFF_00_00_02], 2, 127);
+    /// let mut buffer = [0; 8];
+    ///
+    /// blit.blit_scaled_int(&mut buffer, Size::new(4, 2), &BlitOptions::new(), 2);
+    /// assert_eq!(buffer.map(|pixel| pixel & 0xFF), [1, 1, 2, 2, 1, 1, 2, 2]);
+    /// ```
+    pub fn blit_scaled_int<F>(
+        &self,
+        dst: &mut [u32],
+        dst_size: Size,
+        options: &BlitOptions,
+        factor: F,
+    ) where
+        F: ToPrimitive,
+    {
+        let factor = factor.to_u32().unwrap_or_default();
+        if factor == 0 || options.opacity_alpha() == 0 {
+            return;
+        }
+
+        // Only the part of the source rectangle inside of the buffer can be drawn
+        let sub_rect = options
+            .sub_rect
+            .unwrap_or_else(|| SubRect::from_size(self.size));
+        let options = &*options.anchored(sub_rect.size.saturating_mul((factor, factor)));
+        let Some(src_view) = ImageView::full(self.size).sub(sub_rect) else {
+            return;
+        };
+
+        // When the source rectangle is clipped the stretched pixels aren't squares anymore
+        if src_view.size() != sub_rect.size {
+            let mut stretch_options = options.clone().with_scale(ScaleMode::Stretch);
+            stretch_options.area = Some(sub_rect.size.saturating_mul((factor, factor)));
+            stretch_options.filter = Filter::Nearest;
+            stretch_options.vertical_slice = None;
+            stretch_options.horizontal_slice = None;
+            stretch_options.flip_horizontal = false;
+            stretch_options.flip_vertical = false;
+            stretch_options.rotation = Rotation::None;
+            self.blit(dst, dst_size, &stretch_options);
+
+            return;
+        }
+
+        let mut dst_area = match ImageView::full(dst_size).sub_i32(
+            options.x,
+            options.y,
+            sub_rect.size.saturating_mul((factor, factor)),
+        ) {
+            Some(dst_area) => dst_area,
+            None => return,
+        };
+        if let Some(mask) = options.mask {
+            dst_area = dst_area.clip(mask);
+
+            // When it's fully clipped do nothing
+            if dst_area.width() == 0 || dst_area.height() == 0 {
+                return;
+            }
+        }
+
+        // How many scaled pixels are clipped at the left and top
+        let (dst_x, dst_y) = dst_area.coord();
+        let (offset_x, offset_y) = ((dst_x - options.x) as usize, (dst_y - options.y) as u32);
+
+        let width = self.size.width as usize;
+        let (src_x, src_y) = (sub_rect.x as usize, sub_rect.y as usize);
+
+        // Expand every source row once and draw it for every destination row it covers
+        let mut row = Vec::with_capacity(dst_area.width() as usize);
+        let mut expanded_row = None;
+        dst_area
+            .parent_ranges_iter(dst_size)
+            .zip(offset_y..)
+            .for_each(|(dst_range, y)| {
+                let src_row = src_y + (y / factor) as usize;
+                if expanded_row != Some(src_row) {
+                    let start = src_row * width + src_x;
+                    row.clear();
+                    row.extend(
+                        self.data[start..start + sub_rect.width() as usize]
+                            .iter()
+                            .flat_map(|pixel| std::iter::repeat_n(*pixel, factor as usize))
+                            .skip(offset_x)
+                            .take(dst_range.len()),
+                    );
+
+                    expanded_row = Some(src_row);
+                }
+
+                Self::blit_pixels(dst[dst_range].iter_mut(), row.iter().copied(), options);
+            });
+    }
+
+    /// Find the ranges without transparent pixels again after the pixels changed, also updating the cached rows.
+    fn update_opaque_runs(&mut self) {
+        self.opaque_runs = Self::find_opaque_runs(&self.data, self.size);
+        if !self.row_runs.is_empty() {
+            self.row_runs = Self::find_row_runs(&self.opaque_runs, self.size);
+        }
+    }
+
+    /// Find the index of the first opaque run of every row, with the amount of runs as the last item.
+    fn find_row_runs(opaque_runs: &[Range<usize>], size: Size) -> Vec<usize> {
+        let width = size.width as usize;
+
+        (0..=size.height as usize)
+            .map(|y| opaque_runs.partition_point(|run| run.start < y * width))
+            .collect()
+    }
+
+    /// Find the ranges of every row without transparent pixels.
+    fn find_opaque_runs(data: &[Color], size: Size) -> Vec<Range<usize>> {
+        let width = size.width as usize;
+        if width == 0 {
+            return Vec::new();
+        }
+
+        let mut runs = Vec::new();
+        for (y, row) in data.chunks_exact(width).enumerate() {
+            let mut start = None;
+            for (x, pixel) in row.iter().enumerate() {
+                match (start, pixel >> 24 > 0) {
+                    (None, true) => start = Some(y * width + x),
+                    (Some(run_start), false) => {
+                        runs.push(run_start..y * width + x);
+                        start = None;
+                    }
+                    _ => (),
+                }
+            }
+            if let Some(run_start) = start {
+                runs.push(run_start..(y + 1) * width);
+            }
+        }
+
+        runs
+    }
+
+    /// Blit each pixel of a strip with the blend mode of the options.
+    #[inline(always)]
+    fn blit_pixels<'a>(
+        dst_iter: impl Iterator<Item = &'a mut Color>,
+        blit_iter: impl Iterator<Item = Color>,
+        options: &BlitOptions,
+    ) {
+        Self::blit_pixels_with(dst_iter, blit_iter, options, &options.blend);
+    }
+
+    /// Make a pixel fully transparent when its alpha is below the treshold and fully opaque otherwise.
+    ///
+    /// The treshold is shifted to the alpha bits so the pixel can be compared directly.
+    #[inline(always)]
+    fn apply_alpha_threshold(pixel: Color, alpha_threshold: Color) -> Color {
+        if pixel < alpha_threshold {
+            0x00_00_00_00
+        } else {
+            pixel | 0xFF_00_00_00
+        }
+    }
+
+    /// Blit each pixel of a strip with a custom blend operation.
+    #[inline(always)]
+    fn blit_pixels_with<'a, P: Pixel + 'a, B: PixelBlend + ?Sized>(
+        dst_iter: impl Iterator<Item = &'a mut P>,
+        blit_iter: impl Iterator<Item = Color>,
+        options: &BlitOptions,
+        blend: &B,
+    ) {
+        let blit_iter = blit_iter.map(|pixel| blend.remap(pixel));
+
+        // Match outside of the loop so drawing without a treshold doesn't check every pixel
+        match options.alpha_threshold_bits() {
+            Some(alpha_threshold) => Self::blit_pixels_converted(
+                dst_iter,
+                blit_iter.map(move |pixel| Self::apply_alpha_threshold(pixel, alpha_threshold)),
+                options,
+                blend,
+            ),
+            None => Self::blit_pixels_converted(dst_iter, blit_iter, options, blend),
+        }
+    }
+
+    /// Blit each pixel of a strip, converting the destination pixels.
+    #[inline(always)]
+    fn blit_pixels_converted<'a, P: Pixel + 'a, B: PixelBlend + ?Sized>(
+        dst_iter: impl Iterator<Item = &'a mut P>,
+        blit_iter: impl Iterator<Item = Color>,
+        options: &BlitOptions,
+        blend: &B,
+    ) {
+        // Match outside of the loop so the default order doesn't convert anything
+        match options.channel_order {
+            ChannelOrder::Argb => Self::blend_pixels(
+                dst_iter,
+                blit_iter,
+                options,
+                blend,
+                P::to_argb,
+                P::from_argb,
+            ),
+            order => Self::blend_pixels(
+                dst_iter,
+                blit_iter,
+                options,
+                blend,
+                |pixel| order.unpack(pixel.to_argb()),
+                |pixel| P::from_argb(order.pack(pixel)),
+            ),
+        }
+    }
+
+    /// Blend each pixel of a strip, converting the destination pixels from and to their pixel type and channel order.
+    #[inline(always)]
+    fn blend_pixels<'a, P: Pixel + 'a>(
+        dst_iter: impl Iterator<Item = &'a mut P>,
+        blit_iter: impl Iterator<Item = Color>,
+        options: &BlitOptions,
+        blend: &(impl PixelBlend + ?Sized),
+        unpack: impl Fn(P) -> Color,
+        pack: impl Fn(Color) -> P,
+    ) {
+        // Blit each pixel, branch outside of the loop so the mask path stays as fast as possible
+        match (blend.is_mask(), options.opacity_alpha()) {
+            (true, 0xFF) => dst_iter.zip(blit_iter).for_each(|(dst_pixel, blit_pixel)| {
+                *dst_pixel = pack(BlendMode::Mask.blend(unpack(*dst_pixel), blit_pixel));
+            }),
+            (false, 0xFF) => dst_iter.zip(blit_iter).for_each(|(dst_pixel, blit_pixel)| {
+                *dst_pixel = pack(blend.blend(unpack(*dst_pixel), blit_pixel));
+            }),
+            // Masking would lose the fade
+            (true, opacity) => dst_iter.zip(blit_iter).for_each(|(dst_pixel, blit_pixel)| {
+                *dst_pixel = pack(
+                    BlendMode::Alpha
+                        .blend(unpack(*dst_pixel), BlendMode::fade(blit_pixel, opacity)),
+                );
+            }),
+            (false, opacity) => dst_iter.zip(blit_iter).for_each(|(dst_pixel, blit_pixel)| {
+                *dst_pixel =
+                    pack(blend.blend(unpack(*dst_pixel), BlendMode::fade(blit_pixel, opacity)));
+            }),
+        }
+    }
+}
+
+impl Blit for BlitBuffer {
+    fn blit(&self, dst: &mut [u32], dst_size: Size, options: &BlitOptions) {
+        self.as_buffer_ref().blit(dst, dst_size, options);
+    }
+
+    fn source_size(&self) -> Option<Size> {
+        Some(self.size)
+    }
+
+    fn blit_row(&self, dst_row: &mut [u32], dst_size: Size, options: &BlitOptions, y: u32) {
+        self.as_buffer_ref().blit_row(dst_row, dst_size, options, y);
+    }
+}
+
+impl<'a> From<&'a BlitBuffer> for BlitBufferRef<'a> {
+    fn from(buffer: &'a BlitBuffer) -> Self {
+        buffer.as_buffer_ref()
+    }
+}
+
+impl std::fmt::Debug for BlitBuffer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("BlitBuffer")
+            .field("width", &self.size.width)
+            .field("height", &self.size.height)
+            .finish()
+    }
+}
+
+/// Borrowed pixel data that can be drawn like a [`BlitBuffer`] without owning the pixels.
+///
+/// This can be used for pixels that are already processed, for example for a static table of sprites:
+///
+/// ```rust
+/// # use blit::{Blit, BlitBufferRef, BlitOptions, geom::Size};
+/// static SPRITE: BlitBufferRef =
+///     BlitBufferRef::new(Size { width: 2, height: 1 }, &[0xFF_FF_FF_FF, 0]);
+///
+/// let mut buffer = [0; 2];
+/// SPRITE.blit(&mut buffer, Size::new(2, 1), &BlitOptions::new());
+/// assert_eq!(buffer, [0xFF_FF_FF_FF, 0]);
+/// ```
+///
+/// The rows don't have to be packed tightly, with [`BlitBufferRef::from_strided`] a part of a bigger image held elsewhere can be drawn without copying it.
+#[derive(Clone, Copy)]
+pub struct BlitBufferRef<'a> {
+    /// Image size in pixels.
+    size: Size,
+
+    /// Amount of pixels from the start of a row to the start of the next row, at least the width.
+    stride: usize,
+
+    /// Colors, the highest 8 bits are alpha and the remaining 24 bits the RGB color channels.
+    data: &'a [Color],
+
+    /// Sorted index ranges of the data without transparent pixels, see [`BlitBuffer`].
+    ///
+    /// Only set when borrowed from a [`BlitBuffer`].
+    opaque_runs: &'a [Range<usize>],
+
+    /// Index of the first opaque run of every row, see [`BlitBuffer::with_cached_rows`].
+    ///
+    /// Only set when borrowed from a [`BlitBuffer`] with cached rows.
+    row_runs: &'a [usize],
+}
+
+impl<'a> BlitBufferRef<'a> {
+    /// Borrow pixels that are already processed, they are used as is with the full alpha channel.
+    ///
+    /// # Panics
+    ///
+    /// When the amount of pixels doesn't match the size, in a constant this is a compile error.
+    pub const fn new(size: Size, data: &'a [Color]) -> Self {
+        assert!(
+            data.len() == size.pixels(),
+            "amount of pixels doesn't match the size"
+        );
+
+        Self {
+            size,
+            stride: size.width as usize,
+            data,
+            opaque_runs: &[],
+            row_runs: &[],
+        }
+    }
+
+    /// Borrow pixels of which the rows are the stride apart, the pixels between the rows are never drawn.
+    ///
+    /// Fails when the stride is smaller than the width or when the data is too short to contain all rows, the last row doesn't need the pixels after it.
+    ///
+    /// ```rust
+    /// # use blit::{Blit, BlitBufferRef, BlitOptions, geom::Size};
+    /// // Right half of an image with a width of 4 pixels
+    /// let atlas = [0, 0, 0xFF_00_00_01, 0xFF_00_00_02, 0, 0, 0xFF_00_00_03, 0xFF_00_00_04];
+    /// let sprite = BlitBufferRef::from_strided(Size::new(2, 2), 4, &atlas[2..]).unwrap();
+    ///
+    /// let mut buffer = [0; 4];
+    /// sprite.blit(&mut buffer, Size::new(2, 2), &BlitOptions::new());
+    /// assert_eq!(buffer.map(|pixel| pixel & 0xFF), [1, 2, 3, 4]);
+    /// ```
+    pub fn from_strided(size: Size, stride: usize, data: &'a [Color]) -> Result<Self, Error> {
+        let width = size.width as usize;
+        let needed = match size.height as usize {
+            0 => 0,
+            height => (height - 1) * stride + width,
+        };
+        if stride < width || data.len() < needed {
+            return Err(Error::StrideMismatch {
+                size,
+                stride,
+                len: data.len(),
+            });
+        }
+
+        Ok(Self {
+            size,
+            stride,
+            data,
+            opaque_runs: &[],
+            row_runs: &[],
+        })
+    }
+
+    /// Width of the buffer in pixels.
+    pub fn width(&self) -> u32 {
+        self.size.width
+    }
+
+    /// Height of the buffer in pixels.
+    pub fn height(&self) -> u32 {
+        self.size.height
+    }
+
+    /// Size of the buffer in pixels.
+    pub fn size(&self) -> Size {
+        self.size
+    }
+
+    /// Get a reference to the pixel data.
+    ///
+    /// When the stride is bigger than the width this includes the pixels between the rows.
+    pub fn pixels(&self) -> &'a [Color] {
+        self.data
+    }
+
+    /// Amount of pixels from the start of a row to the start of the next row.
+    pub fn stride(&self) -> usize {
+        self.stride
+    }
+
+    /// Draw the buffer like [`Blit::blit`] on a destination with another pixel type.
+    ///
+    /// See [`BlitBuffer::blit_into`].
+    pub fn blit_into<P>(&self, dst: &mut [P], dst_size: Size, options: &BlitOptions)
+    where
+        P: Pixel,
+    {
+        self.blit_blend(dst, dst_size, options, &options.blend);
+    }
+
+    /// Draw the buffer like [`Blit::blit`] but combine every pixel with a custom operation instead of the blend mode of the options.
+    ///
+    /// See [`BlitBuffer::blit_blend`].
+    pub fn blit_blend<P, B>(&self, dst: &mut [P], dst_size: Size, options: &BlitOptions, blend: &B)
+    where
+        P: Pixel,
+        B: PixelBlend + ?Sized,
+    {
+        // Fully faded out, nothing will be drawn
+        if options.opacity_alpha() == 0 {
+            return;
+        }
+
+        // The unchecked inner loops rely on the destination containing all pixels of the size
+        #[cfg(feature = "unsafe-fast")]
+        assert!(
+            dst.len() >= dst_size.pixels(),
+            "destination has less pixels than its size"
+        );
+
+        // Get the total area we need to draw the slices in
+        let area = options.area(self.size);
+        let options = &*options.anchored(area);
+
+        // The slices divide the sub rectangle if set
+        let source = options
+            .sub_rect
+            .unwrap_or_else(|| SubRect::from_size(self.size));
+
+        // Which slices do we need to draw if any
+        let mut slice_projections =
+            Self::slice_projections(options, source.size, options.rotated_size(area)).peekable();
+
+        if slice_projections.peek().is_none() {
+            // Render without projections, clipping is handled for each drawn part
+            self.blit_slice(dst, dst_size, options, blend);
+        } else {
+            // Loop over each slice
+            slice_projections.for_each(|(slice_source, target, fill)| {
+                let mut slice_options = options.clone();
+                if fill == SliceFill::Stretch {
+                    slice_options.scale = ScaleMode::Stretch;
+                }
+
+                // Rotate and mirror the slice positions in the area, the slices themselves are rotated and mirrored when drawn
+                let target = options.place_in_area(target, area);
+
+                // Move the position to which part of the slice we need to draw
+                slice_options.x = options.x + target.x;
+                slice_options.y = options.y + target.y;
+                slice_options.area = Some(target.size);
+
+                // Move the slice to the position of the sub rectangle
+                slice_options.sub_rect = Some(SubRect::new(
+                    source.x + slice_source.x,
+                    source.y + slice_source.y,
+                    slice_source.size,
+                ));
+
+                self.blit_slice(dst, dst_size, &slice_options, blend)
+            });
+        }
+    }
+
+    /// Size of the data with the stride as the width, used to find the pixels of a row.
+    fn data_size(&self) -> Size {
+        Size::new(self.stride, self.size.height)
+    }
+
+    /// Divide the target area into given slices of rectangles to draw.
+    ///
+    /// The slices divide the source rectangle, an axis without a slice is used as a single section.
+    /// A `(source, target, fill)` tuple is returned for every one of the at most 9 rectangles without allocating, the source is relative to the source rectangle.
+    /// Nothing is returned when there are no slices.
+    fn slice_projections(
+        options: &BlitOptions,
+        source: Size,
+        target_area: Size,
+    ) -> impl Iterator<Item = (SubRect, SubRect, SliceFill)> {
+        let (vertical, horizontal) = (options.vertical_slice, options.horizontal_slice);
+        let is_sliced = vertical.is_some() || horizontal.is_some();
+
+        // Return a cartesian product of the ranges of both axes, the columns are cheap to divide again for every row
+        SliceProjection::divide_axis(horizontal, source.height, target_area.height)
+            .filter(move |_| is_sliced)
+            .flat_map(move |row| {
+                SliceProjection::divide_axis(vertical, source.width, target_area.width).map(
+                    move |column| {
+                        let fill = SliceFill::combine(column.fill(), row.fill());
+                        let (source, target) =
+                            SliceProjection::combine_into_sub_rects(&column, &row);
+
+                        (source, target, fill)
+                    },
+                )
+            })
+    }
+
+    /// Blit a sliced section.
+    fn blit_slice<P: Pixel, B: PixelBlend + ?Sized>(
+        &self,
+        dst: &mut [P],
+        dst_size: Size,
+        options: &BlitOptions,
+        blend: &B,
+    ) {
+        // If the size of the image is the same as our buffer and the full image is drawn at location zero we can completely blit all bytes
+        if options.x == 0
+            && options.y == 0
+            && dst_size == self.size
+            && self.stride == self.size.width as usize
+            && options.mask.is_none()
+            && !options.flip_horizontal
+            && !options.flip_vertical
+            && options.rotation == Rotation::None
+            && options.fills_area_exactly(self.size, options.area(self.size))
+            && options.sub_rect(self.size) == SubRect::from_size(self.size)
+        {
+            let pixels = dst_size.pixels();
+            self.blit_horizontal(dst, 0..pixels, 0..pixels, options, blend);
+
+            return;
+        }
+
+        // Convert the destination to view so we can calculate with it
+        let dst_view = ImageView::full(dst_size);
+
+        // Convert our source to a view
+        let src_view = ImageView::full(self.size);
+
+        // Find a view on the dst based on the area
+        let area = options.area(self.size);
+        let dst_area = match dst_view.sub_i32(options.x, options.y, area) {
+            Some(dst_area) => dst_area,
+            None => return,
+        };
+
+        // Another view based on the subrectangle
+        let sub_rect_view = match src_view.sub(options.sub_rect(self.size)) {
+            Some(sub_rect_view) => sub_rect_view,
+            None => return,
+        };
+
+        // The area in the orientation of the source
+        let unrotated_area = options.rotated_size(area);
+
+        // We can draw the image exactly
+        if options.fills_area_exactly(sub_rect_view.size(), unrotated_area) {
+            self.blit_exact(
+                dst,
+                dst_size,
+                options,
+                SubRect::new(options.x, options.y, area),
+                dst_area,
+                sub_rect_view,
+                blend,
+            );
+        } else if options.scale == ScaleMode::Stretch {
+            self.blit_stretched(dst, dst_size, options, dst_area, sub_rect_view, area, blend);
+        } else {
+            // Draw every tile exactly, reusing the options and only moving the target and the source of the tile
+            let (sub_x, sub_y) = sub_rect_view.coord();
+            let tile_size = sub_rect_view.size();
+            let (phase_x, phase_y) = options.uv_phase(tile_size);
+            let (spacing, margin) = options.tile_gaps();
+
+            for (target_y, source_y, height) in options.remainder.tile_segments(
+                unrotated_area.height,
+                tile_size.height,
+                spacing.height,
+                margin.height,
+                phase_y,
+            ) {
+                for (target_x, source_x, width) in options.remainder.tile_segments(
+                    unrotated_area.width,
+                    tile_size.width,
+                    spacing.width,
+                    margin.width,
+                    phase_x,
+                ) {
+                    // Rotate and mirror the tile positions in the area, the tiles themselves are rotated and mirrored when drawn
+                    let target = options
+                        .place_in_area(SubRect::new(target_x, target_y, (width, height)), area);
+                    let target =
+                        SubRect::new(options.x + target.x, options.y + target.y, target.size);
+
+                    // Tiles completely outside of the destination are skipped
+                    let Some(tile_area) = dst_view.sub_i32(target.x, target.y, target.size) else {
+                        continue;
+                    };
+                    let tile_source = ImageView::new_unchecked(
+                        sub_x + source_x as i32,
+                        sub_y + source_y as i32,
+                        Size::new(width, height),
+                    );
+
+                    self.blit_exact(
+                        dst,
+                        dst_size,
+                        options,
+                        target,
+                        tile_area,
+                        tile_source,
+                        blend,
+                    );
+                }
+            }
+        }
+    }
+
+    /// Blit the source view to the target rectangle without scaling, the source view has the size of the target in the orientation of the source.
+    ///
+    /// The destination view is the target clipped by the destination, it's clipped further by the mask of the options.
+    #[allow(clippy::too_many_arguments)]
+    fn blit_exact<P: Pixel, B: PixelBlend + ?Sized>(
+        &self,
+        dst: &mut [P],
+        dst_size: Size,
+        options: &BlitOptions,
+        target: SubRect,
+        mut dst_area: ImageView,
+        mut sub_rect_view: ImageView,
+        blend: &B,
+    ) {
+        if let Some(mask) = options.mask {
+            // Clip the dst view on the mask area first
+            dst_area = dst_area.clip(mask);
+
+            // When it's fully clipped do nothing
+            if dst_area.width() == 0 || dst_area.height() == 0 {
+                return;
+            }
+        }
+
+        let (transpose, flip_horizontal, flip_vertical) = options.transform();
+
+        // How much the coordinates got offset by clipping on the destination and the mask, when flipped the clipped pixels are on the other side of the source
+        let (new_x, new_y) = dst_area.coord();
+        let offset_x = if flip_horizontal {
+            target.x + target.width() as i32 - (new_x + dst_area.width() as i32)
+        } else {
+            new_x - target.x
+        };
+        let offset_y = if flip_vertical {
+            target.y + target.height() as i32 - (new_y + dst_area.height() as i32)
+        } else {
+            new_y - target.y
+        };
+
+        // Shift the UV coords of the sub rect view, when transposed the axes of the source are swapped
+        if transpose {
+            sub_rect_view.0.x += offset_y;
+            sub_rect_view.0.y += offset_x;
+            sub_rect_view.0.size = Size::new(dst_area.height(), dst_area.width());
+        } else {
+            sub_rect_view.0.x += offset_x;
+            sub_rect_view.0.y += offset_y;
+            sub_rect_view.0.size = dst_area.size();
+        }
+
+        // Pixel range of the destination
+        let dst_ranges = dst_area.parent_ranges_iter(dst_size);
+
+        if transpose {
+            // Every row of the destination is a column of the source
+            let src_columns = sub_rect_view.parent_columns_iter(self.data_size());
+
+            let blit_column = |(src_column, dst_range): (StepBy<Range<usize>>, Range<usize>)| {
+                let blit_iter = src_column.map(|index| view::pixel(self.data, index));
+                if flip_horizontal {
+                    BlitBuffer::blit_pixels_with(
+                        view::range_mut(dst, dst_range).iter_mut(),
+                        blit_iter.rev(),
+                        options,
+                        blend,
+                    );
+                } else {
+                    BlitBuffer::blit_pixels_with(
+                        view::range_mut(dst, dst_range).iter_mut(),
+                        blit_iter,
+                        options,
+                        blend,
+                    );
+                }
+            };
+            if flip_vertical {
+                src_columns.rev().zip(dst_ranges).for_each(blit_column);
+            } else {
+                src_columns.zip(dst_ranges).for_each(blit_column);
+            }
+        } else {
+            // Pixel range of the source
+            let src_ranges = sub_rect_view.parent_ranges_iter(self.data_size());
+
+            let blit_range = |(src_range, dst_range): (Range<usize>, Range<usize>)| {
+                if flip_horizontal {
+                    let blit_iter = view::range(self.data, src_range).iter().copied().rev();
+                    BlitBuffer::blit_pixels_with(
+                        view::range_mut(dst, dst_range).iter_mut(),
+                        blit_iter,
+                        options,
+                        blend,
+                    );
+                } else {
+                    self.blit_horizontal(dst, dst_range, src_range, options, blend);
+                }
+            };
+            if flip_vertical {
+                src_ranges.rev().zip(dst_ranges).for_each(blit_range);
+            } else {
+                src_ranges.zip(dst_ranges).for_each(blit_range);
+            }
+        }
+    }
+
+    /// Blit the source view resampled to fill the area with nearest-neighbor sampling.
+    #[allow(clippy::too_many_arguments)]
+    fn blit_stretched<P: Pixel, B: PixelBlend + ?Sized>(
+        &self,
+        dst: &mut [P],
+        dst_size: Size,
+        options: &BlitOptions,
+        mut dst_area: ImageView,
+        src_view: ImageView,
+        area: Size,
+        blend: &B,
+    ) {
+        if let Some(mask) = options.mask {
+            dst_area = dst_area.clip(mask);
+
+            // When it's fully clipped do nothing
+            if dst_area.width() == 0 || dst_area.height() == 0 {
+                return;
+            }
+        }
+
+        let (transpose, flip_horizontal, flip_vertical) = options.transform();
+        let (src_x, src_y) = (src_view.0.x as usize, src_view.0.y as usize);
+
+        // Length of the source along the horizontal and vertical axis of the destination
+        let (src_length_x, src_length_y) = if transpose {
+            (src_view.height(), src_view.width())
+        } else {
+            (src_view.width(), src_view.height())
+        };
+
+        // Map a position on the destination to a position relative to the area, mirrored when flipped
+        let relative = |position: i32, start: i32, area_length: u32, flip: bool| -> u32 {
+            let position = (position - start) as u32;
+            if flip {
+                area_length - position - 1
+            } else {
+                position
+            }
+        };
+
+        let (dst_x, dst_y) = dst_area.coord();
+        let dst_columns = dst_x..dst_x + dst_area.width() as i32;
+        let stride = self.stride;
+
+        match options.filter {
+            Filter::Nearest => {
+                // Map a position on the destination to an offset in the source along a single axis
+                let scale =
+                    |position: i32, start: i32, area_length: u32, flip: bool, src_length: u32| {
+                        let position = relative(position, start, area_length, flip) as u64;
+
+                        (position * src_length as u64 / area_length as u64) as usize
+                    };
+
+                // The source offsets are the same for every row
+                let columns = dst_columns
+                    .map(|x| scale(x, options.x, area.width, flip_horizontal, src_length_x))
+                    .collect::<Vec<_>>();
+
+                dst_area
+                    .parent_ranges_iter(dst_size)
+                    .zip(dst_y..)
+                    .for_each(|(dst_range, y)| {
+                        let row = scale(y, options.y, area.height, flip_vertical, src_length_y);
+
+                        let blit_iter = columns.iter().map(|column| {
+                            let (x, y) = if transpose {
+                                (row, *column)
+                            } else {
+                                (*column, row)
+                            };
+
+                            self.data[(src_y + y) * stride + src_x + x]
+                        });
+                        BlitBuffer::blit_pixels_with(
+                            dst[dst_range].iter_mut(),
+                            blit_iter,
+                            options,
+                            blend,
+                        );
+                    });
+            }
+            Filter::Bilinear => {
+                // The source samples are the same for every row
+                let columns = dst_columns
+                    .map(|x| {
+                        let position = relative(x, options.x, area.width, flip_horizontal);
+
+                        Sample::new(position, area.width, src_length_x)
+                    })
+                    .collect::<Vec<_>>();
+
+                dst_area
+                    .parent_ranges_iter(dst_size)
+                    .zip(dst_y..)
+                    .for_each(|(dst_range, y)| {
+                        let position = relative(y, options.y, area.height, flip_vertical);
+                        let row = Sample::new(position, area.height, src_length_y);
+
+                        let blit_iter = columns.iter().map(|column| {
+                            let (x, y) = if transpose {
+                                (row, *column)
+                            } else {
+                                (*column, row)
+                            };
+                            let pixel =
+                                |x: usize, y: usize| self.data[(src_y + y) * stride + src_x + x];
+
+                            filter::bilinear(
+                                [
+                                    pixel(x.first, y.first),
+                                    pixel(x.second, y.first),
+                                    pixel(x.first, y.second),
+                                    pixel(x.second, y.second),
+                                ],
+                                x.weight,
+                                y.weight,
+                            )
+                        });
+                        BlitBuffer::blit_pixels_with(
+                            dst[dst_range].iter_mut(),
+                            blit_iter,
+                            options,
+                            blend,
+                        );
+                    });
+            }
+        }
+    }
+
+    /// Blit a horizontal strip.
+    fn blit_horizontal<P: Pixel, B: PixelBlend + ?Sized>(
+        &self,
+        dst: &mut [P],
+        dst_index: Range<usize>,
+        blit_index: Range<usize>,
+        options: &BlitOptions,
+        blend: &B,
+    ) {
+        // Opaque runs can only be copied when the pixels don't need to be blended or converted
+        if blend.is_mask()
+            && options.opacity_alpha() == 0xFF
+            && options.alpha_threshold.is_none()
+            && options.channel_order == ChannelOrder::Argb
+        {
+            // Offset from the source index to the destination index
+            let to_dst = |index: usize| dst_index.start + index - blit_index.start;
+
+            // Skip the runs before the strip, with cached rows only the runs of the row are searched
+            let row = blit_index
+                .start
+                .checked_div(self.stride)
+                .and_then(|y| self.row_runs.get(y..y + 2));
+            let first = match row {
+                Some(&[row_start, row_end]) => {
+                    row_start
+                        + self.opaque_runs[row_start..row_end]
+                            .partition_point(|run| run.end <= blit_index.start)
+                }
+                _ => self
+                    .opaque_runs
+                    .partition_point(|run| run.end <= blit_index.start),
+            };
+
+            let mut start = blit_index.start;
+            for run in self.opaque_runs[first..]
+                .iter()
+                .take_while(|run| run.start < blit_index.end)
+            {
+                let (run_start, run_end) = (run.start.max(start), run.end.min(blit_index.end));
+
+                // Mask the mixed pixels before the run
+                P::mask_from_argb(
+                    view::range_mut(dst, to_dst(start)..to_dst(run_start)),
+                    view::range(self.data, start..run_start),
+                );
+
+                P::copy_from_argb(
+                    view::range_mut(dst, to_dst(run_start)..to_dst(run_end)),
+                    view::range(self.data, run_start..run_end),
+                );
+
+                start = run_end;
+            }
+
+            // Mask the mixed pixels after the last run
+            P::mask_from_argb(
+                view::range_mut(dst, to_dst(start)..dst_index.end),
+                view::range(self.data, start..blit_index.end),
+            );
+
+            return;
+        }
+
+        // Same size iterators over both our buffer and the output buffer
+        let blit_iter = view::range(self.data, blit_index).iter().copied();
+        let dst_iter = view::range_mut(dst, dst_index).iter_mut();
+
+        BlitBuffer::blit_pixels_with(dst_iter, blit_iter, options, blend);
+    }
+}
+
+impl Blit for BlitBufferRef<'_> {
+    fn blit(&self, dst: &mut [u32], dst_size: Size, options: &BlitOptions) {
+        self.blit_blend(dst, dst_size, options, &options.blend);
+    }
+
+    fn source_size(&self) -> Option<Size> {
+        Some(self.size)
+    }
+
+    fn blit_row(&self, dst_row: &mut [u32], dst_size: Size, options: &BlitOptions, y: u32) {
+        // Skip quickly when the row doesn't intersect with the area
+        let (y, area) = (y as i32, options.area(self.size));
+        let options = &*options.anchored(area);
+        if y < options.y || y >= options.y + area.height as i32 {
+            return;
+        }
+
+        let row_options = options.clone().offset_by((0, -y));
+
+        self.blit(dst_row, Size::new(dst_size.width, 1), &row_options);
+    }
+}
+
+impl std::fmt::Debug for BlitBufferRef<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("BlitBufferRef")
+            .field("width", &self.size.width)
+            .field("height", &self.size.height)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// All combinations of rotations and flips applied to the options.
+    fn transformed(options: &BlitOptions) -> impl Iterator<Item = BlitOptions> + '_ {
+        [
+            Rotation::None,
+            Rotation::Clockwise90,
+            Rotation::Clockwise180,
+            Rotation::Clockwise270,
+        ]
+        .into_iter()
+        .flat_map(move |rotation| {
+            [(false, false), (true, false), (false, true), (true, true)]
+                .into_iter()
+                .map(move |(flip_horizontal, flip_vertical)| {
+                    options
+                        .clone()
+                        .with_rotation(rotation)
+                        .with_flip_horizontal(flip_horizontal)
+                        .with_flip_vertical(flip_vertical)
+                })
+        })
+    }
+
+    #[test]
+    fn cached_rows() {
+        let source = (0..48)
+            .map(|i| if i % 5 < 2 { 0 } else { 0xFF_00_00_00 | i })
+            .collect::<Vec<_>>();
+        let blit = BlitBuffer::from_buffer(&source, 8, 127);
+        let cached = blit.clone().with_cached_rows(true);
+        let dst_size = Size::new(12, 12);
+
+        for options in [
+            BlitOptions::new(),
+            BlitOptions::new_position(-3, 2),
+            BlitOptions::new_position(5, -1).with_mask((6, 0, 4, 12)),
+            BlitOptions::new_position(1, 1).with_sub_rect((3, 1, 4, 4)),
+            BlitOptions::new().with_area((11, 9)),
+            BlitOptions::new_position(2, 0)
+                .with_area((10, 12))
+                .with_sub_rect((1, 2, 5, 3))
+                .with_uv_offset((2, 1)),
+            BlitOptions::new()
+                .with_slice9((2, 1, 3, 2))
+                .with_area((12, 10)),
+        ] {
+            for options in transformed(&options) {
+                let mut expected = [0xFF_10_20_30; 144];
+                blit.blit(&mut expected, dst_size, &options);
+
+                let mut result = [0xFF_10_20_30; 144];
+                cached.blit(&mut result, dst_size, &options);
+                assert_eq!(result, expected, "{options:?}");
+
+                // A cached buffer drawn row by row is the same too
+                let mut result = [0xFF_10_20_30; 144];
+                for (y, row) in result.chunks_exact_mut(12).enumerate() {
+                    cached.blit_row(row, dst_size, &options, y as u32);
+                }
+                assert_eq!(result, expected, "{options:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn exact_fit() {
+        let mut buffer = [0xFF, 0xFF_00, 0xFF_00_00, 0xFF, 0xFF_00, 0xFF_00_00];
+
+        // The last number should be masked
+        let blit = BlitBuffer::from_buffer(
+            &[
+                0xFF_00_00_AA,
+                0xFF_00_AA_00,
+                0xFF_AA_00_00,
+                0xBB,
+                0xBB,
+                0xBB,
+            ],
+            2,
+            127,
+        );
+        blit.blit(
+            &mut buffer,
+            Size::new(2, 3),
+            &BlitOptions::new_position(0, 0),
+        );
+
+        // Create a copy but cast the u32 to a i32
+        let expected = [
+            0xAA | 0xFF_00_00_00,
+            0xAA_00 | 0xFF_00_00_00,
+            0xAA_00_00 | 0xFF_00_00_00,
+            0xFF,
+            0xFF_00,
+            0xFF_00_00,
+        ];
+        assert_eq!(
+            buffer, expected,
+            "\nResult:\n{:08x?}\nExpected:\n{:08x?}",
+            &buffer, &expected
+        );
+    }
+
+    #[test]
+    fn compose() {
+        let blit = BlitBuffer::from_buffer(&[0xFF_00_00_AA; 16], 4, 127);
+        let dst_size = Size::new(16, 16);
+
+        let grandparent = BlitOptions::new_position(2, 2).with_mask((0, 0, 8, 8));
+        let parent = BlitOptions::new_position(1, 1).with_mask((0, 0, 5, 5));
+        let child = BlitOptions::new_position(3, 2);
+
+        let composed = BlitOptions::compose(&grandparent, &BlitOptions::compose(&parent, &child));
+        let manual = BlitOptions::new_position(6, 5).with_mask((2, 2, 5, 5));
+        assert_eq!(composed, manual);
+
+        let mut composed_buffer = [0; 16 * 16];
+        blit.blit(&mut composed_buffer, dst_size, &composed);
+
+        // Only the part inside both masks is drawn
+        let mut expected = [0; 16 * 16];
+        for y in 5..7 {
+            for x in 6..7 {
+                expected[y * 16 + x] = 0xFF_00_00_AA;
+            }
+        }
+        assert_eq!(composed_buffer, expected);
+
+        // Offsetting and clipping manually gives the same result
+        let mut offset_buffer = [0; 16 * 16];
+        blit.blit(
+            &mut offset_buffer,
+            dst_size,
+            &child
+                .clone()
+                .offset_by((1, 1))
+                .clipped_by((0, 0, 5, 5))
+                .offset_by((2, 2))
+                .clipped_by((0, 0, 8, 8)),
+        );
+        assert_eq!(offset_buffer, expected);
+
+        // Offsetting far out of range saturates instead of overflowing
+        let far = BlitOptions::new_position(i32::MAX - 1, i32::MIN + 1)
+            .with_mask((i32::MAX, i32::MIN, 1, 1))
+            .offset_by((10, -10));
+        assert_eq!(far.position(), (i32::MAX, i32::MIN));
+        let mask = far.mask.unwrap();
+        assert_eq!((mask.x, mask.y), (i32::MAX, i32::MIN));
+    }
+
+    #[test]
+    fn clipping_matrix() {
+        // Every pixel has a unique color, with a transparent diagonal
+        let source = (0..16)
+            .map(|i| if i % 5 == 0 { 0 } else { 0xFF_00_00_00 | i })
+            .collect::<Vec<_>>();
+        let blit = BlitBuffer::from_buffer(&source, 4, 127);
+        let dst_size = Size::new(8, 8);
+
+        // Fully inside, off each edge, off each corner and fully outside
+        let positions = [
+            (2, 2),
+            (-2, 2),
+            (6, 2),
+            (2, -2),
+            (2, 6),
+            (-2, -2),
+            (6, -2),
+            (-2, 6),
+            (6, 6),
+            (-4, 2),
+            (8, 2),
+            (2, -4),
+            (2, 8),
+            (-10, -10),
+        ];
+        let sub_rects = [None, Some((1, 1, 2, 3).into()), Some((-1, 2, 4, 4).into())];
+        let areas = [None, Some(Size::new(7, 5)), Some(Size::new(3, 2))];
+        let masks = [None, Some((1, 1, 5, 5).into()), Some((4, 4, 3, 3).into())];
+
+        for (x, y) in positions {
+            for sub_rect in sub_rects {
+                for area in areas {
+                    for mask in masks {
+                        let mut options = BlitOptions::new_position(x, y);
+                        if let Some(sub_rect) = sub_rect {
+                            options.set_sub_rect::<SubRect>(sub_rect);
+                        }
+                        options.area = area.or(options.area);
+                        options.mask = mask;
+
+                        for options in transformed(&options).flat_map(|options| {
+                            [
+                                (ScaleMode::Tile, Filter::Nearest),
+                                (ScaleMode::Stretch, Filter::Nearest),
+                                (ScaleMode::Stretch, Filter::Bilinear),
+                            ]
+                            .map(|(scale, filter)| {
+                                options.clone().with_scale(scale).with_filter(filter)
+                            })
+                        }) {
+                            let mut buffer = [0; 64];
+                            blit.blit(&mut buffer, dst_size, &options);
+
+                            let mut expected = [0; 64];
+                            reference::blit_reference(&blit, &mut expected, dst_size, &options);
+
+                            assert_eq!(
+                                buffer, expected,
+                                "\n{options:?}\nResult:\n{:08x?}\nExpected:\n{:08x?}",
+                                &buffer, &expected
+                            );
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn uv_offset() {
+        let source = (0..12)
+            .map(|i| if i % 5 == 0 { 0 } else { 0xFF_00_00_00 | i })
+            .collect::<Vec<_>>();
+        let blit = BlitBuffer::from_buffer(&source, 4, 127);
+        let dst_size = Size::new(12, 12);
+
+        // Scrolling a whole tile wraps around to the same pixels
+        let mut scrolled = [0; 144];
+        blit.blit(
+            &mut scrolled,
+            dst_size,
+            &BlitOptions::new().with_uv_offset((-8, 6)),
+        );
+        let mut expected = [0; 144];
+        blit.blit(&mut expected, dst_size, &BlitOptions::new());
+        assert_eq!(scrolled, expected);
+
+        // Scrolling a buffer with the same size as the destination
+        let row = BlitBuffer::from_buffer(&[0xFF_00_00_01, 0xFF_00_00_02, 0xFF_00_00_03], 3, 127);
+        let mut buffer = [0; 3];
+        row.blit(
+            &mut buffer,
+            Size::new(3, 1),
+            &BlitOptions::new().with_uv_offset((1, 0)),
+        );
+        assert_eq!(buffer.map(|pixel| pixel & 0xFF), [2, 3, 1]);
+
+        let slice9 = BlitOptions::new().with_slice9((1, 1, 1, 1));
+        for remainder in [
+            RemainderMode::TrailingClip,
+            RemainderMode::Centered,
+            RemainderMode::None,
+        ] {
+            for uv_offset in [(1, 0), (-7, 2), (13, -5)] {
+                for (area, slices) in [
+                    ((4, 3), (None, None)),
+                    ((11, 7), (None, None)),
+                    ((10, 9), (slice9.vertical_slice, slice9.horizontal_slice)),
+                ] {
+                    let mut options = BlitOptions::new_position(-1, 2)
+                        .with_area(area)
+                        .with_remainder(remainder)
+                        .with_uv_offset(uv_offset)
+                        .with_mask((0, 0, 10, 11));
+                    (options.vertical_slice, options.horizontal_slice) = slices;
+
+                    for options in transformed(&options) {
+                        let mut buffer = [0; 144];
+                        blit.blit(&mut buffer, dst_size, &options);
+
+                        let mut expected = [0; 144];
+                        reference::blit_reference(&blit, &mut expected, dst_size, &options);
+
+                        assert_eq!(buffer, expected, "{options:?}");
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn tiles_per_tile() {
+        let source = (0..8)
+            .map(|i| if i == 5 { 0 } else { 0xFF_00_00_00 | (i + 1) })
+            .collect::<Vec<_>>();
+        let blit = BlitBuffer::from_buffer(&source, 4, 127);
+        let dst_size = Size::new(40, 30);
+
+        // Draw a part of the source with a blit for every tile, clipped to the part of the area
+        let per_tile =
+            |buffer: &mut [u32], sub_rect: SubRect, part: SubRect, mask: Option<SubRect>| {
+                for y in (part.y..part.bottom()).step_by(sub_rect.height() as usize) {
+                    for x in (part.x..part.right()).step_by(sub_rect.width() as usize) {
+                        let mut options = BlitOptions::new_position(x, y)
+                            .with_sub_rect(sub_rect)
+                            .clipped_by(part);
+                        if let Some(mask) = mask {
+                            options = options.clipped_by(mask);
+                        }
+                        blit.blit(buffer, dst_size, &options);
+                    }
+                }
+            };
+
+        for ((x, y), mask) in [
+            ((0, 0), None),
+            ((-3, -1), None),
+            ((5, 7), Some(SubRect::new(8, 0, (20, 20)))),
+            ((30, 25), None),
+        ] {
+            // Plain tiling of the whole source and of a part of it
+            for sub_rect in [SubRect::new(0, 0, (4, 2)), SubRect::new(1, 0, (3, 2))] {
+                let mut options = BlitOptions::new_position(x, y)
+                    .with_area((37, 23))
+                    .with_sub_rect(sub_rect);
+                options.mask = mask;
+
+                let mut result = [0; 1200];
+                blit.blit(&mut result, dst_size, &options);
+
+                let mut expected = [0; 1200];
+                per_tile(&mut expected, sub_rect, SubRect::new(x, y, (37, 23)), mask);
+                assert_eq!(result, expected, "{options:?}");
+            }
+
+            // Only the middle section of a slice is tiled
+            let mut options = BlitOptions::new_position(x, y)
+                .with_area((35, 2))
+                .with_vertical_slice(Slice::ternary(1, 3));
+            options.mask = mask;
+
+            let mut result = [0; 1200];
+            blit.blit(&mut result, dst_size, &options);
+
+            let mut expected = [0; 1200];
+            for (sub_rect, part) in [
+                ((0, 0, 1, 2), (x, y, 1, 2)),
+                ((1, 0, 2, 2), (x + 1, y, 33, 2)),
+                ((3, 0, 1, 2), (x + 34, y, 1, 2)),
+            ] {
+                per_tile(&mut expected, sub_rect.into(), part.into(), mask);
+            }
+            assert_eq!(result, expected, "{options:?}");
+        }
+    }
+
+    #[test]
+    fn tile_gaps() {
+        let source = (0..6).map(|i| 0xFF_00_00_00 | (i + 1)).collect::<Vec<_>>();
+        let blit = BlitBuffer::from_buffer(&source, 3, 127);
+        let dst_size = Size::new(12, 12);
+
+        // Margins shrink the space for the tiles inside of the area
+        let mut buffer = [0; 144];
+        blit.blit(
+            &mut buffer,
+            dst_size,
+            &BlitOptions::new().with_tile_margin((1, 0)),
+        );
+        assert_eq!(buffer[..4], [0, 0xFF_00_00_01, 0, 0]);
+
+        // Gaps of a buffer with the same size as the destination
+        let row = BlitBuffer::from_buffer(&[0xFF_00_00_01, 0xFF_00_00_02, 0xFF_00_00_03], 3, 127);
+        for (options, expected) in [
+            (BlitOptions::new().with_tile_margin((1, 0)), [0, 1, 0]),
+            (BlitOptions::new().with_tile_spacing((1, 0)), [1, 2, 3]),
+        ] {
+            let mut buffer = [0; 3];
+            row.blit(&mut buffer, Size::new(3, 1), &options);
+            assert_eq!(buffer.map(|pixel| pixel & 0xFF), expected, "{options:?}");
+
+            let mut reference = [0; 3];
+            reference::blit_reference(&row, &mut reference, Size::new(3, 1), &options);
+            assert_eq!(buffer, reference, "{options:?}");
+        }
+
+        let slice9 = BlitOptions::new().with_slice9((1, 1, 1, 1));
+        for remainder in [
+            RemainderMode::TrailingClip,
+            RemainderMode::Centered,
+            RemainderMode::None,
+        ] {
+            for (spacing, margin) in [((1, 2), (0, 0)), ((0, 0), (2, 1)), ((2, 1), (1, 3))] {
+                for (area, slices) in [
+                    ((11, 10), (None, None)),
+                    ((4, 3), (None, None)),
+                    ((12, 9), (slice9.vertical_slice, slice9.horizontal_slice)),
+                ] {
+                    let mut options = BlitOptions::new_position(-1, 1)
+                        .with_area(area)
+                        .with_remainder(remainder)
+                        .with_tile_spacing(spacing)
+                        .with_tile_margin(margin)
+                        .with_uv_offset((1, -1));
+                    (options.vertical_slice, options.horizontal_slice) = slices;
+
+                    for options in transformed(&options) {
+                        let mut buffer = [0; 144];
+                        blit.blit(&mut buffer, dst_size, &options);
+
+                        let mut expected = [0; 144];
+                        reference::blit_reference(&blit, &mut expected, dst_size, &options);
+
+                        assert_eq!(buffer, expected, "{options:?}");
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn slice_matrix() {
+        let source = (0..36)
+            .map(|i| if i % 7 == 0 { 0 } else { 0xFF_00_00_00 | i })
+            .collect::<Vec<_>>();
+        let blit = BlitBuffer::from_buffer(&source, 6, 127);
+        let dst_size = Size::new(16, 16);
+
+        let slice9 = BlitOptions::new().with_slice9((1, 2, 2, 1));
+        let slices = [
+            (None, None),
+            (slice9.vertical_slice, slice9.horizontal_slice),
+            (Some(Slice::binary_first(2)), None),
+            (None, Some(Slice::binary_last(3))),
+            (Some(Slice::ternary(1, 4)), Some(Slice::ternary(2, 3))),
+            (
+                Some(Slice::ternary(1, 4).with_fill(SliceFill::Stretch)),
+                Some(Slice::binary_last(3).with_fill(SliceFill::Stretch)),
+            ),
+            (
+                Some(Slice::binary_first(2).with_fill(SliceFill::Stretch)),
+                Some(Slice::ternary(2, 3)),
+            ),
+            (
+                None,
+                Some(Slice::ternary(2, 4).with_fill(SliceFill::Stretch)),
+            ),
+            (Some(Slice::ends(2, 1)), Some(Slice::ends(0, 4))),
+        ];
+        let sub_rects = [None, Some((1, 1, 4, 4).into()), Some((2, 0, 3, 6).into())];
+        let areas = [Size::new(13, 11), Size::new(3, 4)];
+        let positions = [(1, 2), (-3, -2)];
+        let scales = [
+            (
+                RemainderMode::TrailingClip,
+                ScaleMode::Tile,
+                Filter::Nearest,
+            ),
+            (RemainderMode::Centered, ScaleMode::Tile, Filter::Nearest),
+            (RemainderMode::None, ScaleMode::Tile, Filter::Nearest),
+            (
+                RemainderMode::TrailingClip,
+                ScaleMode::Stretch,
+                Filter::Nearest,
+            ),
+            (
+                RemainderMode::TrailingClip,
+                ScaleMode::Stretch,
+                Filter::Bilinear,
+            ),
+        ];
+
+        for (vertical_slice, horizontal_slice) in slices {
+            for sub_rect in sub_rects {
+                for area in areas {
+                    for (x, y) in positions {
+                        for (remainder, scale, filter) in scales {
+                            let mut options = BlitOptions::new_position(x, y)
+                                .with_area(area)
+                                .with_remainder(remainder)
+                                .with_scale(scale)
+                                .with_filter(filter);
+                            options.sub_rect = sub_rect;
+                            options.vertical_slice = vertical_slice;
+                            options.horizontal_slice = horizontal_slice;
+
+                            for options in transformed(&options) {
+                                let mut buffer = [0; 256];
+                                blit.blit(&mut buffer, dst_size, &options);
+
+                                let mut expected = [0; 256];
+                                reference::blit_reference(&blit, &mut expected, dst_size, &options);
+
+                                assert_eq!(
+                                    buffer, expected,
+                                    "\n{options:?}\nResult:\n{:08x?}\nExpected:\n{:08x?}",
+                                    &buffer, &expected
+                                );
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn slice_fill() {
+        let row = BlitBuffer::from_buffer(&[1, 2, 3, 4].map(|i| 0xFF_00_00_00 | i), 4, 127);
+        let draw = |slice: Slice, width: u32| {
+            let mut buffer = vec![0; width as usize];
+            let options = BlitOptions::new()
+                .with_area((width, 1))
+                .with_vertical_slice(slice);
+            row.blit(&mut buffer, Size::new(width, 1), &options);
+
+            buffer.iter().map(|pixel| pixel & 0xFF).collect::<Vec<_>>()
+        };
+
+        // Only the repeated section is stretched
+        assert_eq!(draw(Slice::ternary(1, 3), 7), [1, 2, 3, 2, 3, 2, 4]);
+        assert_eq!(
+            draw(Slice::ternary(1, 3).with_fill(SliceFill::Stretch), 7),
+            [1, 2, 2, 2, 3, 3, 4]
+        );
+        assert_eq!(draw(Slice::binary_first(2), 6), [1, 2, 1, 2, 3, 4]);
+        assert_eq!(
+            draw(Slice::binary_first(2).with_fill(SliceFill::Stretch), 6),
+            [1, 1, 2, 2, 3, 4]
+        );
+        assert_eq!(
+            draw(Slice::binary_last(2).with_fill(SliceFill::Stretch), 6),
+            [1, 2, 3, 3, 4, 4]
+        );
+
+        // Smaller than the source nothing is stretched
+        assert_eq!(
+            draw(Slice::ternary(1, 3).with_fill(SliceFill::Stretch), 3),
+            [1, 2, 4]
+        );
+
+        // The center is tiled when the other axis tiles it, the edges are stretched
+        let square = BlitBuffer::from_buffer(
+            &(1..=16).map(|i| 0xFF_00_00_00 | i).collect::<Vec<_>>(),
+            4,
+            127,
+        );
+        let options = BlitOptions::new()
+            .with_area((6, 6))
+            .with_vertical_slice(Slice::ternary(1, 3).with_fill(SliceFill::Stretch))
+            .with_horizontal_slice(Slice::ternary(1, 3));
+        let mut buffer = [0; 36];
+        square.blit(&mut buffer, Size::new(6, 6), &options);
+        let columns = |row: u32, stretched: bool| {
+            let columns: [u32; 6] = if stretched {
+                [0, 1, 1, 2, 2, 3]
+            } else {
+                [0, 1, 2, 1, 2, 3]
+            };
+
+            columns.map(|column| row * 4 + column + 1)
+        };
+        let expected = [
+            columns(0, true),
+            columns(1, false),
+            columns(2, false),
+            columns(1, false),
+            columns(2, false),
+            columns(3, true),
+        ]
+        .concat();
+        assert_eq!(buffer.map(|pixel| pixel & 0xFF).to_vec(), expected);
+    }
+
+    #[test]
+    fn alpha_blend() {
+        let blit = BlitBuffer::from_buffer_with_alpha(
+            &[0xFF_FF_00_00, 0x80_FF_00_00, 0x00_FF_00_00, 0x80_00_00_FF],
+            4,
+        );
+        let dst_size = Size::new(4, 1);
+
+        // Only the fully transparent pixel is skipped when masking
+        let mut buffer = [0xFF_00_00_FF; 4];
+        blit.blit(&mut buffer, dst_size, &BlitOptions::new());
+        assert_eq!(
+            buffer,
+            [0xFF_FF_00_00, 0x80_FF_00_00, 0xFF_00_00_FF, 0x80_00_00_FF]
+        );
+
+        // Semi-transparent pixels are composited over the destination
+        let mut buffer = [0xFF_00_00_FF, 0xFF_00_00_FF, 0xFF_00_00_FF, 0x00_00_00_00];
+        blit.blit(
+            &mut buffer,
+            dst_size,
+            &BlitOptions::new().with_blend(BlendMode::Alpha),
+        );
+        assert_eq!(
+            buffer,
+            [0xFF_FF_00_00, 0xFF_80_00_7F, 0xFF_00_00_FF, 0x80_00_00_FF]
+        );
+
+        // The optimized paths blend the same as the reference
+        let source = (0..16)
+            .map(|i| (i * 0x11) << 24 | 0x40_80_C0)
+            .collect::<Vec<_>>();
+        let blit = BlitBuffer::from_buffer_with_alpha(&source, 4);
+        for options in [
+            BlitOptions::new(),
+            BlitOptions::new_position(-1, 2).with_area((7, 5)),
+            BlitOptions::new_position(1, 1)
+                .with_sub_rect((1, 1, 2, 2))
+                .with_mask((0, 0, 4, 4)),
+        ] {
+            let options = options.with_blend(BlendMode::Alpha);
+
+            let mut buffer = [0x80_20_20_20; 64];
+            blit.blit(&mut buffer, Size::new(8, 8), &options);
+
+            let mut expected = [0x80_20_20_20; 64];
+            reference::blit_reference(&blit, &mut expected, Size::new(8, 8), &options);
+
+            assert_eq!(buffer, expected, "{options:?}");
+        }
+    }
+
+    #[test]
+    fn blend_modes() {
+        let blit = BlitBuffer::from_buffer_with_alpha(&[0xFF_80_40_00, 0x80_80_40_00, 0], 3);
+        let draw = |blend| {
+            let mut buffer = [0xFF_40_80_FF; 3];
+            blit.blit(
+                &mut buffer,
+                Size::new(3, 1),
+                &BlitOptions::new().with_blend(blend),
+            );
+
+            buffer
+        };
+
+        assert_eq!(
+            draw(BlendMode::Add),
+            [0xFF_C0_C0_FF, 0xFF_80_A0_FF, 0xFF_40_80_FF]
+        );
+        assert_eq!(
+            draw(BlendMode::Multiply),
+            [0xFF_20_20_00, 0xFF_2F_4F_7F, 0xFF_40_80_FF]
+        );
+        assert_eq!(
+            draw(BlendMode::Screen),
+            [0xFF_A0_A0_FF, 0xFF_70_90_FF, 0xFF_40_80_FF]
+        );
+        assert_eq!(
+            draw(BlendMode::Subtract),
+            [0xFF_00_40_FF, 0xFF_1F_5F_FF, 0xFF_40_80_FF]
+        );
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn deserialize_options() {
+        // Options serialized before any of the later fields were added
+        let options: BlitOptions = serde_json::from_str(
+            r#"{"x":1,"y":2,"area":{"width":3,"height":4},"sub_rect":null,"mask":null,"vertical_slice":{"Ternary":{"split_first":1,"split_last":2}},"horizontal_slice":null}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            options,
+            BlitOptions::new_position(1, 2)
+                .with_area((3, 4))
+                .with_vertical_slice(Slice::ternary(1, 2))
+        );
+
+        let options = options
+            .with_blend(BlendMode::Alpha)
+            .with_opacity(0.5)
+            .with_rotation(Rotation::Clockwise90)
+            .with_tile_margin((1, 2));
+        let json = serde_json::to_string(&options).unwrap();
+        assert_eq!(serde_json::from_str::<BlitOptions>(&json).unwrap(), options);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn deserialize() {
+        let blit: BlitBuffer =
+            serde_json::from_str(r#"{"size":{"width":2,"height":1},"data":[4278190081,16777215]}"#)
+                .unwrap();
+        assert_eq!(blit.pixels(), [0xFF_00_00_01, 0]);
+
+        // The data doesn't match the size
+        assert!(serde_json::from_str::<BlitBuffer>(
+            r#"{"size":{"width":2,"height":2},"data":[4278190081,16777215]}"#
+        )
+        .is_err());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn deserialize_region() {
+        let region = geom::ClipRegion::new()
+            .with_rect((0, 0, 4, 4))
+            .with_rect((2, 2, 4, 4));
+        let json = serde_json::to_string(&region).unwrap();
+        assert_eq!(
+            serde_json::from_str::<geom::ClipRegion>(&json).unwrap(),
+            region
+        );
+
+        // Overlapping rectangles are split like when they are added
+        let region = serde_json::from_str::<geom::ClipRegion>(
+            r#"{"rects":[{"x":0,"y":0,"size":{"width":4,"height":4}},{"x":2,"y":2,"size":{"width":4,"height":4}}]}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            region,
+            geom::ClipRegion::new()
+                .with_rect((0, 0, 4, 4))
+                .with_rect((2, 2, 4, 4))
+        );
+        let pixels = region
+            .rects()
+            .iter()
+            .map(|rect| rect.size.pixels())
+            .sum::<usize>();
+        assert_eq!(pixels, 28);
+    }
+
+    #[test]
+    fn channel_order() {
+        let blit = BlitBuffer::from_buffer_with_alpha(&[0xFF_80_40_00, 0x80_80_40_20, 0], 3);
+        let dst = [0xFF_40_80_FF, 0x80_10_20_30, 0xFF_01_02_03];
+
+        for options in [
+            BlitOptions::new(),
+            BlitOptions::new().with_blend(BlendMode::Alpha),
+            BlitOptions::new().with_opacity(0.5),
+            BlitOptions::new_position(1, 0).with_blend(BlendMode::Multiply),
+        ] {
+            let mut expected = dst;
+            blit.blit(&mut expected, Size::new(3, 1), &options);
+
+            for order in [
+                ChannelOrder::Argb,
+                ChannelOrder::Abgr,
+                ChannelOrder::Rgba,
+                ChannelOrder::Bgra,
+            ] {
+                assert_eq!(order.unpack(order.pack(0x11_22_33_44)), 0x11_22_33_44);
+
+                // Drawing on a converted destination gives the converted result
+                let options = options.clone().with_channel_order(order);
+                let mut buffer = dst.map(|pixel| order.pack(pixel));
+                blit.blit(&mut buffer, Size::new(3, 1), &options);
+                assert_eq!(
+                    buffer,
+                    expected.map(|pixel| order.pack(pixel)),
+                    "{options:?}"
+                );
+
+                let mut reference = dst.map(|pixel| order.pack(pixel));
+                reference::blit_reference(&blit, &mut reference, Size::new(3, 1), &options);
+                assert_eq!(buffer, reference, "{options:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn opacity() {
+        let blit = BlitBuffer::from_buffer(&[0xFF_FF_FF_FF, 0, 0xFF_00_00_00], 3, 127);
+        let draw = |options: BlitOptions| {
+            let mut buffer = [0xFF_00_00_FF; 3];
+            blit.blit(&mut buffer, Size::new(3, 1), &options);
+
+            let mut expected = [0xFF_00_00_FF; 3];
+            reference::blit_reference(&blit, &mut expected, Size::new(3, 1), &options);
+            assert_eq!(buffer, expected, "{options:?}");
+
+            buffer
+        };
+
+        assert_eq!(
+            draw(BlitOptions::new().with_opacity(1.0)),
+            [0xFF_FF_FF_FF, 0xFF_00_00_FF, 0xFF_00_00_00]
+        );
+        assert_eq!(
+            draw(BlitOptions::new().with_opacity(0.5)),
+            [0xFF_80_80_FF, 0xFF_00_00_FF, 0xFF_00_00_7F]
+        );
+        assert_eq!(
+            draw(BlitOptions::new().with_opacity(-1.0)),
+            [0xFF_00_00_FF; 3]
+        );
+        assert_eq!(
+            draw(
+                BlitOptions::new()
+                    .with_opacity(0.5)
+                    .with_blend(BlendMode::Add)
+            ),
+            [0xFF_80_80_FF, 0xFF_00_00_FF, 0xFF_00_00_FF]
+        );
+    }
+
+    #[test]
+    fn alpha_threshold() {
+        let source = (0..16)
+            .map(|i| (i << 28) | (0x08_10_18 * i))
+            .collect::<Vec<_>>();
+        let blit = BlitBuffer::from_buffer_with_alpha(&source, 4);
+        let dst_size = Size::new(6, 6);
+
+        for threshold in [0, 0x50, 0xFF] {
+            // Same as applying the treshold when creating the buffer, except that fully transparent pixels are never drawn
+            let thresholded = BlitBuffer::from_buffer(&source, 4, threshold.max(1));
+            let rle = crate::rle::RleBlitBuffer::new(blit.clone());
+
+            for options in [
+                BlitOptions::new_position(1, 1),
+                BlitOptions::new_position(-1, 2)
+                    .with_flip_horizontal(true)
+                    .with_opacity(0.5),
+                BlitOptions::new()
+                    .with_area((6, 6))
+                    .with_scale(ScaleMode::Tile)
+                    .with_blend(BlendMode::Add),
+            ] {
+                let mut expected = [0xFF_20_20_20; 36];
+                thresholded.blit(&mut expected, dst_size, &options);
+
+                let options = options.with_alpha_threshold(threshold);
+                let mut buffer = [0xFF_20_20_20; 36];
+                blit.blit(&mut buffer, dst_size, &options);
+                assert_eq!(buffer, expected, "{options:?}");
+
+                let mut buffer = [0xFF_20_20_20; 36];
+                rle.blit(&mut buffer, dst_size, &options);
+                assert_eq!(buffer, expected, "{options:?}");
+
+                let mut buffer = [0xFF_20_20_20; 36];
+                reference::blit_reference(&blit, &mut buffer, dst_size, &options);
+                assert_eq!(buffer, expected, "{options:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn rotated_sub_rect() {
+        let source = (1..=6).map(|i| 0xFF_00_00_00 | i).collect::<Vec<_>>();
+        let blit = BlitBuffer::from_buffer(&source, 2, 127);
+
+        // Store the frame rotated clockwise next to an empty column, like an atlas
+        let mut stored = [0; 4 * 2];
+        blit.blit(
+            &mut stored,
+            Size::new(4, 2),
+            &BlitOptions::new_position(1, 0).with_rotation(Rotation::Clockwise90),
+        );
+        let stored = BlitBuffer::from_buffer(&stored, 4, 127);
+
+        for rotation in [
+            Rotation::None,
+            Rotation::Clockwise90,
+            Rotation::Clockwise180,
+            Rotation::Clockwise270,
+        ] {
+            let mut expected = [0; 9];
+            blit.blit(
+                &mut expected,
+                Size::new(3, 3),
+                &BlitOptions::new().with_rotation(rotation),
+            );
+
+            let mut buffer = [0; 9];
+            stored.blit(
+                &mut buffer,
+                Size::new(3, 3),
+                &BlitOptions::new()
+                    .with_rotation(rotation)
+                    .with_rotated_sub_rect(SubRect::new(1, 0, (3, 2))),
+            );
+            assert_eq!(buffer, expected, "{rotation:?}");
+
+            // Setting the rotation first sizes the area after rotating
+            let mut buffer = [0; 9];
+            blit.blit(
+                &mut buffer,
+                Size::new(3, 3),
+                &BlitOptions::new()
+                    .with_rotation(rotation)
+                    .with_sub_rect(SubRect::new(0, 0, (2, 3))),
+            );
+            assert_eq!(buffer, expected, "{rotation:?}");
+        }
+    }
+
+    #[test]
+    fn flip_options() {
+        let blit = BlitBuffer::from_buffer(
+            &[
+                0xFF_00_00_01,
+                0xFF_00_00_02,
+                0xFF_00_00_03,
+                0xFF_00_00_04,
+                0xFF_00_00_05,
+                0xFF_00_00_06,
+            ],
+            3,
+            127,
+        );
+        let draw = |options: &BlitOptions| {
+            let mut buffer = [0; 8];
+            blit.blit(&mut buffer, Size::new(4, 2), options);
+
+            buffer.map(|pixel| pixel & 0xFF)
+        };
+
+        // Flipping the options is the same as flipping the buffer itself
+        let mut flipped = blit.clone();
+        flipped.flip_horizontal();
+        flipped.flip_vertical();
+        let mut expected = [0; 8];
+        flipped.blit(
+            &mut expected,
+            Size::new(4, 2),
+            &BlitOptions::new_position(-1, 0),
+        );
+        assert_eq!(
+            draw(
+                &BlitOptions::new_position(-1, 0)
+                    .with_flip_horizontal(true)
+                    .with_flip_vertical(true)
+            ),
+            expected.map(|pixel| pixel & 0xFF)
+        );
+
+        // Tiles are mirrored as a whole
+        assert_eq!(
+            draw(
+                &BlitOptions::new()
+                    .with_area((4, 1))
+                    .with_flip_horizontal(true)
+            ),
+            [1, 3, 2, 1, 0, 0, 0, 0]
+        );
+    }
+
+    #[test]
+    fn rotation() {
+        // 1 2 3
+        // 4 5 6
+        let blit = BlitBuffer::from_buffer(
+            &(1..=6).map(|i| 0xFF_00_00_00 | i).collect::<Vec<_>>(),
+            3,
+            127,
+        );
+        let draw = |rotation| {
+            let mut buffer = [0; 6];
+            let options = BlitOptions::new().with_rotation(rotation);
+            let dst_size = options.area(blit.size());
+            blit.blit(&mut buffer, dst_size, &options);
+
+            (dst_size.as_tuple(), buffer.map(|pixel| pixel & 0xFF))
+        };
+
+        assert_eq!(draw(Rotation::None), ((3, 2), [1, 2, 3, 4, 5, 6]));
+        assert_eq!(draw(Rotation::Clockwise90), ((2, 3), [4, 1, 5, 2, 6, 3]));
+        assert_eq!(draw(Rotation::Clockwise180), ((3, 2), [6, 5, 4, 3, 2, 1]));
+        assert_eq!(draw(Rotation::Clockwise270), ((2, 3), [3, 6, 2, 5, 1, 4]));
+    }
+
+    #[test]
+    fn rotated() {
+        let source = (0..16)
+            .map(|i| if i % 5 == 0 { 0 } else { 0xFF_00_00_00 | i })
+            .collect::<Vec<_>>();
+        let blit = BlitBuffer::from_buffer(&source, 4, 127);
+        let dst_size = Size::new(8, 8);
+
+        for (angle, rotation) in [
+            (0.0, Rotation::None),
+            (std::f32::consts::FRAC_PI_2, Rotation::Clockwise90),
+            (std::f32::consts::PI, Rotation::Clockwise180),
+            (-std::f32::consts::FRAC_PI_2, Rotation::Clockwise270),
+        ] {
+            for options in [
+                BlitOptions::new_position(2, 1),
+                BlitOptions::new_position(-1, 5).with_mask((0, 0, 7, 7)),
+                BlitOptions::new_position(3, 3).with_sub_rect((1, 1, 2, 2)),
+            ] {
+                // Rotating around the center of a square is the same as a quarter turn
+                let size = options.sub_rect(blit.size()).size;
+                let pivot = (size.width as f32 / 2.0, size.height as f32 / 2.0);
+
+                let mut buffer = [0; 64];
+                blit.blit_rotated(&mut buffer, dst_size, &options, angle, pivot);
+
+                let mut expected = [0; 64];
+                blit.blit(
+                    &mut expected,
+                    dst_size,
+                    &options.clone().with_rotation(rotation),
+                );
+
+                assert_eq!(buffer, expected, "{angle} {options:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn stretch() {
+        let blit = BlitBuffer::from_buffer(
+            &[0xFF_00_00_01, 0xFF_00_00_02, 0xFF_00_00_03, 0xFF_00_00_04],
+            2,
+            127,
+        );
+        let draw = |area: (u32, u32)| {
+            let mut buffer = [0; 8];
+            blit.blit(
+                &mut buffer,
+                Size::new(4, 2),
+                &BlitOptions::new()
+                    .with_area(area)
+                    .with_scale(ScaleMode::Stretch),
+            );
+
+            buffer.map(|pixel| pixel & 0xFF)
+        };
+
+        // Upscaling repeats pixels
+        assert_eq!(draw((4, 2)), [1, 1, 2, 2, 3, 3, 4, 4]);
+        // Downscaling skips pixels
+        assert_eq!(draw((1, 1)), [1, 0, 0, 0, 0, 0, 0, 0]);
+        // Scaling a single axis
+        assert_eq!(draw((3, 1)), [1, 1, 2, 0, 0, 0, 0, 0]);
+
+        // Interpolating creates a gradient
+        let blit = BlitBuffer::from_buffer(&[0xFF_00_00_00, 0xFF_00_00_FF], 2, 127);
+        let mut buffer = [0; 4];
+        blit.blit(
+            &mut buffer,
+            Size::new(4, 1),
+            &BlitOptions::new()
+                .with_area((4, 1))
+                .with_scale(ScaleMode::Stretch)
+                .with_filter(Filter::Bilinear),
+        );
+        assert_eq!(
+            buffer,
+            [0xFF_00_00_00, 0xFF_00_00_3F, 0xFF_00_00_BF, 0xFF_00_00_FF]
+        );
+    }
+
+    #[test]
+    fn stride() {
+        let atlas = (0..24)
+            .map(|i| if i % 5 == 0 { 0 } else { 0xFF_00_00_00 | i })
+            .collect::<Vec<_>>();
+        let atlas_buffer = BlitBuffer::from_buffer(&atlas, 6, 127);
+        let dst_size = Size::new(8, 8);
+
+        // Drawing a part of the atlas is the same as drawing a copy of the part
+        let cropped = atlas_buffer.crop((1, 1, 3, 3));
+        let strided = BlitBufferRef::from_strided(Size::new(3, 3), 6, &atlas[7..22]).unwrap();
+        assert_eq!(strided.stride(), 6);
+        for options in [
+            BlitOptions::new_position(1, 2),
+            BlitOptions::new_position(-1, 0).with_sub_rect((1, 1, 2, 2)),
+            BlitOptions::new()
+                .with_area((7, 5))
+                .with_scale(ScaleMode::Stretch),
+            BlitOptions::new()
+                .with_area((7, 5))
+                .with_scale(ScaleMode::Stretch)
+                .with_filter(Filter::Bilinear),
+            BlitOptions::new()
+                .with_area(dst_size)
+                .with_slice9((1, 1, 1, 1)),
+        ] {
+            for options in transformed(&options) {
+                let mut buffer = [0; 64];
+                strided.blit(&mut buffer, dst_size, &options);
+                let mut expected = [0; 64];
+                cropped.blit(&mut expected, dst_size, &options);
+                assert_eq!(buffer, expected, "{options:?}");
+            }
+        }
+
+        // The data must contain every row
+        assert_eq!(
+            BlitBufferRef::from_strided(Size::new(3, 3), 6, &atlas[7..21]).unwrap_err(),
+            Error::StrideMismatch {
+                size: Size::new(3, 3),
+                stride: 6,
+                len: 14
+            }
+        );
+        assert!(BlitBufferRef::from_strided(Size::new(3, 1), 2, &atlas).is_err());
+        assert!(BlitBufferRef::from_strided(Size::new(3, 0), 3, &[]).is_ok());
+    }
+
+    #[test]
+    fn scaled_int() {
+        let source = (0..16)
+            .map(|i| if i % 5 == 0 { 0 } else { 0xFF_00_00_00 | i })
+            .collect::<Vec<_>>();
+        let blit = BlitBuffer::from_buffer(&source, 4, 127);
+        let dst_size = Size::new(16, 16);
+
+        for factor in 1..4 {
+            for options in [
+                BlitOptions::new_position(1, 2),
+                BlitOptions::new_position(-5, 7),
+                BlitOptions::new_position(3, -4).with_mask((4, 0, 7, 9)),
+                BlitOptions::new_position(2, 2).with_sub_rect((1, 1, 2, 3)),
+                BlitOptions::new_position(2, 2).with_sub_rect((-1, 2, 3, 3)),
+            ] {
+                let mut buffer = [0; 256];
+                blit.blit_scaled_int(&mut buffer, dst_size, &options, factor);
+
+                let mut expected = [0; 256];
+                let mut stretch_options = options.clone().with_scale(ScaleMode::Stretch);
+                stretch_options.area = Some(options.sub_rect(blit.size()).size * factor);
+                blit.blit(&mut expected, dst_size, &stretch_options);
+
+                assert_eq!(buffer, expected, "{factor} {options:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn affine() {
+        let source = (0..16)
+            .map(|i| if i % 5 == 0 { 0 } else { 0xFF_00_00_00 | i })
+            .collect::<Vec<_>>();
+        let blit = BlitBuffer::from_buffer(&source, 4, 127);
+        let dst_size = Size::new(16, 16);
+
+        let options = BlitOptions::new_position(3, 2).with_mask((0, 0, 12, 12));
+
+        // The identity matrix is a normal blit
+        let mut buffer = [0; 256];
+        blit.blit_affine(
+            &mut buffer,
+            dst_size,
+            &options,
+            [[1.0, 0.0, 0.0], [0.0, 1.0, 0.0]],
+        );
+        let mut expected = [0; 256];
+        blit.blit(&mut expected, dst_size, &options);
+        assert_eq!(buffer, expected);
+
+        // Scaling by an integer is the same as stretching
+        let mut buffer = [0; 256];
+        blit.blit_affine(
+            &mut buffer,
+            dst_size,
+            &options,
+            [[3.0, 0.0, 0.0], [0.0, 2.0, 0.0]],
+        );
+        let mut expected = [0; 256];
+        blit.blit(
+            &mut expected,
+            dst_size,
+            &options
+                .clone()
+                .with_area((12, 8))
+                .with_scale(ScaleMode::Stretch),
+        );
+        assert_eq!(buffer, expected);
+
+        // A mirroring matrix with a translation is the same as flipping
+        let mut buffer = [0; 256];
+        blit.blit_affine(
+            &mut buffer,
+            dst_size,
+            &options,
+            [[-1.0, 0.0, 4.0], [0.0, 1.0, 0.0]],
+        );
+        let mut expected = [0; 256];
+        blit.blit(
+            &mut expected,
+            dst_size,
+            &options.clone().with_flip_horizontal(true),
+        );
+        assert_eq!(buffer, expected);
+
+        // Matrices that can't be inverted don't draw anything
+        let mut buffer = [0; 256];
+        blit.blit_affine(
+            &mut buffer,
+            dst_size,
+            &options,
+            [[1.0, 2.0, 0.0], [2.0, 4.0, 0.0]],
+        );
+        assert_eq!(buffer, [0; 256]);
+    }
+
+    #[test]
+    fn subpixel() {
+        let source = (0..12)
+            .map(|i| if i % 5 == 0 { 0 } else { 0xFF_00_00_00 | i })
+            .collect::<Vec<_>>();
+        let blit = BlitBuffer::from_buffer(&source, 4, 127);
+        let dst_size = Size::new(8, 8);
+
+        // Fractional positions are rounded down
+        let options = BlitOptions::new_position(-0.5, 1.5);
+        assert_eq!((options.x, options.y), (-1, 1));
+        assert_eq!(options.with_position(f32::NAN, 2.9).x, 0);
+
+        // Coordinates, rectangles and masks are rounded the same way, out of range positions are clamped
+        assert_eq!(Coordinate::new(-0.5, 1e10), Coordinate::new(-1, i32::MAX));
+        assert_eq!(
+            SubRect::new(-2.5, -1e10, (1, 1)),
+            SubRect::new(-3, i32::MIN, (1, 1))
+        );
+        assert_eq!(
+            BlitOptions::new().with_mask((-0.5, 0.5, 1, 1)).mask,
+            Some(SubRect::new(-1, 0, (1, 1)))
+        );
+        let shifted = SubRect::new(0, 0, (2, 2)).shift(-0.5, 1.5);
+        assert_eq!((shifted.x, shifted.y), (-1, 1));
+
+        // Whole positions are a normal blit
+        let options = BlitOptions::new()
+            .with_sub_rect((1, 0, 3, 3))
+            .with_mask((0, 0, 6, 6))
+            .with_anchor(Anchor::Center);
+        for (x, y) in [(2, 3), (-1, 0), (7, 5)] {
+            let mut buffer = [0; 64];
+            blit.blit_subpixel(&mut buffer, dst_size, &options, x as f32, y as f32);
+            let mut expected = [0; 64];
+            blit.blit(
+                &mut expected,
+                dst_size,
+                &options.clone().with_position(x, y),
+            );
+            assert_eq!(buffer, expected);
+        }
+
+        // Coverage is spread over the neighboring pixels
+        let blit = BlitBuffer::from_buffer(&[0xFF_00_00_10], 1, 127);
+        let mut buffer = [0; 3];
+        blit.blit_subpixel(&mut buffer, Size::new(3, 1), &BlitOptions::new(), 1.25, 0.0);
+        assert_eq!(buffer, [0, 0xBF_00_00_10, 0x3F_00_00_10]);
+
+        let mut buffer = [0; 3];
+        blit.blit_subpixel(
+            &mut buffer,
+            Size::new(3, 1),
+            &BlitOptions::new(),
+            -0.75,
+            0.0,
+        );
+        assert_eq!(buffer, [0x3F_00_00_10, 0, 0]);
+    }
+
+    #[test]
+    fn flip_in_place() {
+        let mut blit = BlitBuffer::from_buffer(
+            &[
+                0xFF_00_00_01,
+                0xFF_00_00_02,
+                0xFF_00_00_03,
+                0xFF_00_00_04,
+                0xFF_00_00_05,
+                0xFF_00_00_06,
+            ],
+            2,
+            127,
+        );
+
+        blit.flip_horizontal();
+        assert_eq!(
+            blit.pixels(),
+            [
+                0xFF_00_00_02,
+                0xFF_00_00_01,
+                0xFF_00_00_04,
+                0xFF_00_00_03,
+                0xFF_00_00_06,
+                0xFF_00_00_05
+            ]
+        );
+
+        blit.flip_vertical();
+        assert_eq!(
+            blit.pixels(),
+            [
+                0xFF_00_00_06,
+                0xFF_00_00_05,
+                0xFF_00_00_04,
+                0xFF_00_00_03,
+                0xFF_00_00_02,
+                0xFF_00_00_01
+            ]
+        );
+    }
+
+    #[test]
+    fn opaque_runs() {
+        let mut blit = BlitBuffer::from_buffer(
+            &[
+                0,
+                0xFF_00_00_01,
+                0xFF_00_00_02,
+                0,
+                0xFF_00_00_03,
+                0xFF_00_00_04,
+                0,
+                0xFF_00_00_05,
+            ],
+            4,
+            127,
+        );
+        assert_eq!(blit.opaque_runs, [1..3, 4..6, 7..8]);
+
+        // Cached rows are kept up to date
+        let mut cached = blit.clone().with_cached_rows(true);
+        assert_eq!(cached.row_runs, [0, 1, 3]);
+        cached.flip_horizontal();
+        assert_eq!(cached.row_runs, [0, 1, 3]);
+        let mut changed = cached.clone();
+        changed.pixels_mut();
+        assert_eq!(changed.row_runs, [0, 0, 0]);
+
+        // Changing the pixels with a closure finds the runs again
+        let mut modified = cached.clone();
+        modified.modify_pixels(|pixels| pixels[0] = 0xFF_00_00_06);
+        assert_eq!(modified.opaque_runs, [0..3, 4..5, 6..8]);
+        assert_eq!(modified.row_runs, [0, 1, 3]);
+        assert!(!changed.with_cached_rows(false).has_cached_rows());
+
+        blit.flip_horizontal();
+        assert_eq!(blit.opaque_runs, [1..3, 4..5, 6..8]);
+
+        // Copying the runs gives the same result as masking every pixel
+        let mut masked = blit.clone();
+        masked.pixels_mut();
+        assert!(masked.opaque_runs.is_empty());
+
+        for options in [
+            BlitOptions::new(),
+            BlitOptions::new_position(-1, 1),
+            BlitOptions::new_position(2, 0).with_mask((0, 0, 5, 2)),
+            BlitOptions::new().with_sub_rect((1, 0, 2, 2)),
+            BlitOptions::new().with_flip_vertical(true),
+        ] {
+            let mut expected = [0xFF_10_20_30; 4 * 2];
+            masked.blit(&mut expected, Size::new(4, 2), &options);
+
+            let mut result = [0xFF_10_20_30; 4 * 2];
+            blit.blit(&mut result, Size::new(4, 2), &options);
+
+            assert_eq!(result, expected, "{options:?}");
+
+            let mut result = [0xFF_10_20_30; 4 * 2];
+            cached.blit(&mut result, Size::new(4, 2), &options);
+
+            assert_eq!(result, expected, "{options:?}");
+        }
+    }
+
+    #[test]
+    fn visibility() {
+        let blit = BlitBuffer::from_buffer(&[0xFF_FF_FF_FF; 16], 4, 127);
+        let dst_size = Size::new(10, 10);
+        let mut buffer = [0; 100];
+
+        // Fully inside
+        assert_eq!(
+            blit.blit_with_rect(&mut buffer, dst_size, &BlitOptions::new_position(2, 3)),
+            (Some((2, 3, 4, 4).into()), Visibility::Full)
+        );
+
+        // Straddling the left edge
+        assert_eq!(
+            blit.blit_with_rect(&mut buffer, dst_size, &BlitOptions::new_position(-1, 3)),
+            (Some((0, 3, 3, 4).into()), Visibility::Partial)
+        );
+
+        // Clipped by a mask
+        assert_eq!(
+            blit.blit_with_rect(
+                &mut buffer,
+                dst_size,
+                &BlitOptions::new_position(2, 3).with_mask((0, 0, 4, 4))
+            ),
+            (Some((2, 3, 2, 1).into()), Visibility::Partial)
+        );
+
+        // Fully outside
+        assert_eq!(
+            blit.blit_with_rect(&mut buffer, dst_size, &BlitOptions::new_position(10, 3)),
+            (None, Visibility::Culled)
+        );
+    }
+
+    #[test]
+    fn try_blit() {
+        let blit = BlitBuffer::from_buffer(&[0xFF_FF_FF_FF; 16], 4, 127);
+        let dst_size = Size::new(10, 10);
+
+        // Same result as drawing the normal way
+        for options in [
+            BlitOptions::new_position(2, 3),
+            BlitOptions::new_position(-1, 3).with_anchor(Anchor::Center),
+            BlitOptions::new_position(10, 3),
+        ] {
+            let mut buffer = [0; 100];
+            let stats = blit.try_blit(&mut buffer, dst_size, &options).unwrap();
+
+            let mut expected = [0; 100];
+            let (rect, visibility) = blit.blit_with_rect(&mut expected, dst_size, &options);
+            assert_eq!(stats, BlitStats { rect, visibility });
+            assert_eq!(buffer, expected);
+        }
+
+        assert_eq!(
+            blit.try_blit(&mut [0; 99], dst_size, &BlitOptions::new()),
+            Err(Error::SizeMismatch {
+                size: dst_size,
+                len: 99
+            })
+        );
+        assert_eq!(
+            blit.try_blit(&mut [], Size::new(0, 10), &BlitOptions::new()),
+            Err(Error::EmptyDestination)
+        );
+    }
+
+    #[test]
+    fn pixel_blend() {
+        let source = (0..16)
+            .map(|i| {
+                if i % 3 == 0 {
+                    0
+                } else {
+                    0x80_00_00_00 | (0x08_10_18 * i)
+                }
+            })
+            .collect::<Vec<_>>();
+        let blit = BlitBuffer::from_buffer_with_alpha(&source, 4);
+        let dst_size = Size::new(10, 10);
+        let background = (0..100)
+            .map(|i| 0xFF_00_00_00 | (0x02_03_04 * i))
+            .collect::<Vec<_>>();
+
+        // The blend modes give the same result as drawing the normal way
+        for blend in [BlendMode::Mask, BlendMode::Alpha, BlendMode::Add] {
+            for options in [
+                BlitOptions::new_position(2, 3),
+                BlitOptions::new_position(-1, 3)
+                    .with_flip_horizontal(true)
+                    .with_opacity(0.5),
+                BlitOptions::new_position(1, 1)
+                    .with_area((7, 5))
+                    .with_scale(ScaleMode::Stretch),
+                BlitOptions::new()
+                    .with_area((10, 10))
+                    .with_scale(ScaleMode::Tile),
+            ] {
+                let options = options.with_blend(blend);
+
+                let mut buffer = background.clone();
+                blit.blit_blend(&mut buffer, dst_size, &options, &blend);
+
+                let mut expected = background.clone();
+                blit.blit(&mut expected, dst_size, &options);
+                assert_eq!(buffer, expected);
+            }
+        }
 
-        // Create the data buffer filled with transparent pixels
-        let data = iter
-            .map(|pixel| {
-                if pixel < alpha_treshold {
-                    0x00_00_00_00
+        // Count how often every pixel is drawn, ignoring the blend mode of the options
+        let mut buffer = vec![0_u32; 100];
+        let count = |dst: u32, src: u32| if src >> 24 > 0 { dst + 1 } else { dst };
+        let options = BlitOptions::new_position(2, 2).with_blend(BlendMode::Alpha);
+        blit.blit_blend(&mut buffer, dst_size, &options, &count);
+        blit.blit_blend(&mut buffer, dst_size, &options.with_position(3, 2), &count);
+        assert_eq!(buffer[2 * 10 + 2..2 * 10 + 7], [0, 1, 2, 1, 0]);
+    }
+
+    #[test]
+    fn pixel_type() {
+        assert!((0..=u16::MAX).all(|pixel| u16::from_argb(pixel.to_argb()) == pixel));
+        assert_eq!(0xFF_FF_u16.to_argb(), 0xFF_FF_FF_FF);
+
+        let source = (0..16)
+            .map(|i| {
+                if i % 3 == 0 {
+                    0
                 } else {
-                    pixel | 0xFF_00_00_00
+                    0x80_00_00_00 | (0x08_10_18 * i)
                 }
             })
             .collect::<Vec<_>>();
+        let blit = BlitBuffer::from_buffer_with_alpha(&source, 4);
+        let dst_size = Size::new(10, 10);
+        let background = (0..100).map(|i| i * 0x02_63).collect::<Vec<u16>>();
 
-        // We can calculate the height from the total buffer
-        let size = Size::from_len(data.len(), width.to_usize().unwrap_or_default());
+        // Same as drawing on the converted pixels and converting them back
+        for options in [
+            BlitOptions::new_position(2, 3),
+            BlitOptions::new_position(-1, 3).with_blend(BlendMode::Alpha),
+            BlitOptions::new_position(1, 1)
+                .with_area((7, 5))
+                .with_scale(ScaleMode::Stretch)
+                .with_opacity(0.5),
+        ] {
+            let mut buffer = background.clone();
+            blit.blit_into(&mut buffer, dst_size, &options);
 
-        Self { size, data }
+            let mut expected = background
+                .iter()
+                .map(|pixel| pixel.to_argb())
+                .collect::<Vec<_>>();
+            blit.blit(&mut expected, dst_size, &options);
+            assert_eq!(
+                buffer,
+                expected.into_iter().map(u16::from_argb).collect::<Vec<_>>()
+            );
+        }
     }
 
-    /// Width of the buffer in pixels.
-    pub fn width(&self) -> u32 {
-        self.size.width
+    #[test]
+    fn positions() {
+        let source = (0..16)
+            .map(|i| if i % 3 == 0 { 0 } else { 0xFF_00_00_00 | i })
+            .collect::<Vec<_>>();
+        let blit = BlitBuffer::from_buffer(&source, 4, 127);
+        let dst_size = Size::new(10, 10);
+
+        let positions = [(-2, -2), (0, 0), (3, 5), (8, 8), (9, -3), (20, 20)]
+            .map(Coordinate::from)
+            .to_vec();
+
+        for base_options in [
+            BlitOptions::new(),
+            BlitOptions::new().with_sub_rect((1, 1, 2, 3)),
+            BlitOptions::new().with_mask((1, 2, 6, 6)),
+            BlitOptions::new().with_area((7, 5)),
+            BlitOptions::new().with_anchor((2, 1)),
+            BlitOptions::new().with_anchor(Anchor::Bottom),
+        ] {
+            let mut buffer = [0; 100];
+            blit.blit_positions(&mut buffer, dst_size, &base_options, &positions);
+
+            let mut expected = [0; 100];
+            for position in &positions {
+                let mut options = base_options.clone();
+                options.set_position(*position);
+                blit.blit(&mut expected, dst_size, &options);
+            }
+
+            assert_eq!(buffer, expected, "{base_options:?}");
+        }
     }
 
-    /// Height of the buffer in pixels.
-    pub fn height(&self) -> u32 {
-        self.size.height
+    #[test]
+    fn anchor() {
+        let source = (0..16)
+            .map(|i| if i % 3 == 0 { 0 } else { 0xFF_00_00_00 | i })
+            .collect::<Vec<_>>();
+        let blit = BlitBuffer::from_buffer(&source, 4, 127);
+        let dst_size = Size::new(10, 10);
+
+        for options in [
+            BlitOptions::new_position(5, 5),
+            BlitOptions::new_position(5, 5).with_mask((1, 2, 6, 6)),
+            BlitOptions::new_position(5, 5).with_area((7, 5)),
+            BlitOptions::new_position(5, 5).with_rotation(Rotation::Clockwise90),
+        ] {
+            for anchor in [
+                Anchor::Pivot(Coordinate::new(3, -2)),
+                Anchor::Center,
+                Anchor::BottomRight,
+                Anchor::Top,
+            ] {
+                // The anchor is the same as moving the position
+                let anchored = options.clone().with_anchor(anchor);
+                let offset = anchor.offset(options.area(blit.size()));
+                let moved = options
+                    .clone()
+                    .with_position(options.x - offset.x, options.y - offset.y);
+
+                let mut expected = [0; 100];
+                blit.blit(&mut expected, dst_size, &moved);
+                let mut result = [0; 100];
+                blit.blit(&mut result, dst_size, &anchored);
+                assert_eq!(result, expected, "{anchored:?}");
+
+                let mut reference = [0; 100];
+                crate::reference::blit_reference(&blit, &mut reference, dst_size, &anchored);
+                assert_eq!(reference, expected, "{anchored:?}");
+
+                assert_eq!(
+                    blit.effective_rect(dst_size, &anchored),
+                    blit.effective_rect(dst_size, &moved)
+                );
+            }
+        }
     }
 
-    /// Size of the blitbuffer in pixels.
-    pub fn size(&self) -> Size {
-        self.size
+    #[test]
+    fn split_grid() {
+        let sheet = BlitBuffer::from_buffer(&[0; 7 * 5], 7, 127);
+
+        assert_eq!(
+            sheet.split_grid((3, 2)),
+            [
+                SubRect::new(0, 0, (3, 2)),
+                SubRect::new(3, 0, (3, 2)),
+                SubRect::new(0, 2, (3, 2)),
+                SubRect::new(3, 2, (3, 2)),
+            ]
+        );
+        assert_eq!(sheet.split_grid((7, 5)), [SubRect::from_size((7, 5))]);
+        assert!(sheet.split_grid((8, 1)).is_empty());
+        assert!(sheet.split_grid((0, 1)).is_empty());
     }
 
-    /// Get a reference to the pixel data.
-    pub fn pixels(&self) -> &[Color] {
-        &self.data
+    #[test]
+    fn crop() {
+        let source = (0..20)
+            .map(|i| if i % 3 == 0 { 0 } else { 0xFF_00_00_00 | i })
+            .collect::<Vec<_>>();
+        let blit = BlitBuffer::from_buffer(&source, 5, 127);
+
+        // Drawing the crop is the same as drawing the sub rectangle
+        for rect in [(1, 1, 3, 2), (0, 2, 2, 2), (0, 0, 5, 4)] {
+            let cropped = blit.crop(rect);
+
+            let mut expected = [0; 25];
+            blit.blit(
+                &mut expected,
+                Size::new(5, 5),
+                &BlitOptions::new().with_sub_rect(rect),
+            );
+            let mut result = [0; 25];
+            cropped.blit(&mut result, Size::new(5, 5), &BlitOptions::new());
+            assert_eq!(result, expected, "{rect:?}");
+        }
+
+        // Clipped by the buffer
+        let clipped = blit.crop((-1, 2, 3, 5));
+        assert_eq!(clipped.size(), Size::new(2, 2));
+        assert_eq!(clipped.pixels(), blit.crop((0, 2, 2, 2)).pixels());
+
+        let empty = blit.crop((5, 0, 2, 2));
+        assert_eq!(empty.size(), Size::new(0, 0));
+        assert!(empty.pixels().is_empty());
     }
 
-    /// Get a mutable reference to the pixel data.
-    pub fn pixels_mut(&mut self) -> &mut [Color] {
-        &mut self.data
+    #[test]
+    fn trim() {
+        let mut source = [0; 6 * 5];
+        source[6 + 2] = 0xFF_00_00_01;
+        source[3 * 6 + 4] = 0x80_00_00_02;
+        let blit = BlitBuffer::from_buffer_with_alpha(&source, 6);
+
+        let (trimmed, offset) = blit.trim();
+        assert_eq!(trimmed.size(), Size::new(3, 3));
+        assert_eq!(offset, Coordinate::new(2, 1));
+
+        // Drawing at the offset is the same as drawing the original
+        let mut expected = [0; 6 * 5];
+        blit.blit(&mut expected, Size::new(6, 5), &BlitOptions::new());
+        let mut result = [0; 6 * 5];
+        trimmed.blit(
+            &mut result,
+            Size::new(6, 5),
+            &BlitOptions::new_position(2, 1),
+        );
+        assert_eq!(result, expected);
+
+        // Only the pixels with an alpha of zero are transparent
+        assert_eq!(
+            BlitBuffer::from_buffer_with_alpha(&[0x00_FF_FF_FF, 0x01_00_00_00], 2).opaque_bounds(),
+            Some(SubRect::new(1, 0, (1, 1)))
+        );
+
+        let (empty, offset) = BlitBuffer::from_buffer_with_alpha(&[0; 4], 2).trim();
+        assert_eq!(empty.size(), Size::new(0, 0));
+        assert_eq!(offset, Coordinate::new(0, 0));
     }
 
-    /// Divide the target area into given slices of rectangles to draw.
-    ///
-    /// A `(source, target)` rectangle tuple is returned.
-    fn slice_projections(
-        &self,
-        options: &BlitOptions,
-        target_area: Size,
-    ) -> Vec<(SubRect, SubRect)> {
-        match (options.vertical_slice, options.horizontal_slice) {
-            // No slices, so no need to split it
-            (None, None) => Vec::new(),
-            // Only a horizontal slice
-            (None, Some(horizontal)) => horizontal
-                .divide_area_iter(self.height(), target_area.height)
-                .map(|horizontal| horizontal.into_sub_rects_static_x(self.width()))
-                .collect(),
-            // Only a vertical slice
-            (Some(vertical), None) => vertical
-                .divide_area_iter(self.width(), target_area.width)
-                .map(|vertical| vertical.into_sub_rects_static_y(self.height()))
-                .collect(),
-            // The buffer is split both horizontally and vertically
-            (Some(vertical), Some(horizontal)) => {
-                let horizontal_ranges = vertical
-                    .divide_area_iter(self.width(), target_area.width)
-                    .collect::<Vec<_>>();
-                let vertical_ranges =
-                    horizontal.divide_area_iter(self.height(), target_area.height);
-
-                // Return a cartesian product of all ranges
-                vertical_ranges
-                    .flat_map(|vertical| {
-                        horizontal_ranges.iter().map(move |horizontal| {
-                            SliceProjection::combine_into_sub_rects(horizontal, &vertical)
-                        })
-                    })
-                    .collect()
+    #[test]
+    fn hit_test() {
+        let source = (0..16)
+            .map(|i| if i % 3 == 0 { 0 } else { 0xFF_00_00_00 | i })
+            .collect::<Vec<_>>();
+        let blit = BlitBuffer::from_buffer(&source, 4, 127);
+        let dst_size = Size::new(12, 12);
+
+        for options in [
+            BlitOptions::new_position(2, 1),
+            BlitOptions::new_position(-1, 2).with_area((11, 7)),
+            BlitOptions::new_position(1, 1)
+                .with_area((10, 9))
+                .with_slice9((1, 1, 2, 2))
+                .with_mask((0, 0, 8, 12)),
+            BlitOptions::new_position(3, 0)
+                .with_rotation(Rotation::Clockwise90)
+                .with_flip_horizontal(true)
+                .with_blend(BlendMode::Alpha)
+                .with_opacity(0.5),
+        ] {
+            // Every visible pixel drawn by the reference is hit
+            let mut expected = [0; 12 * 12];
+            crate::reference::blit_reference(
+                &blit,
+                &mut expected,
+                dst_size,
+                &options
+                    .clone()
+                    .with_blend(BlendMode::Mask)
+                    .with_opacity(1.0),
+            );
+
+            for (index, pixel) in expected.iter().enumerate() {
+                let (x, y) = ((index % 12) as i32, (index / 12) as i32);
+                assert_eq!(
+                    blit.hit_test(&options, (x, y)),
+                    *pixel != 0,
+                    "{options:?} at {x}, {y}"
+                );
             }
         }
-    }
 
-    /// Blit a sliced section.
-    fn blit_slice(&self, dst: &mut [u32], dst_size: Size, options: &BlitOptions) {
-        // If the size of the image is the same as our buffer and the location is zero we can completely blit all bytes
-        if options.x == 0 && options.y == 0 && dst_size == self.size {
-            let pixels = dst_size.pixels();
-            self.blit_horizontal(dst, 0..pixels, 0..pixels);
+        assert!(!blit.hit_test(&BlitOptions::new().with_opacity(0.0), (1, 0)));
+    }
 
-            return;
-        }
+    #[test]
+    fn outline() {
+        let blit = BlitBuffer::from_buffer(
+            &[
+                0,
+                0,
+                0,
+                0, //
+                0,
+                0xFF_FF_FF_FF,
+                0xFF_FF_FF_FF,
+                0, //
+                0,
+                0,
+                0,
+                0,
+            ],
+            4,
+            127,
+        );
+        let render = |buffer: &BlitBuffer| {
+            buffer
+                .pixels()
+                .chunks_exact(buffer.width() as usize)
+                .map(|row| {
+                    row.iter()
+                        .map(|pixel| if *pixel == 0 { '.' } else { '#' })
+                        .collect::<String>()
+                })
+                .collect::<Vec<_>>()
+        };
 
-        // Convert the destination to view so we can calculate with it
-        let dst_view = ImageView::full(dst_size);
+        assert_eq!(
+            render(&blit.outline(0xFF_00_00_00, 1)),
+            ["......", ".####.", ".#..#.", ".####.", "......"]
+        );
+        assert_eq!(
+            render(&blit.outline(0xFF_00_00_00, 2)),
+            ["........", ".######.", ".######.", ".##..##.", ".######.", ".######.", "........"]
+        );
 
-        // Convert our source to a view
-        let src_view = ImageView::full(self.size);
+        let empty = blit.outline(0xFF_00_00_00, 0);
+        assert_eq!(empty.size(), blit.size());
+        assert!(empty.pixels().iter().all(|pixel| *pixel == 0));
+    }
 
-        // Find a view on the dst based on the area
-        let area = options.area(self.size);
-        let mut dst_area = match dst_view.sub_i32(options.x, options.y, area) {
-            Some(dst_area) => dst_area,
-            None => return,
-        };
+    #[test]
+    fn shadow() {
+        let blit = BlitBuffer::from_buffer(&[0, 0xFF_00_00_01, 0xFF_00_00_02, 0], 2, 127);
+        let dst_size = Size::new(4, 4);
 
-        // Another view based on the subrectangle
-        let mut sub_rect_view = match src_view.sub(options.sub_rect(self.size)) {
-            Some(sub_rect_view) => sub_rect_view,
-            None => return,
-        };
+        for options in [
+            BlitOptions::new_position(1, 1),
+            BlitOptions::new_position(0, 0)
+                .with_area((3, 3))
+                .with_mask((1, 0, 3, 4)),
+        ] {
+            let mut expected = [0xFF_FF_FF_FF; 16];
+            blit.silhouette(0x80_00_00_00).blit(
+                &mut expected,
+                dst_size,
+                &options
+                    .clone()
+                    .with_position(options.x + 1, options.y + 1)
+                    .with_blend(BlendMode::Alpha),
+            );
+            blit.blit(&mut expected, dst_size, &options);
 
-        // We can draw the image exactly
-        if sub_rect_view.size() == area {
-            if let Some(mask) = options.mask {
-                let (prev_x, prev_y) = dst_area.coord();
+            let mut result = [0xFF_FF_FF_FF; 16];
+            blit.blit_with_shadow(&mut result, dst_size, &options, (1, 1), 0x80_00_00_00);
+            assert_eq!(result, expected, "{options:?}");
+            assert!(result.contains(&0xFF_7F_7F_7F), "{options:?}");
+        }
+    }
 
-                // Clip the dst view on the mask area first
-                dst_area = dst_area.clip(mask);
+    #[test]
+    #[cfg(feature = "unsafe-fast")]
+    #[should_panic(expected = "destination has less pixels than its size")]
+    fn unchecked_short_destination() {
+        let blit = BlitBuffer::from_buffer(&[0xFF_FF_FF_FF; 4], 2, 127);
 
-                // When it's fully clipped do nothing
-                if dst_area.width() == 0 || dst_area.height() == 0 {
-                    return;
-                }
+        // The unchecked loops would write past the end of the destination
+        let mut buffer = [0; 3];
+        blit.blit(&mut buffer, Size::new(2, 2), &BlitOptions::new());
+    }
 
-                // How much coordinates got offset changed
-                let (new_x, new_y) = dst_area.coord();
+    #[test]
+    fn blit_u8() {
+        /// Source without a known size, so all rows are converted.
+        struct Unsized(BlitBuffer);
 
-                // Shift the UV coords of the sub rect view
-                sub_rect_view.0.x = (sub_rect_view.0.x + new_x - prev_x).max(0);
-                sub_rect_view.0.y = (sub_rect_view.0.y + new_y - prev_y).max(0);
-                sub_rect_view.0.size.width = dst_area.width();
-                sub_rect_view.0.size.height = dst_area.height();
+        impl Blit for Unsized {
+            fn blit(&self, dst: &mut [u32], dst_size: Size, options: &BlitOptions) {
+                self.0.blit(dst, dst_size, options);
             }
+        }
 
-            // Pixel range of the source
-            sub_rect_view
-                .parent_ranges_iter(self.size)
-                // Zipped with pixel range of the destination
-                .zip(dst_area.parent_ranges_iter(dst_size))
-                .for_each(|(src_range, dst_range)| self.blit_horizontal(dst, dst_range, src_range));
-        } else {
-            // Recursively call this function with a new area defined by the sub rectangle to tile
-
-            // Amount of tiles we need to fully render
-            let tiles = area / sub_rect_view.size();
-            let remainder = area % sub_rect_view.size();
-
-            for tile_x in 0..tiles.width {
-                // Fully render the filled tiles
-                for tile_y in 0..tiles.height {
-                    let mut new_options = BlitOptions::new_position(
-                        options.x + (tile_x * sub_rect_view.width()) as i32,
-                        options.y + (tile_y * sub_rect_view.height()) as i32,
-                    )
-                    .with_sub_rect(sub_rect_view.as_sub_rect());
-                    new_options.mask = options.mask;
-
-                    self.blit_slice(dst, dst_size, &new_options);
+        let source = (0..16)
+            .map(|i| {
+                if i % 3 == 0 {
+                    0
+                } else {
+                    0x80_00_00_00 | i << 8 | i
                 }
+            })
+            .collect::<Vec<_>>();
+        let blit = BlitBuffer::from_buffer(&source, 4, 127);
+        let dst_size = Size::new(6, 6);
+        let background = (0..36).map(|i| 0xFF_10_20_00 | i).collect::<Vec<_>>();
 
-                if remainder.height > 0 {
-                    // Render the horizontal remainder
-                    let mut new_options = BlitOptions::new_position(
-                        options.x + (tile_x * sub_rect_view.width()) as i32,
-                        options.y + (tiles.height * sub_rect_view.height()) as i32,
-                    )
-                    .with_sub_rect(sub_rect_view.as_sub_rect())
-                    .with_area((sub_rect_view.width(), remainder.height));
-                    new_options.mask = options.mask;
+        for options in [
+            BlitOptions::new_position(1, 1),
+            BlitOptions::new_position(-1, -2).with_blend(BlendMode::Alpha),
+            BlitOptions::new_position(0, 3)
+                .with_area((6, 5))
+                .with_mask((1, 4, 3, 1)),
+            BlitOptions::new_position(2, 2).with_rotation(Rotation::Clockwise90),
+            BlitOptions::new_position(7, 0),
+        ] {
+            let mut expected = background.clone();
+            blit.blit(&mut expected, dst_size, &options);
+            let expected = expected
+                .iter()
+                .flat_map(|pixel| {
+                    let [alpha, red, green, blue] = pixel.to_be_bytes();
+                    [red, green, blue, alpha]
+                })
+                .collect::<Vec<_>>();
 
-                    self.blit_slice(dst, dst_size, &new_options);
-                }
-            }
+            let bytes = background
+                .iter()
+                .flat_map(|pixel| {
+                    let [alpha, red, green, blue] = pixel.to_be_bytes();
+                    [red, green, blue, alpha]
+                })
+                .collect::<Vec<_>>();
+            let mut result = bytes.clone();
+            blit.blit_u8(&mut result, dst_size, &options);
+            assert_eq!(result, expected, "{options:?}");
 
-            if remainder.width > 0 {
-                // Render the vertical remainder
-                for tile_y in 0..tiles.height {
-                    let mut new_options = BlitOptions::new_position(
-                        options.x + (tiles.width * sub_rect_view.width()) as i32,
-                        options.y + (tile_y * sub_rect_view.height()) as i32,
-                    )
-                    .with_sub_rect(sub_rect_view.as_sub_rect())
-                    .with_area((remainder.width, sub_rect_view.height()));
-                    new_options.mask = options.mask;
+            let mut result = bytes;
+            Unsized(blit.clone()).blit_u8(&mut result, dst_size, &options);
+            assert_eq!(result, expected, "{options:?}");
+        }
+    }
 
-                    self.blit_slice(dst, dst_size, &new_options);
-                }
+    #[test]
+    fn blit_with() {
+        let source = (0..16)
+            .map(|i| if i % 3 == 0 { 0 } else { 0xFF_00_00_00 | i })
+            .collect::<Vec<_>>();
+        let blit = BlitBuffer::from_buffer(&source, 4, 127);
+        let dst_size = Size::new(6, 6);
 
-                if remainder.height > 0 {
-                    // Render the single leftover remainder
-                    let mut new_options = BlitOptions::new_position(
-                        options.x + (tiles.width * sub_rect_view.width()) as i32,
-                        options.y + (tiles.height * sub_rect_view.height()) as i32,
-                    )
-                    .with_sub_rect(sub_rect_view.as_sub_rect())
-                    .with_area(remainder);
-                    new_options.mask = options.mask;
+        let remap = |color: u32| (color + 1) & 0x7F_FF_FF_FF;
+        let mut remapped = blit.clone();
+        remapped
+            .pixels_mut()
+            .iter_mut()
+            .filter(|pixel| **pixel >> 24 > 0)
+            .for_each(|pixel| *pixel = remap(*pixel));
+
+        for options in [
+            BlitOptions::new_position(1, 1),
+            BlitOptions::new_position(-1, 0).with_blend(BlendMode::Alpha),
+            BlitOptions::new_position(0, 0)
+                .with_area((6, 5))
+                .with_flip_vertical(true),
+            BlitOptions::new_position(1, 0)
+                .with_opacity(0.5)
+                .with_rotation(Rotation::Clockwise90),
+            BlitOptions::new_position(0, 1)
+                .with_alpha_threshold(0x80)
+                .with_scale(ScaleMode::Stretch)
+                .with_area((5, 3)),
+        ] {
+            let mut expected = [0xFF_10_20_30; 36];
+            remapped.blit(&mut expected, dst_size, &options);
+
+            let mut result = [0xFF_10_20_30; 36];
+            blit.blit_with(&mut result, dst_size, &options, remap);
+            assert_eq!(result, expected, "{options:?}");
+        }
+    }
+
+    #[test]
+    fn stencil() {
+        let source = (0..16)
+            .map(|i| if i % 3 == 0 { 0 } else { 0xFF_00_00_00 | i })
+            .collect::<Vec<_>>();
+        let blit = BlitBuffer::from_buffer(&source, 4, 127);
+        let stencil = BlitBuffer::from_buffer(
+            &(0..20)
+                .map(|i| if i % 2 == 0 { 0xFF_00_00_00 } else { 0 })
+                .collect::<Vec<_>>(),
+            5,
+            127,
+        );
+        let dst_size = Size::new(6, 6);
 
-                    self.blit_slice(dst, dst_size, &new_options);
+        for (options, stencil_position) in [
+            (BlitOptions::new_position(1, 1), (0, 0)),
+            (BlitOptions::new_position(-1, 0).with_area((7, 7)), (2, 3)),
+            (
+                BlitOptions::new_position(0, 0).with_mask((1, 1, 2, 5)),
+                (-1, -1),
+            ),
+        ] {
+            let mut expected = [0xFF_10_20_30; 36];
+            let mut drawn = expected;
+            blit.blit(&mut drawn, dst_size, &options);
+            for (index, pixel) in drawn.iter().enumerate() {
+                let (x, y) = (
+                    (index % 6) as i32 - stencil_position.0,
+                    (index / 6) as i32 - stencil_position.1,
+                );
+                if x >= 0 && y >= 0 && x < 5 && y < 4 && stencil.pixels()[(y * 5 + x) as usize] != 0
+                {
+                    expected[index] = *pixel;
                 }
             }
+
+            let mut result = [0xFF_10_20_30; 36];
+            blit.blit_with_stencil(&mut result, dst_size, &options, &stencil, stencil_position);
+            assert_eq!(result, expected, "{options:?}");
         }
     }
 
-    /// Blit a horizontal strip.
-    fn blit_horizontal(&self, dst: &mut [u32], dst_index: Range<usize>, blit_index: Range<usize>) {
-        // Same size iterators over both our buffer and the output buffer
-        let blit_iter = self.data[blit_index].iter();
-        let dst_iter = dst[dst_index].iter_mut();
+    #[test]
+    fn rect_ops() {
+        let rect = SubRect::new(-1, 2, (3, 2));
 
-        // Blit each pixel
-        dst_iter.zip(blit_iter).for_each(|(dst_pixel, blit_pixel)| {
-            *dst_pixel = Self::blit_pixel(*dst_pixel, *blit_pixel);
-        });
+        assert!(rect.contains_point((-1, 3)));
+        assert!(!rect.contains_point((2, 3)));
+        assert!(rect.contains_rect((0, 2, 2, 2)));
+        assert!(!rect.contains_rect((0, 1, 2, 2)));
+
+        assert!(rect.overlaps((1, 3, 5, 5)));
+        assert!(!rect.overlaps((2, 2, 1, 1)));
+        assert!(!rect.overlaps((0, 2, 0, 1)));
+        assert_eq!(rect.intersection(rect), Some(rect));
+        assert_eq!(rect.intersection((0, 0, 9, 0)), None);
+
+        assert_eq!(rect.union((0, 0, 0, 9)), rect);
+        assert_eq!(SubRect::new(5, 5, (0, 0)).union(rect), rect);
     }
 
-    /// Blit a single pixel.
-    ///
-    /// The main logic of calculating the resulting color that needs to be drawn.
-    #[inline(always)]
-    fn blit_pixel(dst_pixel: Color, blit_pixel: Color) -> Color {
-        // Set the pixel from the blit image if the mask value is set
-        if (blit_pixel >> 24) > 0 {
-            // Pixel from the blit buffer is not masked, use it
-            blit_pixel
-        } else {
-            // Pixel from the blit buffer is masked, use the original color
-            dst_pixel
+    #[test]
+    fn checked_math() {
+        let size = Size::new(4, 2);
+        assert_eq!(size.checked_sub((3, 3)), None);
+        assert_eq!(size.checked_sub((3, 2)), Some(Size::new(1, 0)));
+        assert_eq!(size.checked_div((2, 0)), None);
+        assert_eq!(size.checked_rem((3, 2)), Some(Size::new(1, 0)));
+        assert_eq!(size.checked_add((u32::MAX, 0)), None);
+        assert_eq!(size.checked_mul((2, 3)), Some(Size::new(8, 6)));
+        assert_eq!(size.saturating_mul((u32::MAX, 1)), Size::new(u32::MAX, 2));
+        assert_eq!(size.saturating_add((u32::MAX, 1)), Size::new(u32::MAX, 3));
+
+        let coord = Coordinate::new(i32::MIN, 5);
+        assert_eq!(coord.checked_sub((1, 0)), None);
+        assert_eq!(
+            coord.checked_add((1, -5)),
+            Some(Coordinate::new(i32::MIN + 1, 0))
+        );
+        assert_eq!(coord.saturating_sub((1, 0)), coord);
+        assert_eq!(
+            coord.saturating_add((0, i32::MAX)),
+            Coordinate::new(i32::MIN, i32::MAX)
+        );
+
+        // Degenerate tiling options don't overflow
+        let blit = BlitBuffer::from_buffer(&[0xFF_00_00_01; 4], 2, 127);
+        for remainder in [RemainderMode::TrailingClip, RemainderMode::Centered] {
+            let mut buffer = [0; 16];
+            blit.blit(
+                &mut buffer,
+                Size::new(4, 4),
+                &BlitOptions::new()
+                    .with_area((4, 4))
+                    .with_remainder(remainder)
+                    .with_tile_spacing((u32::MAX, 1))
+                    .with_tile_margin((0, u32::MAX)),
+            );
+            assert_eq!(buffer, [0; 16]);
         }
     }
-}
 
-impl Blit for BlitBuffer {
-    fn blit(&self, dst: &mut [u32], dst_size: Size, options: &BlitOptions) {
-        // TODO: remove this clone
-        let mut options = options.clone();
-
-        // Clip if any of the dimensions are negative
-        if options.x.is_negative() || options.y.is_negative() {
-            // Get the position with the negative dimensions clipped
-            let (new_x, new_y) = (options.x.max(0), options.y.max(0));
-            // Value is not 0 when any of the dimensions is clipped
-            let (diff_x, diff_y) = ((new_x - options.x) as u32, (new_y - options.y) as u32);
-
-            // Use the existing sub rectangle or create one
-            let mut sub_rect = options
-                .sub_rect
-                .unwrap_or_else(|| SubRect::from_size(self.size()));
-
-            if diff_x > sub_rect.size.width || diff_y > sub_rect.size.height {
-                // Nothing to render, image is fully clipped
-                return;
-            }
+    #[test]
+    fn region() {
+        let region = [(0, 0, 4, 4), (2, 2, 4, 4), (1, 1, 2, 2), (5, 0, 1, 1)]
+            .into_iter()
+            .collect::<geom::ClipRegion>();
 
-            // Offset the sub rectangle with the clipped area
-            sub_rect.x += diff_x as i32;
-            sub_rect.y += diff_y as i32;
-            sub_rect.size.width -= diff_x;
-            sub_rect.size.height -= diff_y;
+        // Every pixel of the union is in exactly one rectangle
+        let mut coverage = [0; 49];
+        for rect in region.rects() {
+            for y in rect.y..rect.bottom() {
+                for x in rect.x..rect.right() {
+                    coverage[(y * 7 + x) as usize] += 1;
+                }
+            }
+        }
+        for (index, count) in coverage.iter().enumerate() {
+            let (x, y) = ((index % 7) as i32, (index / 7) as i32);
+            let inside = (x < 4 && y < 4)
+                || ((2..6).contains(&x) && (2..6).contains(&y))
+                || (x, y) == (5, 0);
+            assert_eq!(*count, inside as i32, "{x}, {y}");
+            assert_eq!(region.contains((x, y)), inside, "{x}, {y}");
+        }
 
-            options.set_sub_rect(sub_rect);
-            options.x = new_x;
-            options.y = new_y;
+        let holed = region.clone().without_rect((1, 1, 4, 2));
+        for rect in holed.rects() {
+            assert!(rect.right() <= 1 || rect.x >= 5 || rect.bottom() <= 1 || rect.y >= 3);
         }
+        assert!(holed.contains((0, 0)) && !holed.contains((2, 2)) && holed.contains((2, 3)));
 
-        // Get the total area we need to draw the slices in
-        let area = options.area(self.size);
+        // Blending translucent pixels only once
+        let blit = BlitBuffer::from_buffer_with_alpha(&[0x80_FF_FF_FF; 49], 7);
+        let options = BlitOptions::new().with_blend(BlendMode::Alpha);
+        let mut result = [0xFF_00_00_00; 49];
+        blit.blit_region(&mut result, Size::new(7, 7), &options, &region);
 
-        // Which slices do we need to draw if any
-        let slice_projections = self.slice_projections(&options, area);
+        let mut expected = [0xFF_00_00_00; 49];
+        blit.blit(&mut expected, Size::new(7, 7), &options);
+        for (index, count) in coverage.iter().enumerate() {
+            if *count == 0 {
+                expected[index] = 0xFF_00_00_00;
+            }
+        }
+        assert_eq!(result, expected);
+    }
 
-        if slice_projections.is_empty() {
-            // Render without projections
-            self.blit_slice(dst, dst_size, &options);
-        } else {
-            // Loop over each slice
-            slice_projections.into_iter().for_each(|(source, target)| {
-                let mut slice_options = options
-                    .clone()
-                    // Move the position to which part of the slice we need to draw
-                    .with_position(options.x + target.x, options.y + target.y)
-                    .with_area(target.size);
+    #[test]
+    fn excluding() {
+        let source = (0..16)
+            .map(|i| if i % 3 == 0 { 0 } else { 0xFF_00_00_00 | i })
+            .collect::<Vec<_>>();
+        let blit = BlitBuffer::from_buffer(&source, 4, 127);
+        let dst_size = Size::new(6, 6);
 
-                // Move the already existing subrectangle if applicable
-                slice_options.set_sub_rect(if let Some(sub_rect) = options.sub_rect {
-                    sub_rect.shift(source.x, source.y)
-                } else {
-                    source
-                });
+        for (options, exclude) in [
+            (BlitOptions::new_position(1, 1), SubRect::new(2, 2, (2, 1))),
+            (
+                BlitOptions::new_position(0, 0)
+                    .with_area((6, 6))
+                    .with_mask((1, 0, 4, 6)),
+                SubRect::new(-1, 3, (9, 2)),
+            ),
+        ] {
+            let mut expected = [0xFF_10_20_30; 36];
+            blit.blit(&mut expected, dst_size, &options);
+            for y in exclude.y.max(0)..exclude.bottom().min(6) {
+                for x in exclude.x.max(0)..exclude.right().min(6) {
+                    expected[(y * 6 + x) as usize] = 0xFF_10_20_30;
+                }
+            }
 
-                self.blit_slice(dst, dst_size, &slice_options)
-            });
+            let mut result = [0xFF_10_20_30; 36];
+            blit.blit_excluding(&mut result, dst_size, &options, exclude);
+            assert_eq!(result, expected, "{options:?}");
         }
     }
-}
 
-impl std::fmt::Debug for BlitBuffer {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        f.debug_struct("BlitBuffer")
-            .field("width", &self.size.width)
-            .field("height", &self.size.height)
-            .finish()
-    }
-}
+    #[test]
+    fn self_blit() {
+        let pixels = (1..=12).map(|i| 0xFF_00_00_00 | i).collect::<Vec<_>>();
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+        // Scroll up by one row
+        let mut blit = BlitBuffer::from_buffer(&pixels, 3, 127);
+        blit.self_blit((0, 1, 3, 3), (0, 0));
+        assert_eq!(
+            blit.pixels()
+                .iter()
+                .map(|pixel| pixel & 0xFF)
+                .collect::<Vec<_>>(),
+            [4, 5, 6, 7, 8, 9, 10, 11, 12, 10, 11, 12]
+        );
 
-    #[test]
-    fn exact_fit() {
-        let mut buffer = [0xFF, 0xFF_00, 0xFF_00_00, 0xFF, 0xFF_00, 0xFF_00_00];
+        // Scroll down by one row
+        let mut blit = BlitBuffer::from_buffer(&pixels, 3, 127);
+        blit.self_blit((0, 0, 3, 3), (0, 1));
+        assert_eq!(
+            blit.pixels()
+                .iter()
+                .map(|pixel| pixel & 0xFF)
+                .collect::<Vec<_>>(),
+            [1, 2, 3, 1, 2, 3, 4, 5, 6, 7, 8, 9]
+        );
 
-        // The last number should be masked
+        // Overlapping horizontally, the destination is clipped on the right
+        let mut blit = BlitBuffer::from_buffer(&pixels, 3, 127);
+        blit.self_blit((0, 0, 3, 4), (1, 0));
+        assert_eq!(
+            blit.pixels()
+                .iter()
+                .map(|pixel| pixel & 0xFF)
+                .collect::<Vec<_>>(),
+            [1, 1, 2, 4, 4, 5, 7, 7, 8, 10, 10, 11]
+        );
+
+        // Source partially outside of the buffer
+        let mut blit = BlitBuffer::from_buffer(&pixels, 3, 127);
+        blit.self_blit((-1, -1, 2, 2), (1, 1));
+        assert_eq!(
+            blit.pixels()
+                .iter()
+                .map(|pixel| pixel & 0xFF)
+                .collect::<Vec<_>>(),
+            [1, 2, 3, 4, 5, 6, 7, 8, 1, 10, 11, 12]
+        );
+
+        // Transparent pixels are copied instead of blended
+        let mut transparent = pixels.clone();
+        transparent[1] = 0;
+        let mut blit = BlitBuffer::from_buffer(&transparent, 3, 127);
+        blit.self_blit((0, 0, 3, 2), (1, 1));
+        assert_eq!(
+            blit.pixels(),
+            [
+                transparent[..4].to_vec(),
+                vec![0xFF_00_00_01, 0],
+                transparent[6..7].to_vec(),
+                vec![0xFF_00_00_04, 0xFF_00_00_05],
+                transparent[9..].to_vec()
+            ]
+            .concat()
+        );
+        assert_eq!(blit.opaque_runs, [0..1, 2..3, 3..5, 6..9, 9..12]);
+    }
+
+    #[test]
+    fn remainder() {
         let blit = BlitBuffer::from_buffer(
-            &[
-                0xFF_00_00_AA,
-                0xFF_00_AA_00,
-                0xFF_AA_00_00,
-                0xBB,
-                0xBB,
-                0xBB,
-            ],
-            2,
+            &[0xFF_00_00_01, 0xFF_00_00_02, 0xFF_00_00_03, 0xFF_00_00_04],
+            4,
             127,
         );
+
+        let draw = |remainder| {
+            let mut buffer = [0; 12];
+            blit.blit(
+                &mut buffer,
+                Size::new(12, 1),
+                &BlitOptions::new_position(1, 0)
+                    .with_area((10, 1))
+                    .with_remainder(remainder),
+            );
+
+            buffer.map(|pixel| pixel & 0xFF)
+        };
+
+        assert_eq!(
+            draw(RemainderMode::TrailingClip),
+            [0, 1, 2, 3, 4, 1, 2, 3, 4, 1, 2, 0]
+        );
+        assert_eq!(
+            draw(RemainderMode::Centered),
+            [0, 4, 1, 2, 3, 4, 1, 2, 3, 4, 1, 0]
+        );
+        assert_eq!(
+            draw(RemainderMode::None),
+            [0, 1, 2, 3, 4, 1, 2, 3, 4, 0, 0, 0]
+        );
+
+        // The mask is applied to all tiles
+        let mut buffer = [0; 12];
         blit.blit(
             &mut buffer,
-            Size::new(2, 3),
-            &BlitOptions::new_position(0, 0),
+            Size::new(12, 1),
+            &BlitOptions::new_position(1, 0)
+                .with_area((10, 1))
+                .with_remainder(RemainderMode::Centered)
+                .with_mask((2, 0, 8, 1)),
         );
-
-        // Create a copy but cast the u32 to a i32
-        let expected = [
-            0xAA | 0xFF_00_00_00,
-            0xAA_00 | 0xFF_00_00_00,
-            0xAA_00_00 | 0xFF_00_00_00,
-            0xFF,
-            0xFF_00,
-            0xFF_00_00,
-        ];
         assert_eq!(
-            buffer, expected,
-            "\nResult:\n{:08x?}\nExpected:\n{:08x?}",
-            &buffer, &expected
+            buffer.map(|pixel| pixel & 0xFF),
+            [0, 0, 1, 2, 3, 4, 1, 2, 3, 4, 0, 0]
         );
     }
 }
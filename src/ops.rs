@@ -0,0 +1,136 @@
+//! Color adjustments for pixels packed as `0xAARRGGBB`.
+//!
+//! Every adjustment is available for a single color, which can be applied while drawing with [`BlitBuffer::blit_with`], and for a slice of pixels, which can be used on [`BlitBuffer::modify_pixels`] or on a whole destination buffer.
+//! The alpha channel is never changed.
+//!
+//! # Example
+//!
+//! ```rust
+//! use blit::{ops, BlitBuffer, BlitOptions, geom::Size};
+//!
+//! let sprite = BlitBuffer::from_buffer(&[0xFF_80_80_80], 1, 127);
+//! let mut buffer = [0xFF_40_40_40; 2];
+//!
+//! // Draw the sprite brighter
+//! sprite.blit_with(&mut buffer, Size::new(2, 1), &BlitOptions::new(), |color| {
+//!     ops::brightness(color, 0.25)
+//! });
+//! assert_eq!(buffer[0], 0xFF_C0_C0_C0);
+//!
+//! // Make the whole scene darker for the night
+//! ops::adjust_brightness(&mut buffer, -0.25);
+//! assert_eq!(buffer, [0xFF_80_80_80, 0xFF_00_00_00]);
+//! ```
+//!
+//! [`BlitBuffer::blit_with`]: crate::BlitBuffer::blit_with
+//! [`BlitBuffer::modify_pixels`]: crate::BlitBuffer::modify_pixels
+
+/// Make a color brighter or darker by adding the amount to every color channel.
+///
+/// The amount is in the range `-1.0..=1.0`, where `1.0` makes the color white and `-1.0` makes the color black.
+pub fn brightness(color: u32, amount: f32) -> u32 {
+    let offset = amount.clamp(-1.0, 1.0) * 255.0;
+
+    map_channels(color, |channel| channel + offset)
+}
+
+/// Make the difference between light and dark colors bigger or smaller by scaling every color channel from the middle gray.
+///
+/// A factor of `1.0` keeps the color, `0.0` makes it gray and higher factors increase the contrast.
+pub fn contrast(color: u32, factor: f32) -> u32 {
+    let factor = factor.max(0.0);
+
+    map_channels(color, |channel| (channel - 128.0) * factor + 128.0)
+}
+
+/// Rotate the hue of a color by an amount of degrees, keeping the saturation and value.
+///
+/// Positive degrees move red towards green and green towards blue.
+pub fn hue(color: u32, degrees: f32) -> u32 {
+    let [red, green, blue] = [16, 8, 0].map(|shift| ((color >> shift) & 0xFF) as f32 / 255.0);
+
+    // Convert to HSV
+    let max = red.max(green).max(blue);
+    let min = red.min(green).min(blue);
+    let delta = max - min;
+    if delta == 0.0 {
+        // Grays have no hue
+        return color;
+    }
+    let hue = if max == red {
+        60.0 * ((green - blue) / delta)
+    } else if max == green {
+        60.0 * ((blue - red) / delta + 2.0)
+    } else {
+        60.0 * ((red - green) / delta + 4.0)
+    };
+    let (saturation, value) = (delta / max, max);
+
+    // Convert the rotated hue back to RGB
+    let hue = (hue + degrees).rem_euclid(360.0);
+    let channel = |offset: f32| {
+        let k = (offset + hue / 60.0) % 6.0;
+
+        value - value * saturation * k.min(4.0 - k).clamp(0.0, 1.0)
+    };
+    let [red, green, blue] = [channel(5.0), channel(3.0), channel(1.0)]
+        .map(|channel| (channel * 255.0).round().clamp(0.0, 255.0) as u32);
+
+    (color & 0xFF_00_00_00) | (red << 16) | (green << 8) | blue
+}
+
+/// Apply [`brightness`] to every pixel.
+pub fn adjust_brightness(pixels: &mut [u32], amount: f32) {
+    pixels
+        .iter_mut()
+        .for_each(|pixel| *pixel = brightness(*pixel, amount));
+}
+
+/// Apply [`contrast`] to every pixel.
+pub fn adjust_contrast(pixels: &mut [u32], factor: f32) {
+    pixels
+        .iter_mut()
+        .for_each(|pixel| *pixel = contrast(*pixel, factor));
+}
+
+/// Apply [`hue`] to every pixel.
+pub fn hue_shift(pixels: &mut [u32], degrees: f32) {
+    pixels
+        .iter_mut()
+        .for_each(|pixel| *pixel = hue(*pixel, degrees));
+}
+
+/// Change the red, green and blue channels in the range `0.0..=255.0`, the results are clamped to the range.
+fn map_channels(color: u32, map: impl Fn(f32) -> f32) -> u32 {
+    let channel = |shift: u32| {
+        let channel = map(((color >> shift) & 0xFF) as f32);
+
+        (channel.round().clamp(0.0, 255.0) as u32) << shift
+    };
+
+    (color & 0xFF_00_00_00) | channel(16) | channel(8) | channel(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn adjust() {
+        assert_eq!(brightness(0x80_10_80_F0, 0.5), 0x80_90_FF_FF);
+        assert_eq!(brightness(0x80_10_80_F0, -1.0), 0x80_00_00_00);
+
+        assert_eq!(contrast(0xFF_40_80_C0, 2.0), 0xFF_00_80_FF);
+        assert_eq!(contrast(0xFF_40_80_C0, 0.0), 0xFF_80_80_80);
+        assert_eq!(contrast(0xFF_40_80_C0, 1.0), 0xFF_40_80_C0);
+
+        assert_eq!(hue(0xFF_FF_00_00, 120.0), 0xFF_00_FF_00);
+        assert_eq!(hue(0xFF_FF_00_00, -120.0), 0xFF_00_00_FF);
+        assert_eq!(hue(0x20_80_40_40, 360.0), 0x20_80_40_40);
+        assert_eq!(hue(0xFF_80_80_80, 90.0), 0xFF_80_80_80);
+
+        let mut pixels = [0xFF_FF_00_00, 0xFF_00_FF_00];
+        hue_shift(&mut pixels, 120.0);
+        assert_eq!(pixels, [0xFF_00_FF_00, 0xFF_00_00_FF]);
+    }
+}
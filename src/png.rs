@@ -0,0 +1,162 @@
+//! Load PNG images into buffers without depending on the `image` crate.
+//!
+//! Every PNG color type and bit depth is supported, the pixels are converted to 8-bit `0xAARRGGBB` pixels.
+//! Only the first frame of animated PNGs is loaded.
+//!
+//! # Example
+//!
+//! ```rust
+//! let bytes = include_bytes!("../examples/showcase/smiley_rgb.png");
+//!
+//! let blit = blit::png::from_bytes_with_mask_color(bytes, 0xFF_00_FF).unwrap();
+//! assert_eq!(blit.pixels()[0], 0);
+//! ```
+
+use std::{
+    fs::File,
+    io::{BufRead, BufReader, Cursor, Seek},
+    path::Path,
+};
+
+use ::png::{ColorType, Decoder, DecodingError, Transformations};
+
+use crate::{rgba_pixels, BlitBuffer, ColorKey};
+
+/// Load a PNG from memory, pixels with an alpha below the treshold become transparent, see [`BlitBuffer::from_buffer`].
+pub fn from_bytes_with_alpha(
+    bytes: &[u8],
+    alpha_treshold: u8,
+) -> Result<BlitBuffer, DecodingError> {
+    let (pixels, width) = decode(Cursor::new(bytes))?;
+
+    Ok(BlitBuffer::from_buffer(&pixels, width, alpha_treshold))
+}
+
+/// Load a PNG from memory, ignoring the alpha channel and making a single color transparent.
+pub fn from_bytes_with_mask_color(
+    bytes: &[u8],
+    mask_color: u32,
+) -> Result<BlitBuffer, DecodingError> {
+    from_bytes_with_color_key(bytes, &ColorKey::new(mask_color))
+}
+
+/// Load a PNG from memory, ignoring the alpha channel and making the colors matching the key transparent.
+pub fn from_bytes_with_color_key(
+    bytes: &[u8],
+    key: &ColorKey,
+) -> Result<BlitBuffer, DecodingError> {
+    let (pixels, width) = decode(Cursor::new(bytes))?;
+
+    Ok(BlitBuffer::from_buffer_with_color_key(&pixels, width, key))
+}
+
+/// Load a PNG file, pixels with an alpha below the treshold become transparent, see [`BlitBuffer::from_buffer`].
+pub fn open_with_alpha<P>(path: P, alpha_treshold: u8) -> Result<BlitBuffer, DecodingError>
+where
+    P: AsRef<Path>,
+{
+    let (pixels, width) = decode(BufReader::new(File::open(path)?))?;
+
+    Ok(BlitBuffer::from_buffer(&pixels, width, alpha_treshold))
+}
+
+/// Load a PNG file, ignoring the alpha channel and making a single color transparent.
+pub fn open_with_mask_color<P>(path: P, mask_color: u32) -> Result<BlitBuffer, DecodingError>
+where
+    P: AsRef<Path>,
+{
+    open_with_color_key(path, &ColorKey::new(mask_color))
+}
+
+/// Load a PNG file, ignoring the alpha channel and making the colors matching the key transparent.
+pub fn open_with_color_key<P>(path: P, key: &ColorKey) -> Result<BlitBuffer, DecodingError>
+where
+    P: AsRef<Path>,
+{
+    let (pixels, width) = decode(BufReader::new(File::open(path)?))?;
+
+    Ok(BlitBuffer::from_buffer_with_color_key(&pixels, width, key))
+}
+
+/// Decode the first frame into `0xAARRGGBB` pixels, returning the pixels and the width.
+fn decode<R>(reader: R) -> Result<(Vec<u32>, u32), DecodingError>
+where
+    R: BufRead + Seek,
+{
+    let mut decoder = Decoder::new(reader);
+    // Expand palettes and low bit depths, strip 16-bit channels and always add an alpha channel
+    decoder.set_transformations(Transformations::normalize_to_color8() | Transformations::ALPHA);
+
+    let mut reader = decoder.read_info()?;
+    let mut bytes = vec![0; reader.output_buffer_size().unwrap_or_default()];
+    let info = reader.next_frame(&mut bytes)?;
+    let bytes = &bytes[..info.buffer_size()];
+
+    let pixels = match info.color_type {
+        ColorType::GrayscaleAlpha => bytes
+            .chunks_exact(2)
+            .map(|bytes| u32::from_be_bytes([bytes[1], bytes[0], bytes[0], bytes[0]]))
+            .collect(),
+        _ => rgba_pixels(bytes).collect(),
+    };
+
+    Ok((pixels, info.width))
+}
+
+#[cfg(test)]
+mod tests {
+    use ::png::{BitDepth, Encoder};
+
+    use super::*;
+
+    /// Encode pixels as a PNG.
+    fn encode(width: u32, color_type: ColorType, bit_depth: BitDepth, data: &[u8]) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        let mut encoder = Encoder::new(&mut bytes, width, 1);
+        encoder.set_color(color_type);
+        encoder.set_depth(bit_depth);
+        encoder
+            .write_header()
+            .unwrap()
+            .write_image_data(data)
+            .unwrap();
+
+        bytes
+    }
+
+    #[test]
+    fn load() {
+        let rgba = encode(
+            2,
+            ColorType::Rgba,
+            BitDepth::Eight,
+            &[0x11, 0x22, 0x33, 0xFF, 0x44, 0x55, 0x66, 0x40],
+        );
+        assert_eq!(
+            from_bytes_with_alpha(&rgba, 0x80).unwrap().pixels(),
+            [0xFF_11_22_33, 0]
+        );
+
+        let rgb = encode(
+            2,
+            ColorType::Rgb,
+            BitDepth::Eight,
+            &[0xFF, 0, 0xFF, 1, 2, 3],
+        );
+        assert_eq!(
+            from_bytes_with_mask_color(&rgb, 0xFF_00_FF)
+                .unwrap()
+                .pixels(),
+            [0, 0xFF_01_02_03]
+        );
+
+        // 16-bit grayscale is expanded and stripped to 8 bits
+        let gray = encode(1, ColorType::Grayscale, BitDepth::Sixteen, &[0x80, 0x01]);
+        assert_eq!(
+            from_bytes_with_alpha(&gray, 0xFF).unwrap().pixels(),
+            [0xFF_80_80_80]
+        );
+
+        assert!(from_bytes_with_alpha(&rgb[1..], 0x80).is_err());
+    }
+}
@@ -0,0 +1,294 @@
+//! Slow but straightforward implementation of blitting, serving as the specification of what every option does.
+//!
+//! This is only available with the `reference` feature and should never be used for actual rendering.
+//! It's meant for verifying the output of the optimized implementation in tests:
+//!
+//! ```rust
+//! use blit::{reference, Blit, BlitBuffer, BlitOptions, geom::Size};
+//!
+//! let blit = BlitBuffer::from_buffer(&[0xFF_FF_FF_FF, 0, 0, 0xFF_FF_FF_FF], 2, 127);
+//! let options = BlitOptions::new_position(-1, 1).with_area((5, 3));
+//!
+//! let mut optimized = [0; 16];
+//! blit.blit(&mut optimized, Size::new(4, 4), &options);
+//!
+//! let mut expected = [0; 16];
+//! reference::blit_reference(&blit, &mut expected, Size::new(4, 4), &options);
+//!
+//! assert_eq!(optimized, expected);
+//! ```
+//!
+//! # Semantics
+//!
+//! Every pixel of the destination is handled separately:
+//!
+//! 1. Pixels outside of [`BlitOptions::mask`] are skipped.
+//! 2. Pixels outside of the area at [`BlitOptions::x`] and [`BlitOptions::y`] with size [`BlitOptions::area`] are skipped.
+//! 3. The position in the area is mirrored when [`BlitOptions::flip_horizontal`] or [`BlitOptions::flip_vertical`] is set.
+//!    Then it's rotated back with [`BlitOptions::rotation`], for quarter turns the width and height of the area are swapped.
+//! 4. When a slice is set the area is divided into sections with [`crate::slice::Slice`], dividing the source rectangle [`BlitOptions::sub_rect`] or the full source when it's not set.
+//!    An axis without a slice is a single section spanning the full source rectangle and area.
+//!    A section is stretched instead of tiled when its slice has [`SliceFill::Stretch`] and it isn't tiled along the other axis, see [`SliceFill`].
+//!    Without slices the full area is a single section with [`BlitOptions::sub_rect`] as the source rectangle.
+//! 5. When tiling the source rectangle of the section is shrunk to the size of the section area when it's bigger, it's always shrunk to the part overlapping the source buffer.
+//! 6. The source rectangle is tiled over the section area with the leftover space handled with [`BlitOptions::remainder`], leaving [`BlitOptions::tile_spacing`] empty between the tiles and [`BlitOptions::tile_margin`] empty at the edges, and the position in the tile scrolled by [`BlitOptions::uv_offset`] wrapping around the tile, or stretched over it when [`BlitOptions::scale`] is [`crate::ScaleMode::Stretch`].
+//!    When stretching with [`crate::Filter::Bilinear`] the 2x2 nearest pixels are interpolated with their weights multiplied by their alpha.
+//! 7. When [`BlitOptions::alpha_threshold`] is set the source pixel becomes fully transparent when its alpha is below it or zero, and fully opaque otherwise.
+//! 8. The alpha of the source pixel is multiplied with [`BlitOptions::opacity`].
+//! 9. The source pixel is combined with the destination pixel with [`BlitOptions::blend`], the destination pixel is converted from and back to [`BlitOptions::channel_order`] for this.
+
+use crate::{
+    filter::{self, Sample},
+    geom::SubRect,
+    slice::{Slice, SliceFill, SliceProjection},
+    BlendMode, BlitBuffer, BlitOptions, Color, Filter, RemainderMode, Rotation, ScaleMode, Size,
+};
+
+/// Blit the source by looping over every destination pixel and mapping it back to the source.
+///
+/// Every pixel is checked against all bounds explicitly, no views or ranges are used.
+/// See the [module documentation](self) for the semantics.
+pub fn blit_reference(source: &BlitBuffer, dst: &mut [u32], dst_size: Size, options: &BlitOptions) {
+    let area = options.area(source.size());
+    let options = &*options.anchored(area);
+
+    for dst_y in 0..dst_size.height as i32 {
+        for dst_x in 0..dst_size.width as i32 {
+            if let Some(mask) = options.mask {
+                if !contains(mask, dst_x, dst_y) {
+                    continue;
+                }
+            }
+
+            // Position relative to the area
+            let (x, y) = (dst_x - options.x, dst_y - options.y);
+            if x < 0 || y < 0 || x >= area.width as i32 || y >= area.height as i32 {
+                continue;
+            }
+
+            // Mirror the position in the area
+            let x = if options.flip_horizontal {
+                area.width as i32 - x - 1
+            } else {
+                x
+            };
+            let y = if options.flip_vertical {
+                area.height as i32 - y - 1
+            } else {
+                y
+            };
+
+            // Rotate the position back to the orientation of the source
+            let (width, height) = (area.width as i32, area.height as i32);
+            let (x, y, unrotated_area) = match options.rotation {
+                Rotation::None => (x, y, area),
+                Rotation::Clockwise90 => (y, width - x - 1, Size::new(height, width)),
+                Rotation::Clockwise180 => (width - x - 1, height - y - 1, area),
+                Rotation::Clockwise270 => (height - y - 1, x, Size::new(height, width)),
+            };
+
+            let Some(mut pixel) = source_pixel(source, options, unrotated_area, x as u32, y as u32)
+            else {
+                continue;
+            };
+            if let Some(alpha_threshold) = options.alpha_threshold_bits() {
+                pixel = BlitBuffer::apply_alpha_threshold(pixel, alpha_threshold);
+            }
+
+            let dst_pixel = &mut dst[dst_y as usize * dst_size.width as usize + dst_x as usize];
+            let unpacked = options.channel_order.unpack(*dst_pixel);
+            *dst_pixel = options.channel_order.pack(match options.opacity_alpha() {
+                0xFF => options.blend.blend(unpacked, pixel),
+                opacity => options
+                    .blend
+                    .faded()
+                    .blend(unpacked, BlendMode::fade(pixel, opacity)),
+            });
+        }
+    }
+}
+
+/// Map a position relative to the area to a pixel of the source buffer.
+fn source_pixel(
+    source: &BlitBuffer,
+    options: &BlitOptions,
+    area: Size,
+    x: u32,
+    y: u32,
+) -> Option<Color> {
+    // Find the section of the area the position is in and how it's scaled
+    let (section, section_area, x, y, scale) = if options.vertical_slice.is_none()
+        && options.horizontal_slice.is_none()
+    {
+        (options.sub_rect(source.size()), area, x, y, options.scale)
+    } else {
+        let sub_rect = options
+            .sub_rect
+            .unwrap_or_else(|| SubRect::from_size(source.size()));
+
+        let horizontal = find_projection(options.vertical_slice, sub_rect.width(), area.width, x)?;
+        let vertical =
+            find_projection(options.horizontal_slice, sub_rect.height(), area.height, y)?;
+
+        let section = SubRect::new(
+            sub_rect.x + horizontal.source_start as i32,
+            sub_rect.y + vertical.source_start as i32,
+            Size::new(horizontal.source_amount(), vertical.source_amount()),
+        );
+        let section_area = Size::new(horizontal.target_amount(), vertical.target_amount());
+        let scale = match SliceFill::combine(horizontal.fill(), vertical.fill()) {
+            SliceFill::Stretch => ScaleMode::Stretch,
+            SliceFill::Tile => options.scale,
+        };
+
+        (
+            section,
+            section_area,
+            x - horizontal.target_start,
+            y - vertical.target_start,
+            scale,
+        )
+    };
+
+    // When tiling the source rectangle can never be bigger than the area
+    let (width, height) = match scale {
+        ScaleMode::Tile => (
+            section.width().min(section_area.width),
+            section.height().min(section_area.height),
+        ),
+        ScaleMode::Stretch => (section.width(), section.height()),
+    };
+
+    // Only the part inside the source can be used
+    let left = section.x.max(0);
+    let top = section.y.max(0);
+    let right = (section.x + width as i32).min(source.width() as i32);
+    let bottom = (section.y + height as i32).min(source.height() as i32);
+    if right <= left || bottom <= top {
+        return None;
+    }
+    let (width, height) = ((right - left) as u32, (bottom - top) as u32);
+    let pixel = |src_x: usize, src_y: usize| {
+        source.pixels()[(top as usize + src_y) * source.width() as usize + left as usize + src_x]
+    };
+
+    // Interpolate between the nearest pixels of the stretched source rectangle
+    if scale == ScaleMode::Stretch && options.filter == Filter::Bilinear {
+        let sample_x = Sample::new(x, section_area.width, width);
+        let sample_y = Sample::new(y, section_area.height, height);
+
+        return Some(filter::bilinear(
+            [
+                pixel(sample_x.first, sample_y.first),
+                pixel(sample_x.second, sample_y.first),
+                pixel(sample_x.first, sample_y.second),
+                pixel(sample_x.second, sample_y.second),
+            ],
+            sample_x.weight,
+            sample_y.weight,
+        ));
+    }
+
+    let (src_x, src_y) = match scale {
+        // Tile the source rectangle over the section area, scrolling the source inside of every tile
+        ScaleMode::Tile => {
+            let (spacing, margin) = options.tile_gaps();
+            let tile_x = Tile {
+                remainder: options.remainder,
+                area_length: section_area.width,
+                tile_length: width,
+                spacing: spacing.width,
+                margin: margin.width,
+            };
+            let tile_y = Tile {
+                remainder: options.remainder,
+                area_length: section_area.height,
+                tile_length: height,
+                spacing: spacing.height,
+                margin: margin.height,
+            };
+
+            (
+                (tile_x.position(x)? as i32 + options.uv_offset.x).rem_euclid(width as i32) as u32,
+                (tile_y.position(y)? as i32 + options.uv_offset.y).rem_euclid(height as i32) as u32,
+            )
+        }
+        // Stretch the source rectangle over the section area
+        ScaleMode::Stretch => (
+            (x as u64 * width as u64 / section_area.width as u64) as u32,
+            (y as u64 * height as u64 / section_area.height as u64) as u32,
+        ),
+    };
+
+    Some(pixel(src_x as usize, src_y as usize))
+}
+
+/// Find the projection of a slice containing the target position.
+fn find_projection(
+    slice: Option<Slice>,
+    source_length: u32,
+    target_length: u32,
+    position: u32,
+) -> Option<SliceProjection> {
+    match slice {
+        Some(slice) => slice
+            .divide_area_iter(source_length, target_length)
+            .find(|projection| {
+                position >= projection.target_start && position < projection.target_end
+            }),
+        // Without a slice the whole axis is a single section
+        None => Some(SliceProjection::new(0, source_length, 0, target_length)),
+    }
+}
+
+/// Single dimension of a tile repeated over an area.
+struct Tile {
+    /// How the leftover space is handled.
+    remainder: RemainderMode,
+    /// Length of the area.
+    area_length: u32,
+    /// Length of the tile.
+    tile_length: u32,
+    /// Empty space between the tiles.
+    spacing: u32,
+    /// Empty space between the edges of the area and the tiles.
+    margin: u32,
+}
+
+impl Tile {
+    /// Map a position in the area to a position in the tile, `None` when it's in the empty space.
+    fn position(&self, position: u32) -> Option<u32> {
+        let inner_length = self
+            .area_length
+            .saturating_sub(self.margin.saturating_mul(2));
+        let position = position.checked_sub(self.margin)?;
+        if position >= inner_length {
+            return None;
+        }
+
+        // Tiles repeat with the spacing after them, starting at the first whole tile
+        let period = self.tile_length as i64 + self.spacing as i64;
+        let start =
+            self.remainder
+                .first_whole_tile(inner_length, self.tile_length, self.spacing) as i64;
+        let tile = (position as i64 - start).div_euclid(period);
+        let in_tile = (position as i64 - start).rem_euclid(period) as u32;
+        if in_tile >= self.tile_length {
+            return None;
+        }
+
+        // Only whole tiles are drawn
+        if self.remainder == RemainderMode::None
+            && (tile < 0 || (tile + 1) * period - self.spacing as i64 > inner_length as i64)
+        {
+            return None;
+        }
+
+        Some(in_tile)
+    }
+}
+
+/// Whether the point is inside of the rectangle.
+fn contains(rect: SubRect, x: i32, y: i32) -> bool {
+    x >= rect.x && y >= rect.y && x < rect.right() && y < rect.bottom()
+}
@@ -0,0 +1,205 @@
+//! Run-length encoded buffers for sprites with a lot of transparent pixels.
+//!
+//! # Example
+//!
+//! ```rust
+//! use blit::{rle::RleBlitBuffer, Blit, BlitBuffer, BlitOptions, geom::Size};
+//!
+//! let buffer = BlitBuffer::from_buffer(&[0, 0xFF_FF_FF_FF, 0xFF_FF_FF_FF, 0], 4, 127);
+//! let rle = RleBlitBuffer::from(buffer);
+//!
+//! // Only the single run of opaque pixels is copied
+//! let mut canvas = [0; 4];
+//! rle.blit(&mut canvas, Size::new(4, 1), &BlitOptions::new());
+//! assert_eq!(canvas, [0, 0xFF_FF_FF_FF, 0xFF_FF_FF_FF, 0]);
+//! ```
+
+use std::ops::Range;
+
+use crate::{BlendMode, Blit, BlitBuffer, BlitOptions, ChannelOrder, Size};
+
+/// A [`BlitBuffer`] with the non-transparent pixels of every row stored as runs.
+///
+/// Drawing copies the runs as whole slices and skips the transparent spans without checking every pixel, which is a lot faster for sprites that are mostly transparent.
+/// Options that can't be drawn with runs, such as tiling, slicing, scaling, flipping and rotating, are drawn by the wrapped buffer.
+#[derive(Debug, Clone)]
+pub struct RleBlitBuffer {
+    /// Buffer the runs are created from, its opaque runs are the runs that are drawn.
+    buffer: BlitBuffer,
+    /// Index of the first run of every row, with an extra index for the end of the last row.
+    row_starts: Vec<usize>,
+}
+
+impl RleBlitBuffer {
+    /// Encode the non-transparent pixels of the buffer as runs.
+    ///
+    /// The opaque runs the buffer already found are reused, only the start of every row is looked up.
+    pub fn new(mut buffer: BlitBuffer) -> Self {
+        // The runs are only missing after the pixels were changed with `BlitBuffer::pixels_mut`, pixels outside of the runs are never drawn
+        if buffer.opaque_runs.is_empty() {
+            buffer.update_opaque_runs();
+        }
+        let row_starts = BlitBuffer::find_row_runs(&buffer.opaque_runs, buffer.size());
+
+        Self { buffer, row_starts }
+    }
+
+    /// The wrapped buffer.
+    pub fn buffer(&self) -> &BlitBuffer {
+        &self.buffer
+    }
+
+    /// Size of the buffer in pixels.
+    pub fn size(&self) -> Size {
+        self.buffer.size()
+    }
+
+    /// Amount of runs of non-transparent pixels in all rows.
+    pub fn runs(&self) -> usize {
+        self.buffer.opaque_runs.len()
+    }
+
+    /// Runs of non-transparent pixels of a single row, as indices of the pixels of the whole buffer.
+    fn row_runs(&self, y: usize) -> &[Range<usize>] {
+        &self.buffer.opaque_runs[self.row_starts[y]..self.row_starts[y + 1]]
+    }
+}
+
+impl From<BlitBuffer> for RleBlitBuffer {
+    fn from(buffer: BlitBuffer) -> Self {
+        Self::new(buffer)
+    }
+}
+
+impl Blit for RleBlitBuffer {
+    fn blit(&self, dst: &mut [u32], dst_size: Size, options: &BlitOptions) {
+        let size = self.buffer.size();
+
+        // Only exact blits can be drawn with runs
+        if !options.is_exact(size) {
+            self.buffer.blit(dst, dst_size, options);
+
+            return;
+        }
+
+        if options.opacity_alpha() == 0 {
+            return;
+        }
+
+        let Some((dst_area, src_start, src_y)) = options.exact_placement(size, dst_size) else {
+            return;
+        };
+        let src_end = src_start + dst_area.width() as usize;
+
+        // Runs can be copied directly when nothing needs to be blended or converted
+        let copy = options.blend == BlendMode::Mask
+            && options.opacity_alpha() == 0xFF
+            && options.alpha_threshold.is_none()
+            && options.channel_order == ChannelOrder::Argb;
+
+        let width = size.width as usize;
+        dst_area
+            .parent_ranges_iter(dst_size)
+            .zip(src_y..)
+            .for_each(|(dst_range, y)| {
+                let row_start = y * width;
+                let row = &self.buffer.data[row_start..row_start + width];
+
+                for run in self.row_runs(y) {
+                    // Only the part of the run inside the visible source
+                    let (start, end) = (
+                        (run.start - row_start).max(src_start),
+                        (run.end - row_start).min(src_end),
+                    );
+                    if start >= end {
+                        continue;
+                    }
+
+                    let dst_start = dst_range.start + start - src_start;
+                    let dst_run = &mut dst[dst_start..dst_start + end - start];
+                    if copy {
+                        dst_run.copy_from_slice(&row[start..end]);
+                    } else {
+                        BlitBuffer::blit_pixels(
+                            dst_run.iter_mut(),
+                            row[start..end].iter().copied(),
+                            options,
+                        );
+                    }
+                }
+            });
+    }
+
+    fn source_size(&self) -> Option<Size> {
+        Some(self.buffer.size())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn runs() {
+        let rle = RleBlitBuffer::from(BlitBuffer::from_buffer(
+            &[
+                0,
+                0xFF_00_00_01,
+                0xFF_00_00_02,
+                0,
+                0xFF_00_00_03,
+                0xFF_00_00_04,
+                0,
+                0xFF_00_00_05,
+                0,
+                0,
+            ],
+            5,
+            127,
+        ));
+
+        assert_eq!(rle.row_runs(0), [1..3, 4..5]);
+        assert_eq!(rle.row_runs(1), [5..6, 7..8]);
+        assert_eq!(rle.runs(), 4);
+
+        // Runs cleared by changing the pixels are found again
+        let mut changed = rle.buffer().clone();
+        changed.pixels_mut()[0] = 0xFF_00_00_06;
+        let rle = RleBlitBuffer::from(changed);
+        assert_eq!(rle.row_runs(0), [0..3, 4..5]);
+        let mut canvas = [0; 10];
+        rle.blit(&mut canvas, Size::new(5, 2), &BlitOptions::new());
+        assert_eq!(canvas, rle.buffer().pixels());
+    }
+
+    #[test]
+    fn same_as_buffer() {
+        let source = (0..36)
+            .map(|i| if i % 7 < 3 { 0 } else { 0x80_00_00_00 | i })
+            .collect::<Vec<_>>();
+        let buffer = BlitBuffer::from_buffer_with_alpha(&source, 6);
+        let rle = RleBlitBuffer::from(buffer.clone());
+        let dst_size = Size::new(10, 10);
+
+        for (x, y) in [(2, 2), (-3, 1), (7, -2), (12, 0)] {
+            for options in [
+                BlitOptions::new_position(x, y),
+                BlitOptions::new_position(x, y).with_mask((1, 2, 6, 5)),
+                BlitOptions::new_position(x, y).with_sub_rect((1, 2, 4, 3)),
+                BlitOptions::new_position(x, y).with_blend(BlendMode::Alpha),
+                BlitOptions::new_position(x, y).with_opacity(0.5),
+                BlitOptions::new_position(x, y).with_area((9, 8)),
+                BlitOptions::new_position(x, y).with_flip_horizontal(true),
+                BlitOptions::new_position(x, y).with_channel_order(ChannelOrder::Bgra),
+            ] {
+                let mut expected = [0xFF_10_20_30; 100];
+                buffer.blit(&mut expected, dst_size, &options);
+
+                let mut result = [0xFF_10_20_30; 100];
+                rle.blit(&mut result, dst_size, &options);
+
+                assert_eq!(result, expected, "{options:?}");
+            }
+        }
+    }
+}
@@ -0,0 +1,79 @@
+//! Render a destination image one row at a time.
+//!
+//! This is useful for hardware that consumes a single row of pixels at a time and can't hold a full image in memory, such as LED strips.
+//!
+//! # Example
+//!
+//! ```rust
+//! use blit::{scanline, Blit, BlitBuffer, BlitOptions, geom::Size};
+//!
+//! let sprite = BlitBuffer::from_buffer(&[0xFF_FF_FF_FF; 4], 2, 127);
+//! let scene: [(&dyn Blit, BlitOptions); 2] = [
+//!     (&sprite, BlitOptions::new_position(0, 0)),
+//!     (&sprite, BlitOptions::new_position(3, 1)),
+//! ];
+//!
+//! let dst_size = Size::new(5, 3);
+//! let mut row = [0; 5];
+//! for y in 0..dst_size.height {
+//!     row.fill(0);
+//!     scanline::render_row(&scene, &mut row, dst_size, y);
+//!
+//!     // Send the row to the hardware
+//! }
+//! ```
+
+use crate::{Blit, BlitOptions, Size};
+
+/// Draw all sources on a single row `y` of a virtual destination image with size `dst_size`.
+///
+/// The sources are drawn in order, so the last source will be on top.
+/// Only the pixels of the row are drawn to `dst_row`, which must be `dst_size.width` long.
+pub fn render_row(
+    sources: &[(&dyn Blit, BlitOptions)],
+    dst_row: &mut [u32],
+    dst_size: Size,
+    y: u32,
+) {
+    sources
+        .iter()
+        .for_each(|(source, options)| source.blit_row(dst_row, dst_size, options, y));
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::BlitBuffer;
+
+    use super::*;
+
+    #[test]
+    fn rows_match_full_frame() {
+        let source = (0..16)
+            .map(|i| if i % 3 == 0 { 0 } else { 0xFF_00_00_00 | i })
+            .collect::<Vec<_>>();
+        let sprite = BlitBuffer::from_buffer(&source, 4, 127);
+        let dst_size = Size::new(12, 10);
+
+        let scene: [(&dyn Blit, BlitOptions); 4] = [
+            (&sprite, BlitOptions::new_position(-1, -2)),
+            (&sprite, BlitOptions::new_position(3, 2).with_area((7, 6))),
+            (
+                &sprite,
+                BlitOptions::new_position(5, 4).with_mask((0, 5, 12, 2)),
+            ),
+            (&sprite, BlitOptions::new_position(10, 8)),
+        ];
+
+        let mut expected = vec![0; dst_size.pixels()];
+        scene
+            .iter()
+            .for_each(|(source, options)| source.blit(&mut expected, dst_size, options));
+
+        let mut rows = vec![0; dst_size.pixels()];
+        rows.chunks_exact_mut(dst_size.width as usize)
+            .enumerate()
+            .for_each(|(y, row)| render_row(&scene, row, dst_size, y as u32));
+
+        assert_eq!(rows, expected);
+    }
+}
@@ -0,0 +1,88 @@
+//! Hot loops processing multiple pixels at a time when the target supports it.
+//!
+//! Pixels that don't fill a whole vector, and all pixels on targets without vector instructions, are handled by the scalar version.
+
+use crate::{BlendMode, Color};
+
+/// Copy the source pixels that are not fully transparent over the destination pixels, the same as blending every pixel with [`BlendMode::Mask`].
+#[inline(always)]
+pub(crate) fn mask(dst: &mut [Color], src: &[Color]) {
+    #[cfg(all(target_arch = "wasm32", target_feature = "simd128"))]
+    let (dst, src) = simd128::mask(dst, src);
+
+    dst.iter_mut().zip(src).for_each(|(dst_pixel, src_pixel)| {
+        *dst_pixel = BlendMode::Mask.blend(*dst_pixel, *src_pixel);
+    });
+}
+
+/// WebAssembly 128-bit vectors, only available when compiled with `-C target-feature=+simd128`.
+#[cfg(all(target_arch = "wasm32", target_feature = "simd128"))]
+mod simd128 {
+    use std::arch::wasm32::{
+        u32x4_ne, u32x4_splat, v128, v128_and, v128_bitselect, v128_load, v128_store,
+    };
+
+    use crate::Color;
+
+    /// Mask four pixels at a time, returning the pixels that are left.
+    #[inline(always)]
+    pub(super) fn mask<'a, 'b>(
+        dst: &'a mut [Color],
+        src: &'b [Color],
+    ) -> (&'a mut [Color], &'b [Color]) {
+        let length = dst.len().min(src.len());
+        let (dst, dst_rest) = dst.split_at_mut(length - length % 4);
+        let (src, src_rest) = src.split_at(dst.len());
+
+        let (alpha, zero) = (u32x4_splat(0xFF_00_00_00), u32x4_splat(0));
+        dst.chunks_exact_mut(4)
+            .zip(src.chunks_exact(4))
+            .for_each(|(dst, src)| {
+                // SAFETY: both chunks are exactly four pixels, 16 bytes, and the loads and stores don't need to be aligned
+                let (src_pixels, dst_pixels) = unsafe {
+                    (
+                        v128_load(src.as_ptr().cast::<v128>()),
+                        v128_load(dst.as_ptr().cast::<v128>()),
+                    )
+                };
+
+                // All bits are set for the source pixels that are not fully transparent
+                let visible = u32x4_ne(v128_and(src_pixels, alpha), zero);
+                let result = v128_bitselect(src_pixels, dst_pixels, visible);
+
+                // SAFETY: the same 16 bytes of the destination chunk that were loaded
+                unsafe { v128_store(dst.as_mut_ptr().cast::<v128>(), result) };
+            });
+
+        (dst_rest, src_rest)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn mask_pixels() {
+        let src = (0..11)
+            .map(|i| {
+                if i % 3 == 0 {
+                    0x00_FF_FF_FF
+                } else {
+                    i << 24 | i
+                }
+            })
+            .collect::<Vec<_>>();
+
+        for length in 0..src.len() {
+            let mut dst = vec![0xFF_00_00_FF; length];
+            mask(&mut dst, &src[..length]);
+
+            let expected = src[..length]
+                .iter()
+                .map(|pixel| BlendMode::Mask.blend(0xFF_00_00_FF, *pixel))
+                .collect::<Vec<_>>();
+            assert_eq!(dst, expected);
+        }
+    }
+}
@@ -23,25 +23,53 @@ use serde::{Deserialize, Serialize};
 use crate::{Size, SubRect};
 
 /// Divide the source buffer into multiple sections and repeat the chosen section to fill the area.
+///
+/// New kinds of slices and new settings can be added without a breaking change, so a slice must be created with one of the constructors such as [`Slice::ternary`].
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[non_exhaustive]
 pub enum Slice {
     /// Split the buffer into two and repeat one of the sections.
+    #[non_exhaustive]
     Binary {
         /// Position between the first and last section to split.
+        ///
+        /// It's always counted from the start of the buffer, also when the first section is repeated the last section keeps its length of the buffer length minus the split.
         split: u32,
         /// Which of the sections to scale when the area is bigger than the total size.
         repeat: BinarySection,
+        /// How the repeated section fills the area.
+        #[cfg_attr(feature = "serde", serde(default))]
+        fill: SliceFill,
     },
 
     /// Split the buffer into three and repeat the middle section.
     ///
     /// If you want to resize either the left or right part a binary section is used.
+    #[non_exhaustive]
     Ternary {
         /// Position between the first and the middle section.
         split_first: u32,
         /// Position between the middle and last section.
         split_last: u32,
+        /// How the middle section fills the area.
+        #[cfg_attr(feature = "serde", serde(default))]
+        fill: SliceFill,
+    },
+
+    /// Split the buffer into three by the lengths of both ends and repeat the middle section.
+    ///
+    /// This is the same as [`Slice::Ternary`] but the last split is counted from the end of the buffer, so it doesn't depend on the size of the buffer.
+    /// When the ends together are longer than the buffer they are clamped like reversed splits of [`Slice::Ternary`].
+    #[non_exhaustive]
+    Ends {
+        /// Length of the first section.
+        first: u32,
+        /// Length of the last section.
+        last: u32,
+        /// How the middle section fills the area.
+        #[cfg_attr(feature = "serde", serde(default))]
+        fill: SliceFill,
     },
 }
 
@@ -50,6 +78,18 @@ impl Slice {
     ///
     /// When horizontal this is the top section.
     /// When vertical this is the left section.
+    ///
+    /// The split is counted from the start of the buffer, the last section keeps its size:
+    ///
+    /// ```rust
+    /// # use blit::{Blit, BlitBuffer, BlitOptions, geom::Size, slice::Slice};
+    /// let blit = BlitBuffer::from_buffer(&[0xFF_00_00_01, 0xFF_00_00_02, 0xFF_00_00_03], 3, 127);
+    /// let mut buffer = [0; 5];
+    ///
+    /// let options = BlitOptions::new().with_area((5, 1)).with_vertical_slice(Slice::binary_first(1));
+    /// blit.blit(&mut buffer, Size::new(5, 1), &options);
+    /// assert_eq!(buffer.map(|pixel| pixel & 0xFF), [1, 1, 1, 2, 3]);
+    /// ```
     pub fn binary_first<S>(split: S) -> Self
     where
         S: ToPrimitive,
@@ -59,6 +99,7 @@ impl Slice {
         Self::Binary {
             split,
             repeat: BinarySection::First,
+            fill: SliceFill::Tile,
         }
     }
 
@@ -75,6 +116,7 @@ impl Slice {
         Self::Binary {
             split,
             repeat: BinarySection::Last,
+            fill: SliceFill::Tile,
         }
     }
 
@@ -92,60 +134,170 @@ impl Slice {
         Self::Ternary {
             split_first,
             split_last,
+            fill: SliceFill::Tile,
+        }
+    }
+
+    /// Create a split with the lengths of the first and last section where the middle section is scaled.
+    ///
+    /// When horizontal these are the top and bottom sections.
+    /// When vertical these are the left and right sections.
+    pub fn ends<S1, S2>(first: S1, last: S2) -> Self
+    where
+        S1: ToPrimitive,
+        S2: ToPrimitive,
+    {
+        let first = first.to_u32().unwrap_or_default();
+        let last = last.to_u32().unwrap_or_default();
+
+        Self::Ends {
+            first,
+            last,
+            fill: SliceFill::Tile,
+        }
+    }
+
+    /// Set how the repeated section fills the area.
+    ///
+    /// ```rust
+    /// # use blit::{Blit, BlitBuffer, BlitOptions, geom::Size, slice::{Slice, SliceFill}};
+    /// let blit = BlitBuffer::from_buffer(&[0xFF_00_00_01, 0xFF_00_00_02, 0xFF_00_00_03, 0xFF_00_00_04], 4, 127);
+    /// let mut buffer = [0; 6];
+    ///
+    /// // The middle is stretched instead of repeated
+    /// let slice = Slice::ternary(1, 3).with_fill(SliceFill::Stretch);
+    /// blit.blit(&mut buffer, Size::new(6, 1), &BlitOptions::new().with_area((6, 1)).with_vertical_slice(slice));
+    /// assert_eq!(buffer.map(|pixel| pixel & 0xFF), [1, 2, 2, 3, 3, 4]);
+    /// ```
+    #[must_use]
+    pub fn with_fill(mut self, fill: SliceFill) -> Self {
+        match &mut self {
+            Slice::Binary { fill: current, .. }
+            | Slice::Ternary { fill: current, .. }
+            | Slice::Ends { fill: current, .. } => {
+                *current = fill;
+            }
+        }
+
+        self
+    }
+
+    /// How the repeated section fills the area.
+    pub fn fill(&self) -> SliceFill {
+        match *self {
+            Slice::Binary { fill, .. } | Slice::Ternary { fill, .. } | Slice::Ends { fill, .. } => {
+                fill
+            }
         }
     }
 
     /// Divide the given single dimensional area by the slice ranges.
+    ///
+    /// Every projection returned has a non-empty source and target range, the target ranges are sorted and exactly cover the target.
+    /// When the repeated section is empty but has to fill a part of the target there's nothing to repeat, so the whole source is repeated over the target like there's no slice.
     pub(crate) fn divide_area_iter(
         &self,
         source_length: u32,
         target_length: u32,
     ) -> impl Iterator<Item = SliceProjection> {
-        match self {
-            Slice::Binary { split, repeat } => {
+        let stretch = self.fill() == SliceFill::Stretch;
+
+        let mut sections = match *self {
+            Slice::Binary { split, repeat, .. } => {
+                // A split outside of the source is the same as a split on the edge
+                let split = split.min(source_length);
+
                 // Find the middle intersection depending on which part needs to scale
                 let middle = match repeat {
-                    BinarySection::First => target_length.saturating_sub(*split),
-                    BinarySection::Last => *split,
+                    BinarySection::First => target_length.saturating_sub(source_length - split),
+                    BinarySection::Last => split.min(target_length),
                 };
 
                 [
-                    (0, middle, 0, *split),
-                    (middle, target_length, *split, source_length),
-                    // The (0, 0) pair will be removed by the filter, we have to add this otherwise the compiler will complain about the iterators not being the same size
-                    (0, 0, 0, 0),
+                    (0, middle, 0, split, repeat == BinarySection::First),
+                    (
+                        middle,
+                        target_length,
+                        split,
+                        source_length,
+                        repeat == BinarySection::Last,
+                    ),
+                    // The (0, 0) pair will be removed by the filter, we have to add this otherwise the arrays are not the same size
+                    (0, 0, 0, 0, false),
                 ]
-                .into_iter()
             }
             Slice::Ternary {
                 split_first,
                 split_last,
-            } => {
-                // Find the two middle intersections depending on which part needs to scale
-                let (middle_first, middle_second) = (
-                    *split_first,
-                    target_length.saturating_sub(source_length.saturating_sub(*split_last)),
-                );
+                ..
+            } => Self::ternary_sections(split_first, split_last, source_length, target_length),
+            Slice::Ends { first, last, .. } => Self::ternary_sections(
+                first,
+                source_length.saturating_sub(last),
+                source_length,
+                target_length,
+            ),
+        };
+
+        // An empty repeated section can't fill its part of the target
+        if sections
+            .iter()
+            .any(|(target_start, target_end, source_start, source_end, _)| {
+                target_start < target_end && source_start == source_end
+            })
+        {
+            sections = [
+                (0, target_length, 0, source_length, true),
+                (0, 0, 0, 0, false),
+                (0, 0, 0, 0, false),
+            ];
+        }
 
-                // Ensure they don't go out of bounds
-                let middle_first = middle_first.min(middle_second);
-                let middle_second = middle_second.clamp(middle_first, target_length);
+        sections
+            .into_iter()
+            // Remove empty ranges
+            .filter(|(target_start, target_end, source_start, source_end, _)| {
+                target_start < target_end && source_start < source_end
+            })
+            .map(
+                move |(target_start, target_end, source_start, source_end, repeated)| {
+                    SliceProjection {
+                        stretch: repeated && stretch,
+                        ..SliceProjection::new(source_start, source_end, target_start, target_end)
+                    }
+                },
+            )
+    }
 
-                [
-                    (0, middle_first, 0, *split_first),
-                    (middle_first, middle_second, *split_first, *split_last),
-                    (middle_second, target_length, *split_last, source_length),
-                ]
-                .into_iter()
-            }
-        }
-        // Remove empty ranges
-        .filter(|(target_start, target_end, source_start, source_end)| {
-            target_start < target_end && source_start < source_end
-        })
-        .map(|(target_start, target_end, source_start, source_end)| {
-            SliceProjection::new(source_start, source_end, target_start, target_end)
-        })
+    /// Divide the area into the `(target_start, target_end, source_start, source_end, repeated)` sections of a ternary split.
+    fn ternary_sections(
+        split_first: u32,
+        split_last: u32,
+        source_length: u32,
+        target_length: u32,
+    ) -> [(u32, u32, u32, u32, bool); 3] {
+        // Normalize reversed splits and splits outside of the source
+        let (split_first, split_last) = (
+            split_first.min(split_last).min(source_length),
+            split_first.max(split_last).min(source_length),
+        );
+
+        // Find the two middle intersections depending on which part needs to scale
+        let middle_second = target_length.saturating_sub(source_length - split_last);
+        // Ensure they don't overlap when the target is smaller than the source
+        let middle_first = split_first.min(middle_second);
+
+        [
+            (0, middle_first, 0, split_first, false),
+            (middle_first, middle_second, split_first, split_last, true),
+            (
+                middle_second,
+                target_length,
+                split_last,
+                source_length,
+                false,
+            ),
+        ]
     }
 }
 
@@ -165,6 +317,31 @@ pub enum BinarySection {
     Last,
 }
 
+/// How the repeated section of a slice fills the area.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum SliceFill {
+    /// Repeat the section, the leftover space is handled with [`crate::BlitOptions::remainder`].
+    #[default]
+    Tile,
+    /// Stretch the section over the area, useful for gradients.
+    ///
+    /// A section is only stretched when it isn't tiled along the other axis, so with both slices set the center is only stretched when both slices stretch it.
+    /// When [`crate::BlitOptions::scale`] is [`crate::ScaleMode::Stretch`] all sections are stretched.
+    Stretch,
+}
+
+impl SliceFill {
+    /// Combine the fills of both axes of a section, only stretching when no axis needs tiling.
+    pub(crate) fn combine(horizontal: Option<SliceFill>, vertical: Option<SliceFill>) -> Self {
+        match (horizontal, vertical) {
+            (Some(SliceFill::Tile), _) | (_, Some(SliceFill::Tile)) => SliceFill::Tile,
+            (Some(SliceFill::Stretch), _) | (_, Some(SliceFill::Stretch)) => SliceFill::Stretch,
+            (None, None) => SliceFill::Tile,
+        }
+    }
+}
+
 /// How a slice must be rendered.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub(crate) struct SliceProjection {
@@ -176,6 +353,8 @@ pub(crate) struct SliceProjection {
     pub target_start: u32,
     /// Right part of the relative range in the destination buffer to draw into.
     pub target_end: u32,
+    /// Whether the range is stretched instead of tiled.
+    pub stretch: bool,
 }
 
 impl SliceProjection {
@@ -186,6 +365,34 @@ impl SliceProjection {
             source_end,
             target_start,
             target_end,
+            stretch: false,
+        }
+    }
+
+    /// Divide a single dimensional area by the slice ranges, without a slice the whole area is a single projection.
+    pub fn divide_axis(
+        slice: Option<Slice>,
+        source_length: u32,
+        target_length: u32,
+    ) -> impl Iterator<Item = SliceProjection> {
+        slice
+            .into_iter()
+            .flat_map(move |slice| slice.divide_area_iter(source_length, target_length))
+            .chain(
+                slice
+                    .is_none()
+                    .then(|| Self::new(0, source_length, 0, target_length)),
+            )
+    }
+
+    /// How the range is filled, `None` when tiling and stretching look the same because the source and target have the same length.
+    pub fn fill(&self) -> Option<SliceFill> {
+        if self.stretch {
+            Some(SliceFill::Stretch)
+        } else if self.source_amount() == self.target_amount() {
+            None
+        } else {
+            Some(SliceFill::Tile)
         }
     }
 
@@ -199,22 +406,6 @@ impl SliceProjection {
         self.target_end - self.target_start
     }
 
-    /// Create a `(source, target)` rectangle tuple with a static Y axis.
-    pub fn into_sub_rects_static_y(self, y_size: u32) -> (SubRect, SubRect) {
-        let source = SubRect::from((self.source_start, 0, self.source_amount(), y_size));
-        let target = SubRect::from((self.target_start, 0, self.target_amount(), y_size));
-
-        (source, target)
-    }
-
-    /// Create a `(source, target)` rectangle tuple with a static X axis.
-    pub fn into_sub_rects_static_x(self, x_size: u32) -> (SubRect, SubRect) {
-        let source = SubRect::from((0, self.source_start, x_size, self.source_amount()));
-        let target = SubRect::from((0, self.target_start, x_size, self.target_amount()));
-
-        (source, target)
-    }
-
     /// Create a `(source, target)` rectangle tuple from horizontal and vertical projections.
     pub fn combine_into_sub_rects(
         horizontal: &SliceProjection,
@@ -248,6 +439,11 @@ mod tests {
             .divide_area_iter(30, 100)
             .collect::<Vec<_>>();
         let vertical_projs = vertical_slice.divide_area_iter(75, 150).collect::<Vec<_>>();
+
+        // The last split of the ends is counted from the end of the source
+        assert!(Slice::ends(10, 10)
+            .divide_area_iter(30, 100)
+            .eq(horizontal_slice.divide_area_iter(30, 100)));
         assert_eq!(
             horizontal_projs,
             [
@@ -280,30 +476,218 @@ mod tests {
             ((20, 50, 10, 25).into(), (90, 125, 10, 25).into())
         );
 
+        // An axis without a slice is a single projection
+        let unsliced_y = SliceProjection::divide_axis(None, 25, 25).collect::<Vec<_>>();
+        assert_eq!(unsliced_y, [SliceProjection::new(0, 25, 0, 25)]);
+        let unsliced_y = &unsliced_y[0];
+        let unsliced_x = &SliceProjection::new(0, 10, 0, 10);
+        assert!(SliceProjection::divide_axis(Some(vertical_slice), 75, 150)
+            .eq(vertical_slice.divide_area_iter(75, 150)));
+
         assert_eq!(
-            horizontal_projs[0].clone().into_sub_rects_static_y(25),
+            SliceProjection::combine_into_sub_rects(&horizontal_projs[0], unsliced_y),
             ((0, 0, 10, 25).into(), (0, 0, 10, 25).into())
         );
         assert_eq!(
-            horizontal_projs[1].clone().into_sub_rects_static_y(25),
+            SliceProjection::combine_into_sub_rects(&horizontal_projs[1], unsliced_y),
             ((10, 0, 10, 25).into(), (10, 0, 80, 25).into())
         );
         assert_eq!(
-            horizontal_projs[2].clone().into_sub_rects_static_y(25),
+            SliceProjection::combine_into_sub_rects(&horizontal_projs[2], unsliced_y),
             ((20, 0, 10, 25).into(), (90, 0, 10, 25).into())
         );
 
         assert_eq!(
-            vertical_projs[0].clone().into_sub_rects_static_x(10),
+            SliceProjection::combine_into_sub_rects(unsliced_x, &vertical_projs[0]),
             ((0, 0, 10, 25).into(), (0, 0, 10, 25).into())
         );
         assert_eq!(
-            vertical_projs[1].clone().into_sub_rects_static_x(10),
+            SliceProjection::combine_into_sub_rects(unsliced_x, &vertical_projs[1]),
             ((0, 25, 10, 25).into(), (0, 25, 10, 100).into())
         );
         assert_eq!(
-            vertical_projs[2].clone().into_sub_rects_static_x(10),
+            SliceProjection::combine_into_sub_rects(unsliced_x, &vertical_projs[2]),
             ((0, 50, 10, 25).into(), (0, 125, 10, 25).into())
         );
     }
+
+    #[test]
+    fn binary() {
+        // The last section keeps its size when the first section is repeated
+        assert_eq!(
+            Slice::binary_first(10)
+                .divide_area_iter(30, 100)
+                .collect::<Vec<_>>(),
+            [
+                SliceProjection::new(0, 10, 0, 80),
+                SliceProjection::new(10, 30, 80, 100)
+            ]
+        );
+        assert_eq!(
+            Slice::binary_last(10)
+                .divide_area_iter(30, 100)
+                .collect::<Vec<_>>(),
+            [
+                SliceProjection::new(0, 10, 0, 10),
+                SliceProjection::new(10, 30, 10, 100)
+            ]
+        );
+
+        // Only the repeated section is stretched
+        let stretched = Slice::binary_last(10)
+            .with_fill(SliceFill::Stretch)
+            .divide_area_iter(30, 100)
+            .map(|projection| projection.fill())
+            .collect::<Vec<_>>();
+        assert_eq!(stretched, [None, Some(SliceFill::Stretch)]);
+
+        // Before the split was counted from the end when the first section was repeated
+        assert_eq!(
+            Slice::binary_first(1)
+                .divide_area_iter(3, 5)
+                .collect::<Vec<_>>(),
+            [
+                SliceProjection::new(0, 1, 0, 3),
+                SliceProjection::new(1, 3, 3, 5)
+            ]
+        );
+        assert_eq!(
+            SliceFill::combine(Some(SliceFill::Stretch), Some(SliceFill::Tile)),
+            SliceFill::Tile
+        );
+    }
+
+    #[test]
+    fn ends() {
+        let divide = |slice: Slice, source_length, target_length| {
+            slice
+                .divide_area_iter(source_length, target_length)
+                .collect::<Vec<_>>()
+        };
+
+        // Ends longer than the target, the last end is kept and the first end is cut off
+        assert_eq!(
+            divide(Slice::ends(10, 10), 30, 15),
+            [
+                SliceProjection::new(0, 10, 0, 5),
+                SliceProjection::new(20, 30, 5, 15)
+            ]
+        );
+        assert_eq!(
+            divide(Slice::ends(10, 10), 30, 8),
+            [SliceProjection::new(20, 30, 0, 8)]
+        );
+
+        // Ends longer than the source overlap, the splits are swapped
+        assert_eq!(
+            divide(Slice::ends(20, 20), 30, 100),
+            divide(Slice::ternary(10, 20), 30, 100)
+        );
+
+        // An end longer than the source leaves nothing to repeat
+        assert_eq!(
+            divide(Slice::ends(40, 0), 30, 100),
+            [SliceProjection::new(0, 30, 0, 100)]
+        );
+        assert_eq!(
+            divide(Slice::ends(40, 0), 30, 20),
+            [SliceProjection::new(0, 30, 0, 20)]
+        );
+        assert_eq!(
+            divide(Slice::ends(0, u32::MAX), 30, 20),
+            [SliceProjection::new(0, 30, 0, 20)]
+        );
+
+        // Nothing to draw on
+        assert!(divide(Slice::ends(10, 10), 30, 0).is_empty());
+    }
+
+    #[test]
+    fn empty_repeated_section() {
+        // Nothing to repeat, so the whole source is repeated
+        for slice in [
+            Slice::binary_first(0),
+            Slice::binary_last(30),
+            Slice::ternary(10, 10),
+            Slice::ends(10, 20),
+        ] {
+            assert_eq!(
+                slice.divide_area_iter(30, 100).collect::<Vec<_>>(),
+                [SliceProjection::new(0, 30, 0, 100)],
+                "{slice:?}"
+            );
+        }
+
+        // When the target is too small to show the repeated section the sections are kept
+        assert_eq!(
+            Slice::ternary(10, 10)
+                .divide_area_iter(30, 20)
+                .collect::<Vec<_>>(),
+            [SliceProjection::new(10, 30, 0, 20)]
+        );
+    }
+
+    #[test]
+    fn extreme_inputs() {
+        // Simple xorshift so the test is deterministic without extra dependencies
+        let mut state = 0x2545_F491_u32;
+        let mut random = move || {
+            state ^= state << 13;
+            state ^= state >> 17;
+            state ^= state << 5;
+
+            // Bias towards the edge cases
+            match state % 8 {
+                0 => 0,
+                1 => u32::MAX,
+                2 => state % 4,
+                3 => state >> 1,
+                _ => state % 64,
+            }
+        };
+
+        for _ in 0..10_000 {
+            let (source_length, target_length) = (random(), random());
+            let slice = match random() % 4 {
+                0 => Slice::binary_first(random()),
+                1 => Slice::binary_last(random()),
+                2 => Slice::ends(random(), random()),
+                _ => Slice::ternary(random(), random()),
+            };
+
+            let mut previous_target_end = 0;
+            for projection in slice.divide_area_iter(source_length, target_length) {
+                assert!(
+                    projection.source_start < projection.source_end
+                        && projection.source_end <= source_length,
+                    "{slice:?} {source_length} {target_length}: {projection:?}"
+                );
+                assert!(
+                    projection.target_start < projection.target_end
+                        && projection.target_end <= target_length,
+                    "{slice:?} {source_length} {target_length}: {projection:?}"
+                );
+
+                // Target ranges follow each other without gaps or overlaps
+                assert_eq!(
+                    projection.target_start, previous_target_end,
+                    "{slice:?} {source_length} {target_length}: {projection:?}"
+                );
+                previous_target_end = projection.target_end;
+            }
+
+            // The target ranges exactly cover the target
+            if source_length > 0 {
+                assert_eq!(
+                    previous_target_end, target_length,
+                    "{slice:?} {source_length} {target_length}"
+                );
+            } else {
+                assert_eq!(
+                    slice.divide_area_iter(source_length, target_length).count(),
+                    0
+                );
+            }
+        }
+    }
 }
@@ -0,0 +1,109 @@
+//! Draw on the surface of the `softbuffer` crate.
+//!
+//! Every frame the surface needs to be resized to the window, its buffer cleared and presented, [`Frame`] does this and exposes the buffer as a [`Canvas`].
+
+use std::num::NonZeroU32;
+
+use raw_window_handle::{HasDisplayHandle, HasWindowHandle};
+use softbuffer::{Buffer, SoftBufferError, Surface};
+
+use crate::{canvas::Canvas, Size};
+
+/// Buffer of a surface for drawing a single frame.
+///
+/// Draw on it with [`Frame::canvas`] and show it in the window with [`Frame::present`].
+pub struct Frame<'a, D, W> {
+    /// Pixels of the surface.
+    buffer: Buffer<'a, D, W>,
+    /// Size the surface is resized to.
+    size: Size,
+}
+
+impl<'a, D, W> Frame<'a, D, W>
+where
+    D: HasDisplayHandle,
+    W: HasWindowHandle,
+{
+    /// Resize the surface and borrow its buffer cleared with a color.
+    ///
+    /// The width and height are at least a single pixel because surfaces can't be empty, minimized windows can report a size of zero.
+    pub fn new(
+        surface: &'a mut Surface<D, W>,
+        size: Size,
+        clear_color: u32,
+    ) -> Result<Self, SoftBufferError> {
+        let (width, height) = surface_size(size);
+        surface.resize(width, height)?;
+
+        let mut buffer = surface.buffer_mut()?;
+        buffer.fill(clear_color);
+
+        Ok(Self {
+            buffer,
+            size: Size::new(width.get(), height.get()),
+        })
+    }
+
+    /// Size of the buffer.
+    pub fn size(&self) -> Size {
+        self.size
+    }
+
+    /// Canvas to draw on the buffer.
+    pub fn canvas(&mut self) -> Canvas<'_> {
+        Canvas::from_parts(&mut self.buffer, self.size)
+    }
+
+    /// Show the buffer in the window.
+    ///
+    /// The alpha of every pixel is cleared first, the surface expects the highest bits to be zero.
+    pub fn present(mut self) -> Result<(), SoftBufferError> {
+        clear_alpha(&mut self.buffer);
+
+        self.buffer.present()
+    }
+}
+
+/// Size of the surface for a frame, surfaces can't be empty so both axes are at least a single pixel.
+fn surface_size(size: Size) -> (NonZeroU32, NonZeroU32) {
+    (
+        NonZeroU32::MIN.saturating_add(size.width.saturating_sub(1)),
+        NonZeroU32::MIN.saturating_add(size.height.saturating_sub(1)),
+    )
+}
+
+/// Convert drawn pixels to the `0RGB` format of the surface.
+fn clear_alpha(pixels: &mut [u32]) {
+    pixels.iter_mut().for_each(|pixel| *pixel &= 0x00_FF_FF_FF);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{BlendMode, BlitBuffer, BlitOptions};
+
+    #[test]
+    fn surface() {
+        // Minimized windows still get a single pixel
+        assert_eq!(
+            surface_size(Size::new(0, 0)),
+            (NonZeroU32::MIN, NonZeroU32::MIN)
+        );
+        let (width, height) = surface_size(Size::new(3, 2));
+        assert_eq!((width.get(), height.get()), (3, 2));
+
+        // Draw on the pixels like a frame does before presenting
+        let mut pixels = vec![0xFF_10_20_30; 6];
+        let mut canvas = Canvas::from_parts(&mut pixels, Size::new(3, 2));
+        let blit = BlitBuffer::from_buffer_with_alpha(&[0xFF_FF_00_00, 0x80_00_00_FF], 2);
+        canvas.blit(
+            &blit,
+            &BlitOptions::new_position(1, 1).with_blend(BlendMode::Alpha),
+        );
+        clear_alpha(&mut pixels);
+        assert!(pixels.iter().all(|pixel| pixel >> 24 == 0));
+        assert_eq!(pixels[..4], [0x10_20_30; 4]);
+        assert_eq!(pixels[4], 0xFF_00_00);
+        assert_ne!(pixels[5], 0x10_20_30);
+    }
+}
@@ -0,0 +1,672 @@
+//! Tile maps made with the [Tiled](https://www.mapeditor.org) editor, loaded from `.tmx` maps and `.tsx` tilesets.
+//!
+//! Only orthogonal maps with a fixed size are supported, with the layers stored in the CSV format which is the default of Tiled.
+//! Tilesets stored in a separate `.tsx` file only have their first global tile ID and path in the map, they are loaded with [`Tileset::from_tsx`] and put in the map with [`TiledMap::set_tileset`].
+//!
+//! # Example
+//!
+//! ```rust
+//! use blit::{tiled::TiledMap, BlitBuffer, BlitOptions, geom::Size};
+//!
+//! let map = TiledMap::from_tmx(
+//!     r#"<map orientation="orthogonal" width="3" height="1" tilewidth="1" tileheight="1">
+//!         <tileset firstgid="1" name="terrain" tilewidth="1" tileheight="1" tilecount="2" columns="2">
+//!             <image source="terrain.png" width="2" height="1"/>
+//!         </tileset>
+//!         <layer name="ground" width="3" height="1">
+//!             <data encoding="csv">2,0,2147483649</data>
+//!         </layer>
+//!     </map>"#,
+//! )
+//! .unwrap();
+//!
+//! // The pixels of every tileset are loaded separately, in the same order as the tilesets of the map
+//! let terrain = BlitBuffer::from_buffer(&[0xFF_00_00_01, 0xFF_00_00_02], 2, 127);
+//!
+//! let mut canvas = [0; 3];
+//! let ground = map.layer("ground").unwrap();
+//! map.blit_layer(ground, &[terrain], &mut canvas, Size::new(3, 1), &BlitOptions::new());
+//! assert_eq!(canvas, [0xFF_00_00_02, 0, 0xFF_00_00_01]);
+//! ```
+
+use quick_xml::DeError;
+use serde::{de::Error as _, Deserialize};
+
+use crate::{
+    geom::{Coordinate, SubRect},
+    Blit, BlitBuffer, BlitOptions, Rotation, Size,
+};
+
+/// Bit of a global tile ID set when the tile is mirrored around the vertical axis.
+const FLIP_HORIZONTAL: u32 = 1 << 31;
+/// Bit of a global tile ID set when the tile is mirrored around the horizontal axis.
+const FLIP_VERTICAL: u32 = 1 << 30;
+/// Bit of a global tile ID set when the tile is mirrored diagonally, swapping the x and y axis.
+const FLIP_DIAGONAL: u32 = 1 << 29;
+/// Bit of a global tile ID set when a hexagonal tile is rotated, ignored since only orthogonal maps are supported.
+const ROTATE_HEXAGONAL: u32 = 1 << 28;
+
+/// Tile map with the tilesets and the tile layers of a Tiled map.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TiledMap {
+    /// Amount of tiles horizontally and vertically.
+    size: Size,
+    /// Size of a single grid cell in pixels.
+    tile_size: Size,
+    /// Tilesets ordered by their first global tile ID.
+    tilesets: Vec<Tileset>,
+    /// Tile layers in the order they are drawn, layers in groups are included in the order of the group.
+    layers: Vec<TileLayer>,
+}
+
+impl TiledMap {
+    /// Read the tilesets and tile layers from the XML of a `.tmx` file.
+    ///
+    /// Object layers and image layers are skipped.
+    /// Fails when the XML is invalid, the map is not orthogonal, the map is infinite or a layer is not stored in the CSV format.
+    pub fn from_tmx(xml: &str) -> Result<Self, DeError> {
+        let map = quick_xml::de::from_str::<Tmx>(xml)?;
+
+        let orientation = map.orientation.as_deref().unwrap_or("orthogonal");
+        if orientation != "orthogonal" {
+            return Err(DeError::custom(format!(
+                "unsupported {orientation} map, only orthogonal maps are supported"
+            )));
+        }
+        if map.infinite == Some(1) {
+            return Err(DeError::custom(
+                "unsupported infinite map, only maps with a fixed size are supported",
+            ));
+        }
+
+        let mut tilesets = Vec::new();
+        let mut layers = Vec::new();
+        Self::read_elements(
+            map.elements,
+            Coordinate::new(0, 0),
+            true,
+            &mut tilesets,
+            &mut layers,
+        )?;
+        tilesets.sort_by_key(|tileset: &Tileset| tileset.first_gid);
+
+        Ok(Self {
+            size: Size::new(map.width, map.height),
+            tile_size: Size::new(map.tile_width, map.tile_height),
+            tilesets,
+            layers,
+        })
+    }
+
+    /// Amount of tiles horizontally and vertically.
+    pub fn size(&self) -> Size {
+        self.size
+    }
+
+    /// Size of a single grid cell in pixels.
+    pub fn tile_size(&self) -> Size {
+        self.tile_size
+    }
+
+    /// All tilesets ordered by their first global tile ID.
+    pub fn tilesets(&self) -> &[Tileset] {
+        &self.tilesets
+    }
+
+    /// Replace a tileset, usually one that's stored in a separate `.tsx` file.
+    ///
+    /// The first global tile ID of the replaced tileset is kept, because it's only stored in the map.
+    ///
+    /// # Panics
+    ///
+    /// When the index is out of bounds.
+    pub fn set_tileset(&mut self, index: usize, tileset: Tileset) {
+        let existing = &mut self.tilesets[index];
+        *existing = Tileset {
+            first_gid: existing.first_gid,
+            source: existing.source.take(),
+            ..tileset
+        };
+    }
+
+    /// All tile layers in the order they are drawn.
+    pub fn layers(&self) -> &[TileLayer] {
+        &self.layers
+    }
+
+    /// First tile layer with the name.
+    pub fn layer(&self, name: &str) -> Option<&TileLayer> {
+        self.layers.iter().find(|layer| layer.name == name)
+    }
+
+    /// Find the tileset of a global tile ID and read the flip flags.
+    ///
+    /// Returns `None` for empty cells, which have an ID of zero, and for IDs before the first tileset.
+    pub fn tile(&self, gid: u32) -> Option<Tile> {
+        let id = gid & !(FLIP_HORIZONTAL | FLIP_VERTICAL | FLIP_DIAGONAL | ROTATE_HEXAGONAL);
+        if id == 0 {
+            return None;
+        }
+
+        // The tileset with the highest first ID that's not bigger than the ID
+        let tileset = self
+            .tilesets
+            .partition_point(|tileset| tileset.first_gid <= id)
+            .checked_sub(1)?;
+
+        Some(Tile {
+            tileset,
+            id: id - self.tilesets[tileset].first_gid,
+            flip_horizontal: gid & FLIP_HORIZONTAL != 0,
+            flip_vertical: gid & FLIP_VERTICAL != 0,
+            flip_diagonal: gid & FLIP_DIAGONAL != 0,
+        })
+    }
+
+    /// Draw all tiles of a layer.
+    ///
+    /// The buffers are the images of the tilesets, in the same order as [`Self::tilesets`], tiles of a tileset without a buffer are skipped.
+    /// Tiles bigger than the grid are aligned at the bottom left of their cell, like Tiled does.
+    /// The layer is drawn at the position of the options and clipped by the mask of the options, the same as the parent of [`BlitOptions::compose`].
+    pub fn blit_layer(
+        &self,
+        layer: &TileLayer,
+        tilesets: &[BlitBuffer],
+        dst: &mut [u32],
+        dst_size: Size,
+        options: &BlitOptions,
+    ) {
+        let width = layer.size.width as usize;
+        if width == 0 {
+            return;
+        }
+
+        for (index, gid) in layer.gids.iter().enumerate() {
+            let Some(tile) = self.tile(*gid) else {
+                continue;
+            };
+            let (Some(tileset), Some(buffer)) =
+                (self.tilesets.get(tile.tileset), tilesets.get(tile.tileset))
+            else {
+                continue;
+            };
+
+            let (x, y) = ((index % width) as i32, (index / width) as i32);
+            let position = Coordinate::new(
+                x.saturating_mul(self.tile_size.width as i32),
+                (y + 1)
+                    .saturating_mul(self.tile_size.height as i32)
+                    .saturating_sub(tileset.tile_size.height as i32),
+            ) + layer.offset;
+
+            buffer.blit(
+                dst,
+                dst_size,
+                &BlitOptions::compose(options, &tile.options(tileset).offset_by(position)),
+            );
+        }
+    }
+
+    /// Collect the tilesets and the tile layers, also from groups.
+    fn read_elements(
+        elements: Vec<TmxElement>,
+        offset: Coordinate,
+        visible: bool,
+        tilesets: &mut Vec<Tileset>,
+        layers: &mut Vec<TileLayer>,
+    ) -> Result<(), DeError> {
+        for element in elements {
+            match element {
+                TmxElement::Tileset(tileset) => tilesets.push(tileset.into()),
+                TmxElement::Layer(layer) => {
+                    let size = Size::new(layer.width, layer.height);
+
+                    let data = layer.data.unwrap_or_default();
+                    if data.encoding.as_deref() != Some("csv") {
+                        return Err(DeError::custom(format!(
+                            "unsupported encoding of layer {}, only the CSV layer format is supported",
+                            layer.name
+                        )));
+                    }
+                    let gids = data
+                        .text
+                        .split(',')
+                        .map(str::trim)
+                        .filter(|gid| !gid.is_empty())
+                        .map(|gid| gid.parse::<u32>().map_err(DeError::custom))
+                        .collect::<Result<Vec<_>, _>>()?;
+                    if gids.len() != size.pixels() {
+                        return Err(DeError::custom(format!(
+                            "layer {} has {} tiles instead of {}",
+                            layer.name,
+                            gids.len(),
+                            size.pixels()
+                        )));
+                    }
+
+                    layers.push(TileLayer {
+                        name: layer.name,
+                        size,
+                        visible: visible && layer.visible != Some(0),
+                        offset: offset + Coordinate::new(layer.offset_x, layer.offset_y),
+                        gids,
+                    });
+                }
+                TmxElement::Group(group) => Self::read_elements(
+                    group.elements,
+                    offset + Coordinate::new(group.offset_x, group.offset_y),
+                    visible && group.visible != Some(0),
+                    tilesets,
+                    layers,
+                )?,
+                TmxElement::Other => (),
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Image divided into tiles of the same size.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Tileset {
+    /// Global tile ID of the first tile, the IDs of the tiles in all layers of a map are offset by this.
+    pub first_gid: u32,
+    /// Name of the tileset.
+    pub name: String,
+    /// Size of a single tile in pixels.
+    pub tile_size: Size,
+    /// Amount of tiles in a row of the image.
+    pub columns: u32,
+    /// Amount of tiles in the image.
+    pub tile_count: u32,
+    /// Space in pixels between the tiles.
+    pub spacing: u32,
+    /// Space in pixels around the tiles at the edges of the image.
+    pub margin: u32,
+    /// Path of the image relative to the file of the tileset, `None` for tilesets of separate images which are not supported.
+    pub image: Option<String>,
+    /// Path of the `.tsx` file when the tileset is not embedded in the map.
+    ///
+    /// Until the tileset is loaded with [`TiledMap::set_tileset`] only the first global tile ID is known.
+    pub source: Option<String>,
+}
+
+impl Tileset {
+    /// Read a tileset from the XML of a separate `.tsx` file.
+    ///
+    /// The first global tile ID is only stored in the map, it's zero until the tileset is put in a map with [`TiledMap::set_tileset`].
+    /// Fails when the XML is invalid.
+    pub fn from_tsx(xml: &str) -> Result<Self, DeError> {
+        let tileset = quick_xml::de::from_str::<TmxTileset>(xml)?;
+
+        Ok(Tileset {
+            first_gid: 0,
+            ..tileset.into()
+        })
+    }
+
+    /// Rectangle in the image of the tile with the local ID, which is the global tile ID minus the first global tile ID.
+    pub fn sub_rect(&self, id: u32) -> SubRect {
+        let columns = self.columns.max(1);
+        let (column, row) = (id % columns, id / columns);
+
+        SubRect::new(
+            self.margin + column * (self.tile_size.width + self.spacing),
+            self.margin + row * (self.tile_size.height + self.spacing),
+            self.tile_size,
+        )
+    }
+}
+
+/// Tile in a layer with its tileset and the flip flags from the global tile ID.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Tile {
+    /// Index of the tileset in [`TiledMap::tilesets`].
+    pub tileset: usize,
+    /// Local ID of the tile in the tileset.
+    pub id: u32,
+    /// Whether the tile is mirrored around the vertical axis.
+    pub flip_horizontal: bool,
+    /// Whether the tile is mirrored around the horizontal axis.
+    pub flip_vertical: bool,
+    /// Whether the tile is mirrored diagonally, swapping the x and y axis.
+    ///
+    /// Tiled applies this before the other flips, together with them it describes the quarter turn rotations.
+    pub flip_diagonal: bool,
+}
+
+impl Tile {
+    /// Options drawing the tile from the tileset image at `(0, 0)`, flipped and rotated like the flags describe.
+    pub fn options(&self, tileset: &Tileset) -> BlitOptions {
+        // Mirroring diagonally is the same as rotating clockwise followed by mirroring horizontally
+        let (rotation, flip_horizontal) = if self.flip_diagonal {
+            (Rotation::Clockwise90, !self.flip_horizontal)
+        } else {
+            (Rotation::None, self.flip_horizontal)
+        };
+
+        // The rotation is set first so the area of the sub rectangle is rotated too
+        BlitOptions::new()
+            .with_rotation(rotation)
+            .with_sub_rect(tileset.sub_rect(self.id))
+            .with_flip_horizontal(flip_horizontal)
+            .with_flip_vertical(self.flip_vertical)
+    }
+}
+
+/// Grid of global tile IDs.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TileLayer {
+    /// Name of the layer.
+    pub name: String,
+    /// Amount of tiles horizontally and vertically.
+    pub size: Size,
+    /// Whether the layer and all groups it's in are visible in the editor.
+    pub visible: bool,
+    /// Offset in pixels of the layer and all groups it's in.
+    pub offset: Coordinate,
+    /// Global tile IDs row by row, with the flip flags in the highest bits.
+    gids: Vec<u32>,
+}
+
+impl TileLayer {
+    /// Global tile IDs row by row, with the flip flags in the highest bits, see [`TiledMap::tile`].
+    pub fn gids(&self) -> &[u32] {
+        &self.gids
+    }
+
+    /// Global tile ID at the grid position, `None` when it's outside of the layer.
+    pub fn gid(&self, x: u32, y: u32) -> Option<u32> {
+        if x >= self.size.width || y >= self.size.height {
+            return None;
+        }
+
+        self.gids
+            .get(y as usize * self.size.width as usize + x as usize)
+            .copied()
+    }
+}
+
+/// Root element of a `.tmx` file.
+#[derive(Deserialize)]
+struct Tmx {
+    #[serde(rename = "@orientation")]
+    orientation: Option<String>,
+    #[serde(rename = "@infinite")]
+    infinite: Option<u8>,
+    #[serde(rename = "@width")]
+    width: u32,
+    #[serde(rename = "@height")]
+    height: u32,
+    #[serde(rename = "@tilewidth")]
+    tile_width: u32,
+    #[serde(rename = "@tileheight")]
+    tile_height: u32,
+    #[serde(rename = "$value", default)]
+    elements: Vec<TmxElement>,
+}
+
+/// Child element of a map or a group, in the order they are defined.
+#[derive(Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum TmxElement {
+    Tileset(TmxTileset),
+    Layer(TmxLayer),
+    Group(TmxGroup),
+    #[serde(other)]
+    Other,
+}
+
+/// Tileset element, embedded in a map or the root of a `.tsx` file.
+#[derive(Deserialize)]
+struct TmxTileset {
+    #[serde(rename = "@firstgid", default)]
+    first_gid: u32,
+    #[serde(rename = "@source")]
+    source: Option<String>,
+    #[serde(rename = "@name", default)]
+    name: String,
+    #[serde(rename = "@tilewidth", default)]
+    tile_width: u32,
+    #[serde(rename = "@tileheight", default)]
+    tile_height: u32,
+    #[serde(rename = "@spacing", default)]
+    spacing: u32,
+    #[serde(rename = "@margin", default)]
+    margin: u32,
+    #[serde(rename = "@tilecount", default)]
+    tile_count: u32,
+    #[serde(rename = "@columns", default)]
+    columns: u32,
+    image: Option<TmxImage>,
+}
+
+impl From<TmxTileset> for Tileset {
+    fn from(tileset: TmxTileset) -> Self {
+        Self {
+            first_gid: tileset.first_gid,
+            name: tileset.name,
+            tile_size: Size::new(tileset.tile_width, tileset.tile_height),
+            columns: tileset.columns,
+            tile_count: tileset.tile_count,
+            spacing: tileset.spacing,
+            margin: tileset.margin,
+            image: tileset.image.map(|image| image.source),
+            source: tileset.source,
+        }
+    }
+}
+
+/// Image element of a tileset.
+#[derive(Deserialize)]
+struct TmxImage {
+    #[serde(rename = "@source")]
+    source: String,
+}
+
+/// Tile layer element.
+#[derive(Deserialize)]
+struct TmxLayer {
+    #[serde(rename = "@name", default)]
+    name: String,
+    #[serde(rename = "@width")]
+    width: u32,
+    #[serde(rename = "@height")]
+    height: u32,
+    #[serde(rename = "@visible")]
+    visible: Option<u8>,
+    #[serde(rename = "@offsetx", default)]
+    offset_x: f32,
+    #[serde(rename = "@offsety", default)]
+    offset_y: f32,
+    data: Option<TmxData>,
+}
+
+/// Encoded global tile IDs of a layer.
+#[derive(Default, Deserialize)]
+struct TmxData {
+    #[serde(rename = "@encoding")]
+    encoding: Option<String>,
+    #[serde(rename = "$text", default)]
+    text: String,
+}
+
+/// Group of layers, moving and hiding all layers in it.
+#[derive(Deserialize)]
+struct TmxGroup {
+    #[serde(rename = "@visible")]
+    visible: Option<u8>,
+    #[serde(rename = "@offsetx", default)]
+    offset_x: f32,
+    #[serde(rename = "@offsety", default)]
+    offset_y: f32,
+    #[serde(rename = "$value", default)]
+    elements: Vec<TmxElement>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn load() {
+        let xml = r#"<?xml version="1.0" encoding="UTF-8"?>
+<map version="1.10" orientation="orthogonal" renderorder="right-down" width="3" height="2" tilewidth="2" tileheight="2" infinite="0" nextlayerid="4" nextobjectid="1">
+ <tileset firstgid="1" name="terrain" tilewidth="2" tileheight="2" spacing="1" margin="1" tilecount="4" columns="2">
+  <image source="terrain.png" width="6" height="6"/>
+  <tile id="0"><properties><property name="solid" type="bool" value="true"/></properties></tile>
+ </tileset>
+ <tileset firstgid="5" source="props.tsx"/>
+ <layer id="1" name="ground" width="3" height="2">
+  <properties><property name="depth" value="0"/></properties>
+  <data encoding="csv">
+1,2,0,
+4,3,5
+</data>
+ </layer>
+ <objectgroup id="2" name="spawns"><object id="1" x="0" y="0"/></objectgroup>
+ <group id="3" name="top" offsetx="1" offsety="-2" visible="0">
+  <layer id="4" name="decoration" width="3" height="2" offsetx="2.5">
+   <data encoding="csv">0,0,0,0,2147483649,0</data>
+  </layer>
+ </group>
+</map>"#;
+        let mut map = TiledMap::from_tmx(xml).unwrap();
+
+        assert_eq!(map.size(), Size::new(3, 2));
+        assert_eq!(map.tile_size(), Size::new(2, 2));
+        assert_eq!(map.tilesets().len(), 2);
+        assert_eq!(map.tilesets()[0].image.as_deref(), Some("terrain.png"));
+        assert_eq!(map.tilesets()[1].source.as_deref(), Some("props.tsx"));
+
+        // Tiles are offset by the margin and the spacing
+        assert_eq!(map.tilesets()[0].sub_rect(3), SubRect::new(4, 4, (2, 2)));
+
+        assert_eq!(
+            map.layers()
+                .iter()
+                .map(|layer| layer.name.as_str())
+                .collect::<Vec<_>>(),
+            ["ground", "decoration"]
+        );
+        let ground = map.layer("ground").unwrap();
+        assert_eq!(ground.gids(), [1, 2, 0, 4, 3, 5]);
+        assert_eq!(ground.gid(2, 1), Some(5));
+        assert_eq!(ground.gid(3, 0), None);
+        assert!(ground.visible);
+
+        // Groups move and hide their layers
+        let decoration = map.layer("decoration").unwrap();
+        assert_eq!(decoration.offset, Coordinate::new(3, -2));
+        assert!(!decoration.visible);
+        assert_eq!(
+            map.tile(decoration.gids()[4]),
+            Some(Tile {
+                tileset: 0,
+                id: 0,
+                flip_horizontal: true,
+                flip_vertical: false,
+                flip_diagonal: false,
+            })
+        );
+
+        // The tileset from a separate file keeps the first ID from the map
+        let props = Tileset::from_tsx(
+            r#"<tileset version="1.10" name="props" tilewidth="2" tileheight="4" tilecount="1" columns="1">
+ <image source="props.png" width="2" height="4"/>
+</tileset>"#,
+        )
+        .unwrap();
+        map.set_tileset(1, props);
+        assert_eq!(map.tilesets()[1].first_gid, 5);
+        assert_eq!(map.tilesets()[1].tile_size, Size::new(2, 4));
+        assert_eq!(
+            map.tile(5).map(|tile| (tile.tileset, tile.id)),
+            Some((1, 0))
+        );
+        assert_eq!(map.tile(0), None);
+
+        // Tall tiles are aligned at the bottom of their cell
+        let terrain = BlitBuffer::from_buffer(&[0xFF_00_00_01; 6 * 6], 6, 127);
+        let props = BlitBuffer::from_buffer(&[0xFF_00_00_02; 2 * 4], 2, 127);
+        let mut canvas = [0; 6 * 6];
+        map.blit_layer(
+            map.layer("ground").unwrap(),
+            &[terrain, props],
+            &mut canvas,
+            Size::new(6, 6),
+            &BlitOptions::new_position(0, 2),
+        );
+        let column = |x: usize| (0..6).map(|y| canvas[y * 6 + x] & 0xFF).collect::<Vec<_>>();
+        assert_eq!(column(0), [0, 0, 1, 1, 1, 1]);
+        assert_eq!(column(4), [0, 0, 2, 2, 2, 2]);
+
+        // Unsupported maps are an error
+        for invalid in [
+            xml.replace("orthogonal", "isometric"),
+            xml.replace(r#"infinite="0""#, r#"infinite="1""#),
+            xml.replace(r#"encoding="csv""#, r#"encoding="base64""#),
+            xml.replace("4,3,5", "4,3"),
+            xml.replace("4,3,5", "4,3,x"),
+        ] {
+            assert!(TiledMap::from_tmx(&invalid).is_err(), "{invalid}");
+        }
+    }
+
+    #[test]
+    fn flips() {
+        // Every pixel of the 2x3 tile has a unique color
+        let source = (1..=6).map(|i| 0xFF_00_00_00 | i).collect::<Vec<_>>();
+        let tileset = Tileset {
+            first_gid: 1,
+            name: String::new(),
+            tile_size: Size::new(2, 3),
+            columns: 1,
+            tile_count: 1,
+            spacing: 0,
+            margin: 0,
+            image: None,
+            source: None,
+        };
+        let buffer = BlitBuffer::from_buffer(&source, 2, 127);
+
+        for flags in 0..8 {
+            let gid = 1 | flags << 29;
+            let map = TiledMap {
+                size: Size::new(1, 1),
+                tile_size: Size::new(2, 3),
+                tilesets: vec![tileset.clone()],
+                layers: Vec::new(),
+            };
+            let tile = map.tile(gid).unwrap();
+
+            // Tiled first swaps the axes, then mirrors horizontally and then vertically
+            let (width, height) = if tile.flip_diagonal { (3, 2) } else { (2, 3) };
+            let mut expected = vec![0; width * height];
+            for y in 0..height {
+                for x in 0..width {
+                    let (mut source_x, mut source_y) = (x, y);
+                    if tile.flip_horizontal {
+                        source_x = width - 1 - source_x;
+                    }
+                    if tile.flip_vertical {
+                        source_y = height - 1 - source_y;
+                    }
+                    if tile.flip_diagonal {
+                        (source_x, source_y) = (source_y, source_x);
+                    }
+                    expected[y * width + x] = source[source_y * 2 + source_x];
+                }
+            }
+
+            let mut result = vec![0; width * height];
+            buffer.blit(
+                &mut result,
+                Size::new(width as u32, height as u32),
+                &tile.options(&tileset),
+            );
+            assert_eq!(result, expected, "{tile:?}");
+        }
+    }
+}
@@ -1,4 +1,4 @@
-use std::ops::Range;
+use std::{iter::StepBy, ops::Range};
 
 use num_traits::ToPrimitive;
 
@@ -42,8 +42,8 @@ impl ImageView {
 
         // Clip the width and height to right and bottom side if applicable
         let (actual_width, actual_height) = (
-            (target.width() as i32 - subtract_x).min(parent_right - target.x),
-            (target.height() as i32 - subtract_y).min(parent_bottom - target.y),
+            (target.width() as i32 - subtract_x).min(parent_right - x),
+            (target.height() as i32 - subtract_y).min(parent_bottom - y),
         );
 
         Some(Self::new_unchecked(
@@ -71,7 +71,10 @@ impl ImageView {
     ///
     /// Each range represents a slice of bytes that can be taken.
     /// Bounds checks should have already been done by the new function.
-    pub fn parent_ranges_iter(&self, parent_size: Size) -> impl Iterator<Item = Range<usize>> {
+    pub fn parent_ranges_iter(
+        &self,
+        parent_size: Size,
+    ) -> impl DoubleEndedIterator<Item = Range<usize>> {
         let (width, height) = (self.0.width() as usize, self.0.height() as usize);
         let (start_x, start_y) = (self.0.x as usize, self.0.y as usize);
         let end_y = start_y + height;
@@ -86,6 +89,28 @@ impl ImageView {
         })
     }
 
+    /// Iterator over vertical columns in the buffer the view is based on.
+    ///
+    /// Each column is a range of indices from top to bottom, stepping over the width of the parent.
+    /// Bounds checks should have already been done by the new function.
+    pub fn parent_columns_iter(
+        &self,
+        parent_size: Size,
+    ) -> impl DoubleEndedIterator<Item = StepBy<Range<usize>>> {
+        let (width, height) = (self.0.width() as usize, self.0.height() as usize);
+        let (start_x, start_y) = (self.0.x as usize, self.0.y as usize);
+        let end_x = start_x + width;
+
+        let parent_width = parent_size.width as usize;
+
+        (start_x..end_x).map(move |x| {
+            let start = start_y * parent_width + x;
+            let end = start + height * parent_width;
+
+            (start..end).step_by(parent_width)
+        })
+    }
+
     /// Size in pixels.
     pub fn size(&self) -> Size {
         self.0.size
@@ -101,18 +126,8 @@ impl ImageView {
 
     /// Create a sub rectangle where the position can be negative.
     pub fn sub_i32(&self, x: i32, y: i32, size: Size) -> Option<Self> {
-        // Don't allow the X and Y to be smaller than 0
-        let (clip_x, clip_y) = (x.max(0), y.max(0));
-        // Calculate how much pixels got clipped
-        let (offset_x, offset_y) = (clip_x - x, clip_y - y);
-
-        // Remove the clipped pixels from the size
-        let (new_width, new_height) = (
-            (size.width as i32 - offset_x).max(0),
-            (size.height as i32 - offset_y).max(0),
-        );
-
-        Self::new((x, y, new_width, new_height), self.as_sub_rect())
+        // Clipping the negative part is handled by the constructor
+        Self::new(SubRect { x, y, size }, self.as_sub_rect())
     }
 
     /// Clip the view to fit in another one.
@@ -120,35 +135,7 @@ impl ImageView {
     where
         R: Into<SubRect>,
     {
-        let other = other.into();
-        let mut new = self.clone();
-
-        let (right, bottom) = (self.0.right(), self.0.bottom());
-
-        // Clip the left edge
-        if self.0.x < other.x {
-            new.0.x = other.x;
-            let width = (right - new.0.x).max(0);
-            new.0.size.width = width as u32;
-        }
-        // Clip the top edge
-        if self.0.y < other.y {
-            new.0.y = other.y;
-            let height = (bottom - new.0.y).max(0);
-            new.0.size.height = height as u32;
-        }
-        // Clip the right edge
-        if right > other.right() {
-            let width = (other.right() - new.0.x).max(0);
-            new.0.size.width = width as u32;
-        }
-        // Clip the bottom edge
-        if bottom > other.bottom() {
-            let height = (other.bottom() - new.0.y).max(0);
-            new.0.size.height = height as u32;
-        }
-
-        new
+        Self(self.0.clamped_to(other.into()))
     }
 
     /// Get the amount of X pixels.
@@ -172,12 +159,59 @@ impl ImageView {
     }
 }
 
-impl Into<SubRect> for ImageView {
-    fn into(self) -> SubRect {
-        self.0
+impl From<ImageView> for SubRect {
+    fn from(view: ImageView) -> Self {
+        view.0
     }
 }
 
+/// Pixels in a range that's known to be inside of the slice, such as the ranges of a clipped [`ImageView`].
+///
+/// The callers of this function, [`range_mut`] and [`pixel`] must uphold the same invariant: every range and index is derived from an [`ImageView`] clipped to the size of the slice.
+/// Source views are clipped to the size of the source data, destination views are clipped to the destination size and the blits assert that the destination holds all pixels of its size when the `unsafe-fast` feature is enabled.
+///
+/// The invariant is checked by a debug assertion, with the `unsafe-fast` feature the bounds are not checked again in release builds.
+#[inline(always)]
+pub(crate) fn range<T>(slice: &[T], range: Range<usize>) -> &[T] {
+    debug_assert!(range.start <= range.end && range.end <= slice.len());
+
+    #[cfg(feature = "unsafe-fast")]
+    // SAFETY: `range.start <= range.end <= slice.len()` by the invariant of this function
+    unsafe {
+        slice.get_unchecked(range)
+    }
+    #[cfg(not(feature = "unsafe-fast"))]
+    &slice[range]
+}
+
+/// Mutable pixels in a range that's known to be inside of the slice, see [`range`].
+#[inline(always)]
+pub(crate) fn range_mut<T>(slice: &mut [T], range: Range<usize>) -> &mut [T] {
+    debug_assert!(range.start <= range.end && range.end <= slice.len());
+
+    #[cfg(feature = "unsafe-fast")]
+    // SAFETY: `range.start <= range.end <= slice.len()` by the invariant of `range`
+    unsafe {
+        slice.get_unchecked_mut(range)
+    }
+    #[cfg(not(feature = "unsafe-fast"))]
+    &mut slice[range]
+}
+
+/// Pixel at an index that's known to be inside of the slice, see [`range`].
+#[inline(always)]
+pub(crate) fn pixel<T: Copy>(slice: &[T], index: usize) -> T {
+    debug_assert!(index < slice.len());
+
+    #[cfg(feature = "unsafe-fast")]
+    // SAFETY: `index < slice.len()` by the invariant of `range`
+    unsafe {
+        *slice.get_unchecked(index)
+    }
+    #[cfg(not(feature = "unsafe-fast"))]
+    slice[index]
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -217,6 +251,16 @@ mod tests {
             ImageView::new((0, 95, 10, 10), (0, 0, 100, 100)),
             Some(ImageView::new_unchecked(0, 95, Size::new(10, 5)))
         );
+        // Clip both sides of a parent not at the origin
+        assert_eq!(
+            ImageView::new((5, 5, 200, 200), (10, 10, 100, 100)),
+            Some(ImageView::new_unchecked(10, 10, Size::new(100, 100)))
+        );
+        // Negative position
+        assert_eq!(
+            ImageView::full(Size::new(100, 100)).sub_i32(-5, -8, Size::new(10, 10)),
+            Some(ImageView::new_unchecked(0, 0, Size::new(5, 2)))
+        );
     }
 
     #[test]
@@ -249,4 +293,15 @@ mod tests {
             vec![1010..1020, 1110..1120, 1210..1220]
         );
     }
+
+    #[test]
+    fn parent_columns() {
+        assert_eq!(
+            ImageView::new_unchecked(1, 2, Size::new(2, 3))
+                .parent_columns_iter(Size::new(10, 10))
+                .map(|column| column.collect::<Vec<_>>())
+                .collect::<Vec<_>>(),
+            vec![vec![21, 31, 41], vec![22, 32, 42]]
+        );
+    }
 }
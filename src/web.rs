@@ -0,0 +1,60 @@
+//! Convert between pixels and the `ImageData` of a 2D canvas in the browser.
+//!
+//! The pixels of an `ImageData` are bytes in RGBA order, which are converted from and to the `0xAARRGGBB` pixels used by this crate.
+//! A composited frame can be drawn on a canvas with `CanvasRenderingContext2d::put_image_data`.
+//!
+//! Creating and reading `ImageData` only works when running in a browser on `wasm32`.
+
+use wasm_bindgen::{Clamped, JsValue};
+use web_sys::ImageData;
+
+use crate::{rgba_bytes, rgba_pixels, BlitBuffer, Size};
+
+/// Create image data from pixels, such as a destination buffer after drawing on it.
+///
+/// Fails when the amount of pixels doesn't match the size.
+pub fn to_image_data(pixels: &[u32], size: Size) -> Result<ImageData, JsValue> {
+    ImageData::new_with_u8_clamped_array_and_sh(
+        Clamped(&to_rgba_bytes(pixels)),
+        size.width,
+        size.height,
+    )
+}
+
+/// Create a buffer from image data, for example read from a canvas with `CanvasRenderingContext2d::get_image_data`.
+///
+/// The alpha channel is used as is, see [`BlitBuffer::from_buffer_with_alpha`].
+pub fn from_image_data(image_data: &ImageData) -> BlitBuffer {
+    BlitBuffer::from_buffer_with_alpha(&from_rgba_bytes(&image_data.data()), image_data.width())
+}
+
+/// Convert `0xAARRGGBB` pixels to bytes in RGBA order.
+///
+/// ```rust
+/// assert_eq!(blit::web::to_rgba_bytes(&[0xFF_11_22_33]), [0x11, 0x22, 0x33, 0xFF]);
+/// ```
+pub fn to_rgba_bytes(pixels: &[u32]) -> Vec<u8> {
+    pixels.iter().flat_map(|pixel| rgba_bytes(*pixel)).collect()
+}
+
+/// Convert bytes in RGBA order to `0xAARRGGBB` pixels, trailing bytes that don't form a whole pixel are ignored.
+pub fn from_rgba_bytes(bytes: &[u8]) -> Vec<u32> {
+    rgba_pixels(bytes).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rgba_bytes() {
+        let pixels = [0xFF_11_22_33, 0x80_00_FF_01, 0];
+        let bytes = to_rgba_bytes(&pixels);
+        assert_eq!(
+            bytes,
+            [0x11, 0x22, 0x33, 0xFF, 0x00, 0xFF, 0x01, 0x80, 0, 0, 0, 0]
+        );
+        assert_eq!(from_rgba_bytes(&bytes), pixels);
+        assert_eq!(from_rgba_bytes(&bytes[..7]), pixels[..1]);
+    }
+}